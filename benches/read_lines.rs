@@ -1,6 +1,6 @@
 use {
     criterion::{black_box, criterion_group, criterion_main, Criterion},
-    simple_lines::ReadExt,
+    simple_lines::{BufReadExt, ReadExt},
     std::io::{BufRead, Cursor},
 };
 
@@ -11,20 +11,40 @@ fn compare_bufread_lines(c: &mut Criterion) {
         b.iter(|| {
             assert_eq!(
                 33532728,
-                Cursor::new(black_box(input.clone()))
-                    .lines_rc()
+                ReadExt::lines_rc(Cursor::new(black_box(input.clone())))
                     .filter_map(Result::ok)
                     .fold(0, |acc, n| acc + n.len())
             )
         })
     });
+    c.bench_function("simple_lines::RcLineIterator::fold_str()", |b| {
+        b.iter(|| {
+            assert_eq!(
+                33532728,
+                ReadExt::lines_rc(Cursor::new(black_box(input.clone())))
+                    .fold_str(0, |acc, n| acc + n.map_or(0, str::len))
+            )
+        })
+    });
+    c.bench_function("simple_lines::BufReadExt::lines_rc()", |b| {
+        b.iter(|| {
+            assert_eq!(
+                33532728,
+                BufReadExt::lines_rc(std::io::BufReader::new(Cursor::new(black_box(
+                    input.clone()
+                ))))
+                .filter_map(Result::ok)
+                .fold(0, |acc, n| acc + n.len())
+            )
+        })
+    });
     c.bench_function("std::BufReader::lines()", |b| {
         b.iter(|| {
             assert_eq!(
                 33532728,
                 std::io::BufReader::new(Cursor::new(black_box(input.clone())))
                     .lines()
-                    .filter_map(Result::ok)
+                    .map_while(Result::ok)
                     .fold(0, |acc, n| acc + n.len())
             )
         })
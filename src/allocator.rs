@@ -0,0 +1,130 @@
+use {
+    crate::{bound::BorrowedLines, source::LineSource},
+    allocator_api2::{alloc::Allocator, vec::Vec},
+    std::{fmt, ops::Deref, rc::Rc},
+};
+
+/// Iterator over the lines of a reader, whose buffers are allocated by a custom allocator.
+/// Created by `RcLineIterator::into_allocator()`.
+///
+/// Like `RcLineIterator`, the returned line is reused if the consumer doesn't keep a reference to it.
+pub struct AllocLineIterator<TSource: LineSource, TAlloc: Allocator + Clone> {
+    lines: BorrowedLines<TSource>,
+    buffer: Rc<AllocLine<TAlloc>>,
+}
+
+impl<T: LineSource, A: Allocator + Clone> AllocLineIterator<T, A> {
+    pub(crate) fn new(lines: BorrowedLines<T>, alloc: A) -> Self {
+        Self {
+            lines,
+            buffer: Rc::new(AllocLine(Vec::new_in(alloc))),
+        }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource, A: Allocator + Clone> std::iter::FusedIterator for AllocLineIterator<T, A> {}
+
+impl<T: LineSource, A: Allocator + Clone> Iterator for AllocLineIterator<T, A> {
+    type Item = Result<Rc<AllocLine<A>>, crate::Error<Rc<AllocLine<A>>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = &mut self.buffer;
+        let mut share = |line: &str| {
+            let owned = if let Some(r) = Rc::get_mut(buffer) {
+                r.0.clear();
+                r
+            } else {
+                let alloc = buffer.0.allocator().clone();
+                *buffer = Rc::new(AllocLine(Vec::with_capacity_in(line.len(), alloc)));
+                Rc::get_mut(buffer).unwrap()
+            };
+            owned.0.extend_from_slice(line.as_bytes());
+            buffer.clone()
+        };
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(share(x)),
+            Err(e) => Err(e.map_incomplete(share)),
+        })
+    }
+
+    /// Skips lines without validating or copying them
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating or copying them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+}
+
+/// Line whose content is allocated by `TAlloc`
+pub struct AllocLine<TAlloc: Allocator>(Vec<u8, TAlloc>);
+
+impl<A: Allocator> Deref for AllocLine<A> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        // SAFETY: The content is only ever written from a valid &str in `AllocLineIterator::next()`
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+impl<A: Allocator> fmt::Debug for AllocLine<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<A: Allocator> fmt::Display for AllocLine<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::ReadExt,
+        allocator_api2::alloc::{AllocError, Allocator, Global, Layout},
+        std::{cell::Cell, ptr::NonNull},
+    };
+
+    #[derive(Default)]
+    struct CountingAllocator {
+        allocations: Cell<usize>,
+    }
+
+    unsafe impl Allocator for CountingAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.allocations.set(self.allocations.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn allocates_retained_lines_in_allocator() {
+        let alloc = CountingAllocator::default();
+        let mut lines = std::io::Cursor::new("first\nsecond\nthird")
+            .lines_rc()
+            .into_allocator(&alloc);
+        let first = lines.next().unwrap().unwrap();
+        assert_eq!("first", &**first);
+        assert_eq!(1, alloc.allocations.get());
+        for line in lines {
+            assert!(!line.unwrap().is_empty());
+        }
+        assert_eq!(2, alloc.allocations.get());
+    }
+}
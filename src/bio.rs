@@ -0,0 +1,304 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Groups the lines of a FASTA file into records of a `>` header and the sequence lines following it.
+    /// The sequence lines are concatenated without their linebreaks. Blank lines are skipped.
+    ///
+    /// Lines longer than the capacity of this iterator are joined, but a record whose header and sequence
+    /// together are larger than `max_record_size` bytes is rejected before more of it is buffered.
+    /// The iteration ends after the first error, because a malformed file can't be trusted to continue.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let fasta = ">seq1 description\nACGT\nAC\n>seq2\nTT\n";
+    /// let records = std::io::Cursor::new(fasta).lines_rc().fasta_records(1024);
+    /// let records = records.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(("seq1 description", "ACGTAC"), (records[0].header.as_str(), records[0].sequence.as_str()));
+    /// assert_eq!(("seq2", "TT"), (records[1].header.as_str(), records[1].sequence.as_str()));
+    /// ```
+    pub fn fasta_records(self, max_record_size: usize) -> FastaRecords<T> {
+        FastaRecords {
+            lines: RecordLines::new(self, max_record_size),
+            next_header: None,
+            finished: false,
+        }
+    }
+
+    /// Groups the lines of a FASTQ file into records of four lines: the `@` header, the sequence,
+    /// the `+` separator and the quality, which must be as long as the sequence. Blank lines between records are skipped.
+    ///
+    /// Records larger than `max_record_size` bytes are rejected like in `fasta_records()`, which also ends the iteration.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ReadExt, SequenceError};
+    ///
+    /// let fastq = "@read1\nACGT\n+\nIIII\n@read2\nAC\n+\nI\n";
+    /// let mut records = std::io::Cursor::new(fastq).lines_rc().fastq_records(1024);
+    /// let record = records.next().unwrap().unwrap();
+    /// assert_eq!(("read1", "ACGT", "IIII"), (record.header.as_str(), record.sequence.as_str(), record.quality.as_str()));
+    /// assert!(matches!(records.next(), Some(Err(SequenceError::Invalid { line: 7 }))));
+    /// assert!(records.next().is_none());
+    /// ```
+    pub fn fastq_records(self, max_record_size: usize) -> FastqRecords<T> {
+        FastqRecords {
+            lines: RecordLines::new(self, max_record_size),
+            finished: false,
+        }
+    }
+}
+
+/// Record of a FASTA file, created by `FastaRecords`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastaRecord {
+    /// Header line without the leading `>`
+    pub header: String,
+    /// Concatenated sequence lines
+    pub sequence: String,
+}
+
+/// Record of a FASTQ file, created by `FastqRecords`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastqRecord {
+    /// Header line without the leading `@`
+    pub header: String,
+    /// Sequence line
+    pub sequence: String,
+    /// Quality line, which has one character per character of the sequence
+    pub quality: String,
+}
+
+/// Error of `FastaRecords` and `FastqRecords`. Line numbers are zero-based.
+#[derive(thiserror::Error, Debug)]
+pub enum SequenceError {
+    /// The line couldn't be read
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The record starting in `line` is larger than allowed
+    #[error("record in line {line} too large")]
+    TooLarge {
+        /// Number of the header line of the record
+        line: u64,
+    },
+    /// A line doesn't fit the format, like a missing header or a quality of the wrong length
+    #[error("invalid record in line {line}")]
+    Invalid {
+        /// Number of the offending line
+        line: u64,
+    },
+}
+
+/// Reads whole lines by joining their `Incomplete` parts
+struct RecordLines<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_record_size: usize,
+    /// Number of the next line
+    number: u64,
+}
+
+impl<T: LineSource> RecordLines<T> {
+    fn new(lines: RcLineIterator<T>, max_record_size: usize) -> Self {
+        Self {
+            lines,
+            max_record_size,
+            number: 0,
+        }
+    }
+
+    /// Returns the next line and its number, or `TooLarge` for the record starting in `record` once it gets longer than `max_size`.
+    /// A line starting with `header` starts a new record instead, which may be as large as `max_record_size`.
+    /// The remaining parts of a line which is too large aren't read.
+    fn read(
+        &mut self,
+        mut max_size: usize,
+        mut record: u64,
+        header: Option<char>,
+    ) -> Option<Result<(u64, String), SequenceError>> {
+        let number = self.number;
+        let mut line = String::new();
+        loop {
+            let part = match self.lines.next()? {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x,
+                Err(e) => return Some(Err(SequenceError::Read(e))),
+            };
+            if line.is_empty() && header.is_some_and(|x| part.starts_with(x)) {
+                max_size = self.max_record_size;
+                record = number;
+            }
+            if line.len() + part.len() > max_size {
+                return Some(Err(SequenceError::TooLarge { line: record }));
+            }
+            line.push_str(&part);
+            if !self.lines.continues_line() {
+                self.number += 1;
+                return Some(Ok((number, line)));
+            }
+        }
+    }
+
+    /// Returns the next line which isn't blank as the start of a new record
+    fn read_header(&mut self, header: char) -> Option<Result<(u64, String), SequenceError>> {
+        loop {
+            match self.read(self.max_record_size, self.number, Some(header)) {
+                Some(Ok((_, x))) if x.trim().is_empty() => continue,
+                x => return x,
+            }
+        }
+    }
+}
+
+/// Iterator over the records of a FASTA file, created by `RcLineIterator::fasta_records()`
+pub struct FastaRecords<T: LineSource> {
+    lines: RecordLines<T>,
+    /// Header of the next record, which was read to end the previous one
+    next_header: Option<(u64, String)>,
+    finished: bool,
+}
+
+impl<T: LineSource> FastaRecords<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.lines.stats()
+    }
+
+    fn read_record(&mut self) -> Option<Result<FastaRecord, SequenceError>> {
+        let (number, header) = match self.next_header.take() {
+            Some(x) => x,
+            None => match self.lines.read_header('>')? {
+                Ok(x) => x,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        let Some(header) = header.strip_prefix('>') else {
+            return Some(Err(SequenceError::Invalid { line: number }));
+        };
+        let mut record = FastaRecord {
+            header: header.to_string(),
+            sequence: String::new(),
+        };
+        let mut size = header.len();
+        let max_size = self.lines.max_record_size;
+        while let Some(line) = self.lines.read(max_size - size, number, Some('>')) {
+            let line = match line {
+                Ok((_, x)) if x.trim().is_empty() => continue,
+                Ok(x) if x.1.starts_with('>') => {
+                    self.next_header = Some(x);
+                    break;
+                }
+                Ok((_, x)) => x,
+                Err(e) => return Some(Err(e)),
+            };
+            size += line.len();
+            record.sequence.push_str(&line);
+        }
+        Some(Ok(record))
+    }
+}
+
+impl<T: LineSource> Iterator for FastaRecords<T> {
+    type Item = Result<FastaRecord, SequenceError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record = self.read_record();
+        self.finished = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+/// Iterator over the records of a FASTQ file, created by `RcLineIterator::fastq_records()`
+pub struct FastqRecords<T: LineSource> {
+    lines: RecordLines<T>,
+    finished: bool,
+}
+
+impl<T: LineSource> FastqRecords<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.lines.stats()
+    }
+
+    fn read_record(&mut self) -> Option<Result<FastqRecord, SequenceError>> {
+        let (number, header) = match self.lines.read_header('@')? {
+            Ok(x) => x,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut size = header.len();
+        let mut lines = Vec::with_capacity(3);
+        while lines.len() < 3 {
+            // Quality lines may start with `@`, so no line of the record is treated as a header
+            let max_size = self.lines.max_record_size - size;
+            let line = match self.lines.read(max_size, number, None) {
+                Some(Ok((_, x))) => x,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    return Some(Err(SequenceError::Invalid {
+                        line: self.lines.number,
+                    }))
+                }
+            };
+            size += line.len();
+            lines.push(line);
+        }
+        let quality = lines.pop().unwrap_or_default();
+        let separator = lines.pop().unwrap_or_default();
+        let sequence = lines.pop().unwrap_or_default();
+        let Some(header) = header.strip_prefix('@') else {
+            return Some(Err(SequenceError::Invalid { line: number }));
+        };
+        if !separator.starts_with('+') {
+            return Some(Err(SequenceError::Invalid { line: number + 2 }));
+        }
+        if quality.len() != sequence.len() {
+            return Some(Err(SequenceError::Invalid { line: number + 3 }));
+        }
+        Some(Ok(FastqRecord {
+            header: header.to_string(),
+            sequence,
+            quality,
+        }))
+    }
+}
+
+impl<T: LineSource> Iterator for FastqRecords<T> {
+    type Item = Result<FastqRecord, SequenceError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record = self.read_record();
+        self.finished = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn rejects_record_before_buffering_it() {
+        let fasta = format!(">a\n{}\n>b\nAC\n", "A".repeat(100));
+        let mut records = std::io::Cursor::new(fasta.clone())
+            .lines_rc_with_capacity(8)
+            .fasta_records(102);
+        assert_eq!(100, records.next().unwrap().unwrap().sequence.len());
+        assert_eq!("AC", records.next().unwrap().unwrap().sequence);
+
+        let mut records = std::io::Cursor::new(fasta)
+            .lines_rc_with_capacity(8)
+            .fasta_records(50);
+        assert!(matches!(
+            records.next(),
+            Some(Err(SequenceError::TooLarge { line: 0 }))
+        ));
+        assert!(records.stats().bytes < 64);
+        assert!(records.next().is_none());
+    }
+}
@@ -1,61 +1,1609 @@
 use {
-    linereader::LineReader,
-    std::{io::Read, rc::Rc},
+    crate::{
+        checkpoint::Checkpoint,
+        index::LineIndex,
+        io,
+        source::{self, LineSource},
+        stats::{ProgressHook, ProgressInterval, Stats},
+        trim::{Trim, Trimming},
+    },
+    alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec::Vec},
+    core::{
+        sync::atomic::{AtomicBool, Ordering},
+        task::Poll,
+        time::Duration,
+    },
 };
 
-pub struct RcLineIterator<TRead: Read> {
-    line_reader: LineReader<TRead>,
+/// Iterator over the lines of a reader, created by `ReadExt::lines_rc()` or `BufReadExt::lines_rc()`.
+///
+/// The returned `Rc<String>` is reused for the next line, if the consumer doesn't keep a reference to it.
+pub struct RcLineIterator<TSource: LineSource> {
+    lines: BorrowedLines<TSource>,
+    buffers: SharedBuffers,
+    /// Value of `stats().bytes` at the end of the source, if its size is known
+    end: Option<u64>,
+}
+
+/// Item of `RcLineIterator`
+pub(crate) type RcLine = Result<Rc<String>, crate::Error<Rc<String>>>;
+
+/// Copies `line`, sharing its content. `Io` errors are recreated from their kind and message.
+#[cfg(feature = "std")]
+fn copy_line(line: &RcLine) -> RcLine {
+    use crate::Error;
+    Err(match line {
+        Ok(x) => return Ok(x.clone()),
+        Err(Error::Io(e)) => Error::Io(io::Error::new(e.kind(), e.to_string())),
+        Err(Error::Encoding(e)) => Error::Encoding(*e),
+        Err(Error::Incomplete(x)) => Error::Incomplete(x.clone()),
+        Err(Error::Cancelled) => Error::Cancelled,
+        Err(Error::TimedOut) => Error::TimedOut,
+        Err(Error::QuotaExceeded) => Error::QuotaExceeded,
+        Err(Error::TooManyLines) => Error::TooManyLines,
+        Err(Error::ForbiddenByte { byte, position }) => Error::ForbiddenByte {
+            byte: *byte,
+            position: *position,
+        },
+        Err(Error::BinaryContent) => Error::BinaryContent,
+        Err(Error::LineTooLong) => Error::LineTooLong,
+    })
+}
+
+/// Validates the lines of a `LineSource` and borrows them from its buffer
+pub(crate) struct BorrowedLines<TSource: LineSource> {
+    source: TSource,
     max_size: usize,
-    buffer: Rc<String>,
     pending_incomplete: bool,
+    current_length: usize,
+    stats: Stats,
+    progress: Option<ProgressHook>,
+    cancellation: Option<Arc<AtomicBool>>,
+    read_timeout: Option<Duration>,
+    quotas: Quotas,
+    forbidden_bytes: Option<Box<[bool; 256]>>,
+    detect_binary: bool,
+    index: Option<LineIndex>,
+    trimming: Option<Trimming>,
+    stop: Option<Stop>,
+    stop_on_io_error: bool,
+    finished: bool,
+    /// Set once `None` was returned, after which lines can't be unread anymore
+    ended: bool,
+    /// Lines which were peeked or unread, returned from the last to the first before reading from `source` again
+    pushed_back: Vec<RcLine>,
+    /// Pushed back line which was returned last by `next_str()`
+    popped: Option<Rc<String>>,
+    #[cfg(feature = "unicode")]
+    pub(crate) graphemes: Option<crate::graphemes::GraphemeJoin>,
+}
+
+impl<T: LineSource> RcLineIterator<T> {
+    pub(crate) fn new(source: T, max_size: usize) -> Self {
+        Self {
+            lines: BorrowedLines::new(source, max_size),
+            buffers: SharedBuffers::default(),
+            end: None,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn into_source(self) -> T {
+        self.lines.source
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn source(&self) -> &T {
+        self.lines.source()
+    }
+
+    /// Returns whether lines which were peeked or unread are pending
+    pub(crate) fn has_pushed_back(&self) -> bool {
+        !self.lines.pushed_back.is_empty()
+    }
+
+    /// Replaces the pending lines with copies of the ones pending in `other`, e.g. for a fork which is positioned after them
+    #[cfg(feature = "std")]
+    pub(crate) fn copy_pushed_back(&mut self, other: &Self) {
+        self.lines.pushed_back = other.lines.pushed_back.iter().map(copy_line).collect();
+    }
+
+    /// Returns the next line as a `String` which is copied once from the reader's buffer, without going through the shared buffers
+    #[cfg(feature = "std")]
+    pub(crate) fn next_owned(&mut self) -> Option<crate::chunks::OwnedLine> {
+        if let Some(line) = self.lines.pushed_back.pop() {
+            return Some(match line {
+                Ok(x) => Ok(Rc::unwrap_or_clone(x)),
+                Err(e) => Err(e.map_incomplete(Rc::unwrap_or_clone)),
+            });
+        }
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(x.to_owned()),
+            Err(e) => Err(e.map_incomplete(str::to_owned)),
+        })
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn into_line_index(self) -> Option<LineIndex> {
+        self.lines.index
+    }
+
+    /// Returns whether the last item was an `Incomplete` part which is continued by the next one
+    #[cfg(feature = "std")]
+    pub(crate) fn continues_line(&self) -> bool {
+        self.lines.continues_line()
+    }
+
+    /// Skips `n` lines including all their `Incomplete` parts, without validating them
+    #[cfg(feature = "std")]
+    pub(crate) fn skip_lines(&mut self, n: u64) -> bool {
+        self.lines.skip_lines(n)
+    }
+
+    /// Returns the maximum length of a line before it is split into `Incomplete` parts
+    #[cfg(feature = "std")]
+    pub(crate) fn max_size(&self) -> usize {
+        self.lines.max_size
+    }
+
+    /// Returns the position after the last returned line, including whether it was an `Incomplete` part.
+    /// `SeekExt::resume_lines_rc()` continues from there with the same capacity and stats.
+    ///
+    /// Returns `None` while lines returned by `peek_line()` or passed to `unread()` are pending,
+    /// as they are consumed from the reader already and resuming would skip them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ReadExt, SeekExt};
+    ///
+    /// let mut lines = std::io::Cursor::new("first\nsecond\n").lines_rc();
+    /// assert_eq!("first", *lines.next().unwrap().unwrap());
+    /// let checkpoint = lines.checkpoint().unwrap();
+    /// assert_eq!(6, checkpoint.offset());
+    ///
+    /// let mut resumed = std::io::Cursor::new("first\nsecond\n").resume_lines_rc(&checkpoint).unwrap();
+    /// assert_eq!("second", *resumed.next().unwrap().unwrap());
+    /// assert_eq!(2, resumed.stats().lines);
+    /// ```
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        match self.has_pushed_back() {
+            true => None,
+            false => Some(self.position()),
+        }
+    }
+
+    /// Returns the position after the last line consumed from the reader, which may be pending
+    pub(crate) fn position(&self) -> Checkpoint {
+        Checkpoint {
+            stats: self.lines.stats,
+            max_size: self.lines.max_size,
+            pending_incomplete: self.lines.pending_incomplete,
+            current_length: self.lines.current_length,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn restore(&mut self, checkpoint: &Checkpoint) {
+        self.lines.stats = checkpoint.stats;
+        self.lines.pending_incomplete = checkpoint.pending_incomplete;
+        self.lines.current_length = checkpoint.current_length;
+    }
+
+    /// Returns the next item without consuming it, e.g. to decide how to parse a section.
+    /// Its line is consumed from the reader, so it is already part of `stats()`, and `checkpoint()` fails while it is pending.
+    ///
+    /// Conversions like `into_rc_str()` return peeked and unread lines first, and `count()`, `count_lines()`, `nth()` and `fold_str()` include them.
+    /// `into_inner()` fails while they are pending, as the returned reader is positioned after them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("[section]\nkey=value").lines_rc();
+    /// assert!(lines.peek_line().unwrap().as_ref().unwrap().starts_with('['));
+    /// assert_eq!("[section]", *lines.next().unwrap().unwrap());
+    /// assert_eq!("key=value", lines.peek_line().unwrap().as_ref().unwrap().as_str());
+    /// assert_eq!(1, lines.count());
+    /// ```
+    pub fn peek_line(&mut self) -> Option<&RcLine> {
+        if self.lines.pushed_back.is_empty() {
+            let line = self.next()?;
+            self.lines.pushed_back.push(line);
+        }
+        self.lines.pushed_back.last()
+    }
+
+    /// Pushes `line` back, so it is returned by the next call to `next()`, e.g. if a parser detects the end of a section one line too late.
+    /// Multiple lines are returned in reverse order of being unread. Like peeked lines, they are already part of `stats()`.
+    ///
+    /// Fails with `line` once `next()` returned `None`, as the iterator stays finished like all `FusedIterator`s.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\nb\n[next]\nc").lines_rc();
+    /// let mut section = Vec::new();
+    /// while let Some(line) = lines.next() {
+    ///     if line.as_ref().is_ok_and(|x| x.starts_with('[')) {
+    ///         lines.unread(line).unwrap();
+    ///         break;
+    ///     }
+    ///     section.push(line.unwrap());
+    /// }
+    /// assert_eq!(2, section.len());
+    /// assert_eq!("[next]", *lines.next().unwrap().unwrap());
+    /// ```
+    pub fn unread(&mut self, line: RcLine) -> Result<(), RcLine> {
+        if self.lines.ended {
+            return Err(line);
+        }
+        self.lines.pushed_back.push(line);
+        Ok(())
+    }
+
+    /// Returns the next line of a non-blocking reader, e.g. a socket registered with an event loop like mio.
+    /// If the reader fails with `WouldBlock`, `Poll::Pending` is returned instead of an `Io` error and the bytes of the partial line are kept,
+    /// so this can be called again once the reader is readable. `Poll::Ready(None)` marks the end of the reader.
+    ///
+    /// Pending reads aren't counted in `stats()`. Don't combine this with `with_read_timeout()`, which retries `WouldBlock` until its deadline.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::{io::Read, task::Poll}};
+    ///
+    /// /// Returns its chunks one per read and `WouldBlock` in between
+    /// struct Socket(Vec<&'static [u8]>, bool);
+    /// impl Read for Socket {
+    ///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    ///         self.1 = !self.1;
+    ///         if self.1 && !self.0.is_empty() {
+    ///             return Err(std::io::ErrorKind::WouldBlock.into());
+    ///         }
+    ///         let Some(chunk) = self.0.pop() else { return Ok(0) };
+    ///         buf[..chunk.len()].copy_from_slice(chunk);
+    ///         Ok(chunk.len())
+    ///     }
+    /// }
+    ///
+    /// let mut lines = Socket(vec![b"ne\n", b"o"], false).lines_rc();
+    /// assert!(lines.try_next_line().is_pending());
+    /// assert!(lines.try_next_line().is_pending());
+    /// match lines.try_next_line() {
+    ///     Poll::Ready(Some(line)) => assert_eq!("one", *line.unwrap()),
+    ///     _ => panic!("expected a line"),
+    /// }
+    /// assert!(matches!(lines.try_next_line(), Poll::Ready(None)));
+    /// assert_eq!(1, lines.stats().lines);
+    /// ```
+    pub fn try_next_line(&mut self) -> Poll<Option<RcLine>> {
+        if let Some(line) = self.lines.pushed_back.pop() {
+            return Poll::Ready(Some(line));
+        }
+        let buffers = &mut self.buffers;
+        match self.lines.read_str(false) {
+            Some(Err(crate::Error::Io(e))) if e.kind() == io::ErrorKind::WouldBlock => {
+                Poll::Pending
+            }
+            line => Poll::Ready(line.map(|line| match line {
+                Ok(x) => Ok(buffers.share(x)),
+                Err(e) => Err(e.map_incomplete(|x| buffers.share(x))),
+            })),
+        }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("1234567\nab\n").lines_rc_with_capacity(5);
+    /// lines.by_ref().for_each(drop);
+    /// let stats = lines.stats();
+    /// assert_eq!((3, 11, 2, 7), (stats.lines, stats.bytes, stats.incomplete_lines, stats.longest_line));
+    /// ```
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Calls `callback` with the current totals each time the reader advanced by `interval`, e.g. to drive a progress bar.
+    /// `Stats::bytes` is the offset in the reader, if it was at its start when the iterator was created.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::{ProgressInterval, ReadExt}, std::sync::{Arc, Mutex}};
+    ///
+    /// let offsets = Arc::new(Mutex::new(Vec::new()));
+    /// let offsets_callback = offsets.clone();
+    /// let lines = std::io::Cursor::new("a\nb\nc\nd")
+    ///     .lines_rc()
+    ///     .on_progress(ProgressInterval::Lines(2), move |stats| {
+    ///         offsets_callback.lock().unwrap().push(stats.bytes)
+    ///     });
+    /// lines.for_each(drop);
+    /// assert_eq!(vec![4, 7], *offsets.lock().unwrap());
+    /// ```
+    pub fn on_progress(
+        mut self,
+        interval: ProgressInterval,
+        callback: impl FnMut(&Stats) + Send + 'static,
+    ) -> Self {
+        self.lines.progress = Some(ProgressHook::new(interval, Box::new(callback)));
+        self
+    }
+
+    /// Yields a single `Error::Cancelled` and ends the iteration as soon as `token` is set, e.g. from another thread.
+    /// The token is checked before each line, so a reader which blocks forever can't be cancelled.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::sync::{atomic::{AtomicBool, Ordering}, Arc}};
+    ///
+    /// let token = Arc::new(AtomicBool::new(false));
+    /// let mut lines = std::io::Cursor::new("a\nb\nc").lines_rc().with_cancellation(token.clone());
+    /// assert_eq!("a", *lines.next().unwrap().unwrap());
+    /// token.store(true, Ordering::Relaxed);
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::Cancelled))));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn with_cancellation(mut self, token: Arc<AtomicBool>) -> Self {
+        self.lines.cancellation = Some(token);
+        self
+    }
+
+    /// Limits how long reading a single line may take. If it takes longer, `Error::TimedOut` is returned.
+    /// This prevents attackers from stalling the iterator by sending a line very slowly.
+    ///
+    /// The deadline is checked whenever the reader returns, so blocking readers should have their own,
+    /// shorter timeout (e.g. `TcpStream::set_read_timeout()`). Their `WouldBlock` and `TimedOut` errors are retried until the deadline, non-blocking readers are polled every millisecond.
+    /// The partial line is kept, so iteration can continue after `Error::TimedOut`.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::{io::Read, time::Duration}};
+    ///
+    /// /// Sends one byte per millisecond without ever sending a linebreak
+    /// struct Trickle;
+    /// impl Read for Trickle {
+    ///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    ///         std::thread::sleep(Duration::from_millis(1));
+    ///         buf[0] = b'x';
+    ///         Ok(1)
+    ///     }
+    /// }
+    ///
+    /// let slow = std::io::Cursor::new("a\n").chain(Trickle);
+    /// let mut lines = slow.lines_rc().with_read_timeout(Duration::from_millis(20));
+    /// assert_eq!("a", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::TimedOut))));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.lines.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits the number of bytes read from the reader, so an attacker can't keep the consumer busy with many short lines.
+    /// The line exceeding the quota is replaced by `Error::QuotaExceeded`, which ends the iteration.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("ab\ncd\nef").lines_rc().with_max_total_bytes(6);
+    /// assert_eq!("ab", *lines.next().unwrap().unwrap());
+    /// assert_eq!("cd", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::QuotaExceeded))));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.lines.quotas.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    /// Limits the number of items, so handlers of untrusted uploads can't forget to enforce it.
+    /// If the reader contains more lines, the next one is replaced by `Error::TooManyLines`, which ends the iteration.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("a\nb\nc").lines_rc().with_max_lines(2);
+    /// let result = lines.collect::<Result<Vec<_>, _>>();
+    /// assert!(matches!(result, Err(simple_lines::Error::TooManyLines)));
+    ///
+    /// let lines = std::io::Cursor::new("a\nb\n").lines_rc().with_max_lines(2);
+    /// assert_eq!(2, lines.collect::<Result<Vec<_>, _>>().unwrap().len());
+    /// ```
+    pub fn with_max_lines(mut self, max_lines: u64) -> Self {
+        self.lines.quotas.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Replaces lines containing any of `bytes` by `Error::ForbiddenByte`, e.g. for protocols where NUL indicates an attack or corruption.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\0b\nc").lines_rc().with_forbidden_bytes(b"\0");
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::ForbiddenByte { byte: 0, position: 1 }))));
+    /// assert_eq!("c", *lines.next().unwrap().unwrap());
+    /// ```
+    pub fn with_forbidden_bytes(self, bytes: &[u8]) -> Self {
+        self.with_forbidden_bytes_where(|x| bytes.contains(&x))
+    }
+
+    /// Replaces lines containing any byte matching `predicate` by `Error::ForbiddenByte`
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\tb\n\x1b[31m")
+    ///     .lines_rc()
+    ///     .with_forbidden_bytes_where(|x| x.is_ascii_control() && x != b'\t');
+    /// assert_eq!("a\tb", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::ForbiddenByte { byte: 0x1b, .. }))));
+    /// ```
+    pub fn with_forbidden_bytes_where(mut self, predicate: impl Fn(u8) -> bool) -> Self {
+        let mut forbidden = Box::new([false; 256]);
+        for byte in 0..=255 {
+            forbidden[byte as usize] = predicate(byte);
+        }
+        self.lines.forbidden_bytes = Some(forbidden);
+        self
+    }
+
+    /// Checks whether the input looks binary before reading the first line, like grep does.
+    /// Binary input yields a single `Error::BinaryContent` instead of lots of `Error::Encoding`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01";
+    /// let mut lines = std::io::Cursor::new(png).lines_rc().with_binary_detection();
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::BinaryContent))));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn with_binary_detection(mut self) -> Self {
+        self.lines.detect_binary = true;
+        self
+    }
+
+    /// Records the start offset of every `every`-th line while iterating, e.g. to access them randomly later.
+    /// See `LineIndex` for details.
+    pub fn with_line_index(mut self, every: u64) -> Self {
+        self.lines.index = Some(LineIndex::new(every));
+        self
+    }
+
+    /// Returns the index recorded so far, if enabled by `with_line_index()`
+    pub fn line_index(&self) -> Option<&LineIndex> {
+        self.lines.index.as_ref()
+    }
+
+    /// Trims Unicode whitespace from the given sides of each line before it is copied into the shared buffer, so trimming doesn't allocate.
+    ///
+    /// Of lines split into `Incomplete` parts, only the start of the first and the end of the last part are trimmed.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ReadExt, Trim};
+    ///
+    /// let mut lines = std::io::Cursor::new("  key = value \t\r\n").lines_rc().with_trim(Trim::Both);
+    /// assert_eq!("key = value", *lines.next().unwrap().unwrap());
+    /// ```
+    pub fn with_trim(mut self, trim: Trim) -> Self {
+        self.lines.trimming = Some(Trimming { trim, ascii: false });
+        self
+    }
+
+    /// Ends `Incomplete` parts at the grapheme cluster boundaries found by [unicode-segmentation](https://crates.io/crates/unicode-segmentation)
+    /// instead of after exactly `capacity` bytes, so parts shown to users don't end in broken characters, emoji, flags, Hangul syllables or combining sequences.
+    /// The last cluster of each part, which may continue beyond the capacity, is moved to the start of the next part.
+    ///
+    /// Parts are at most twice the capacity long. A single cluster longer than the capacity is still cut, to keep memory bounded.
+    /// Parts skipped by `Iterator::nth()` or `RcLineIterator::skip_lines()` drop the cluster moved from them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{Error, ReadExt};
+    ///
+    /// let lines = std::io::Cursor::new("ab🇨🇭").lines_rc_with_capacity(6).with_grapheme_boundaries();
+    /// let parts = lines.map(|x| match x {
+    ///     Err(Error::Incomplete(x)) => x.to_string(),
+    ///     x => panic!("{:?}", x),
+    /// });
+    /// assert_eq!(vec!["ab", "🇨🇭"], parts.collect::<Vec<_>>());
+    /// ```
+    #[cfg(feature = "unicode")]
+    pub fn with_grapheme_boundaries(mut self) -> Self {
+        self.lines.graphemes = Some(crate::graphemes::GraphemeJoin::default());
+        self
+    }
+
+    /// Like `with_trim()`, but only trims ASCII whitespace
+    pub fn with_trim_ascii(mut self, trim: Trim) -> Self {
+        self.lines.trimming = Some(Trimming { trim, ascii: true });
+        self
+    }
+
+    /// Ends the iteration with `Error::LineTooLong` instead of splitting a line longer than the capacity into `Incomplete` parts,
+    /// e.g. for protocols which require to close the connection in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("NICK a\r\nPRIVMSG too long\r\nQUIT").lines_rc_with_capacity(10).with_strict_line_length();
+    /// assert_eq!("NICK a", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::LineTooLong))));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn with_strict_line_length(mut self) -> Self {
+        self.lines.quotas.strict_line_length = true;
+        self
+    }
+
+    /// Ends the iteration after the first `Io` error, e.g. of a broken socket which would return the same error on every read.
+    /// `Error::TimedOut` of `with_read_timeout()` doesn't end the iteration.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::io::Read};
+    ///
+    /// let broken = std::io::Cursor::new("a\n").chain(BrokenPipe);
+    /// let mut lines = broken.lines_rc().with_stop_on_io_error();
+    /// assert_eq!("a", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(simple_lines::Error::Io(_)))));
+    /// assert!(lines.next().is_none());
+    ///
+    /// struct BrokenPipe;
+    /// impl Read for BrokenPipe {
+    ///     fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+    ///         Err(std::io::ErrorKind::BrokenPipe.into())
+    ///     }
+    /// }
+    /// ```
+    pub fn with_stop_on_io_error(mut self) -> Self {
+        self.lines.stop_on_io_error = true;
+        self
+    }
+
+    /// Announces that `remaining_bytes` are left to read, e.g. the length of a slice, so `size_hint()` can estimate the number of lines.
+    /// The lower bound assumes lines of the full capacity and the upper bound lines of a single byte.
+    /// `lines_from_path()` sets the size of regular files automatically.
+    ///
+    /// The hint is just an estimate. It is wrong if the source ends early, grows while it is read or fails without consuming bytes.
+    /// The lower bound is 0 if the iteration can end early due to `with_max_lines()`, `with_max_total_bytes()`, `with_strict_line_length()`,
+    /// `with_binary_detection()`, `with_cancellation()` or `with_stop_on_io_error()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let data = "a\nb\nc";
+    /// let lines = std::io::Cursor::new(data).lines_rc_with_capacity(2).with_size_hint(data.len() as u64);
+    /// assert_eq!((2, Some(6)), lines.size_hint());
+    /// ```
+    pub fn with_size_hint(mut self, remaining_bytes: u64) -> Self {
+        self.end = Some(self.lines.stats.bytes + remaining_bytes);
+        self
+    }
+
+    /// Keeps up to `pool_size` buffers which are still referenced by the consumer, to reuse them as soon as they are released.
+    /// Without a pool, a new buffer is allocated for each line while the consumer keeps the previous one.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\nb\nc\nd").lines_rc().with_buffer_pool(1);
+    /// let mut previous = lines.next().unwrap().unwrap();
+    /// for line in lines {
+    ///     // Only two buffers are used alternately
+    ///     previous = line.unwrap();
+    /// }
+    /// assert_eq!("d", *previous);
+    /// ```
+    pub fn with_buffer_pool(mut self, pool_size: usize) -> Self {
+        self.buffers.pool_size = pool_size;
+        self.buffers.pool.truncate(pool_size);
+        self
+    }
+
+    /// Folds all remaining lines while borrowing them from the internal buffer, so no `Rc<String>` is created at all.
+    /// `Iterator::fold()` can't do that, because its closure takes ownership of each item.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let cursor = std::io::Cursor::new("a\nbb\r\nccc");
+    /// let len = cursor.lines_rc().fold_str(0, |acc, line| acc + line.map_or(0, str::len));
+    /// assert_eq!(6, len);
+    /// ```
+    pub fn fold_str<B>(
+        mut self,
+        init: B,
+        mut f: impl FnMut(B, Result<&str, crate::Error<&str>>) -> B,
+    ) -> B {
+        let mut acc = init;
+        while let Some(line) = self.lines.next_str() {
+            acc = f(acc, line);
+        }
+        acc
+    }
+
+    /// Counts the remaining lines like `count_lines()`. In contrast to `count()`, lines longer than the capacity are counted once.
+    /// Quotas, binary detection and cancellation end the count like they end the iteration, and their error is returned with
+    /// `io::ErrorKind::Other` instead of a count, so a limited iterator never reads beyond its limits.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("1234567\n1").lines_rc_with_capacity(5);
+    /// assert_eq!(2, lines.count_lines().unwrap());
+    /// ```
+    pub fn count_lines(mut self) -> io::Result<u64> {
+        self.lines.count_lines()
+    }
+
+    /// Yields `Rc<str>` instead of `Rc<String>`, which is immutable and can't be reused.
+    /// Each line is allocated exactly once, without copying it into an intermediate `String`.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::{collections::HashSet, rc::Rc}};
+    ///
+    /// let lines = std::io::Cursor::new("a\nb\na").lines_rc().into_rc_str();
+    /// let unique = lines.collect::<Result<HashSet<Rc<str>>, _>>().unwrap();
+    /// assert!(unique.contains("a"));
+    /// assert_eq!(2, unique.len());
+    /// ```
+    pub fn into_rc_str(self) -> crate::rc_str::RcStrLineIterator<T> {
+        crate::rc_str::RcStrLineIterator::new(self.lines)
+    }
+
+    /// Yields batches of up to `size` owned lines, e.g. to hand them to worker threads or bulk insert them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut chunks = std::io::Cursor::new("a\nb\nc").lines_rc().chunks_of(2);
+    /// let first = chunks.next().unwrap();
+    /// let handle = std::thread::spawn(move || first.into_iter().map(Result::unwrap).collect::<String>());
+    /// assert_eq!("ab", handle.join().unwrap());
+    /// assert_eq!(1, chunks.next().unwrap().len());
+    /// assert!(chunks.next().is_none());
+    /// ```
+    pub fn chunks_of(self, size: usize) -> crate::chunks::LineChunks<T> {
+        crate::chunks::LineChunks::new(self.lines, size)
+    }
+
+    /// Yields `SmallLine`, which stores short lines inline without any heap allocation.
+    /// Longer lines are shared in a reused `Rc<String>` like in this iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ReadExt, SmallLine};
+    ///
+    /// let mut lines = std::io::Cursor::new("short\n").lines_rc().into_small();
+    /// let line = lines.next().unwrap().unwrap();
+    /// assert!(matches!(line, SmallLine::Inline(_)));
+    /// assert_eq!("short", &*line);
+    /// ```
+    pub fn into_small(self) -> crate::small::SmallLineIterator<T> {
+        crate::small::SmallLineIterator::new(self.lines, self.buffers)
+    }
+
+    /// Yields each line in its own `zeroize::Zeroizing<String>`, which is wiped when the consumer drops it.
+    /// In contrast to the shared buffers of this iterator, lines kept beyond the lifetime of the iterator are wiped too.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("password").lines_rc().into_zeroizing();
+    /// assert_eq!("password", lines.next().unwrap().unwrap().as_str());
+    /// ```
+    #[cfg(feature = "zeroize")]
+    pub fn into_zeroizing(self) -> crate::zeroizing::ZeroizingLineIterator<T> {
+        crate::zeroizing::ZeroizingLineIterator::new(self.lines)
+    }
+
+    /// Allocates the line buffers with `alloc`, e.g. to account for their memory in a bounded allocator.
+    /// Allocators are provided by [allocator_api2](https://crates.io/crates/allocator-api2).
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("a\nb").lines_rc().into_allocator(allocator_api2::alloc::Global);
+    /// assert_eq!(2, lines.filter_map(Result::ok).count());
+    /// ```
+    #[cfg(feature = "allocator_api")]
+    pub fn into_allocator<A: allocator_api2::alloc::Allocator + Clone>(
+        self,
+        alloc: A,
+    ) -> crate::allocator::AllocLineIterator<T, A> {
+        crate::allocator::AllocLineIterator::new(self.lines, alloc)
+    }
+
+    /// Copies the next line into `arena`, so it lives as long as the arena instead of until the next line.
+    /// All lines of a batch are freed at once by resetting the arena.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut arena = bumpalo::Bump::new();
+    /// let mut lines = std::io::Cursor::new("a\nb\nc").lines_rc();
+    /// loop {
+    ///     let batch = std::iter::from_fn(|| lines.next_in(&arena))
+    ///         .take(2)
+    ///         .collect::<Result<Vec<&str>, _>>()
+    ///         .unwrap();
+    ///     if batch.is_empty() {
+    ///         break;
+    ///     }
+    ///     assert!(batch.len() <= 2);
+    ///     drop(batch);
+    ///     arena.reset();
+    /// }
+    /// ```
+    #[cfg(feature = "bumpalo")]
+    pub fn next_in<'a>(
+        &mut self,
+        arena: &'a bumpalo::Bump,
+    ) -> Option<Result<&'a str, crate::Error<&'a str>>> {
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(&*arena.alloc_str(x)),
+            Err(e) => Err(e.map_incomplete(|x| &*arena.alloc_str(x))),
+        })
+    }
 }
 
-impl<T: Read> RcLineIterator<T> {
-    pub fn new(line_reader: LineReader<T>, max_size: usize) -> Self {
+impl<T: LineSource> BorrowedLines<T> {
+    pub(crate) fn new(source: T, max_size: usize) -> Self {
         Self {
-            line_reader,
+            source,
             max_size,
-            buffer: Rc::new(String::new()),
             pending_incomplete: false,
+            current_length: 0,
+            stats: Stats::default(),
+            progress: None,
+            cancellation: None,
+            read_timeout: None,
+            quotas: Quotas::default(),
+            forbidden_bytes: None,
+            detect_binary: false,
+            index: None,
+            trimming: None,
+            stop: None,
+            stop_on_io_error: false,
+            finished: false,
+            ended: false,
+            pushed_back: Vec::new(),
+            popped: None,
+            #[cfg(feature = "unicode")]
+            graphemes: None,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Returns whether an option can end the iteration before the source ends
+    fn may_stop_early(&self) -> bool {
+        let quotas = &self.quotas;
+        quotas.max_total_bytes.is_some()
+            || quotas.max_lines.is_some()
+            || quotas.strict_line_length
+            || self.detect_binary
+            || self.cancellation.is_some()
+            || self.stop_on_io_error
+    }
+
+    /// Returns whether the last item was an `Incomplete` part which is continued by the next one
+    #[cfg(feature = "std")]
+    pub(crate) fn continues_line(&self) -> bool {
+        self.pending_incomplete
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn source(&self) -> &T {
+        &self.source
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn source_mut(&mut self) -> &mut T {
+        &mut self.source
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn into_source(self) -> T {
+        self.source
+    }
+
+    pub(crate) fn next_str(&mut self) -> Option<Result<&str, crate::Error<&str>>> {
+        self.read_str(true)
+    }
+
+    /// Like `next_str()`, but `WouldBlock` errors are only counted in `stats` if `count_would_block` is set
+    fn read_str(&mut self, count_would_block: bool) -> Option<Result<&str, crate::Error<&str>>> {
+        if let Some(line) = self.pushed_back.pop() {
+            return Some(match line {
+                Ok(x) => Ok(self.popped.insert(x).as_str()),
+                Err(crate::Error::Incomplete(x)) => {
+                    Err(crate::Error::Incomplete(self.popped.insert(x).as_str()))
+                }
+                Err(e) => Err(e.map_incomplete(|_| "")),
+            });
+        }
+        if self.finished {
+            self.ended = true;
+            return None;
+        }
+        self.detect_binary_content();
+        if self.stop.is_none() && self.is_cancelled() {
+            self.stats.lines += 1;
+            self.stop = Some(Stop::Cancelled);
+        }
+        if let Some(stop) = self.stop.take() {
+            self.finished = true;
+            return Some(Err(stop.into_error()));
+        }
+        let has_timeout = self.read_timeout.is_some();
+        let line = match read_line(&mut self.source, self.read_timeout) {
+            Some(Ok(line)) => line,
+            Some(Err(e)) if !count_would_block && e.kind() == io::ErrorKind::WouldBlock => {
+                return Some(Err(e.into()));
+            }
+            Some(Err(e)) => {
+                self.stats.lines += 1;
+                self.stats.io_errors += 1;
+                report_progress(&mut self.progress, &self.stats);
+                if e.kind() == io::ErrorKind::TimedOut && has_timeout {
+                    return Some(Err(crate::Error::TimedOut));
+                }
+                self.finished = self.stop_on_io_error;
+                return Some(Err(e.into()));
+            }
+            None => {
+                #[cfg(feature = "unicode")]
+                if let Some(held) = self.graphemes.as_mut().and_then(|x| x.take_held()) {
+                    self.pending_incomplete = false;
+                    self.stats.lines += 1;
+                    let result = match crate::utf8::from_utf8(held) {
+                        Ok(x) => {
+                            self.stats.incomplete_lines += 1;
+                            Err(crate::Error::Incomplete(x))
+                        }
+                        Err(e) => {
+                            self.stats.encoding_errors += 1;
+                            Err(e.into())
+                        }
+                    };
+                    report_progress(&mut self.progress, &self.stats);
+                    return Some(result);
+                }
+                self.finished = true;
+                self.ended = true;
+                return None;
+            }
+        };
+        if let Some(index) = &mut self.index {
+            index.record(self.stats.bytes, self.pending_incomplete);
+        }
+        let (line, is_full) = consume(
+            line,
+            self.max_size,
+            &mut self.current_length,
+            &mut self.stats,
+        );
+        #[cfg(feature = "unicode")]
+        let line = match &mut self.graphemes {
+            Some(graphemes) => graphemes.regroup(line, is_full),
+            None => line,
+        };
+        let was_pending = core::mem::replace(&mut self.pending_incomplete, is_full);
+        let result = if let Some(stop) = self.quotas.exceeded(&self.stats, is_full) {
+            // The line exceeding a quota or the capacity is replaced by the error
+            self.finished = true;
+            Err(stop.into_error())
+        } else if let Some((position, &byte)) =
+            self.forbidden_bytes.as_ref().and_then(|forbidden| {
+                line.iter()
+                    .enumerate()
+                    .find(|(_, b)| forbidden[**b as usize])
+            })
+        {
+            self.stats.forbidden_lines += 1;
+            Err(crate::Error::ForbiddenByte { byte, position })
+        } else {
+            let trimming = self.trimming;
+            let trimmed = crate::utf8::from_utf8(line).map(|x| match trimming {
+                Some(trimming) => trimming.apply(x, !was_pending, !is_full),
+                None => x,
+            });
+            match trimmed {
+                Ok(x) if is_full || was_pending => {
+                    self.stats.incomplete_lines += 1;
+                    Err(crate::Error::Incomplete(x))
+                }
+                Ok(x) => Ok(x),
+                Err(e) => {
+                    self.stats.encoding_errors += 1;
+                    Err(e.into())
+                }
+            }
+        };
+        report_progress(&mut self.progress, &self.stats);
+        Some(result)
+    }
+
+    /// Advances by `n` lines without validating them. Returns false if the source ends before.
+    /// Stops in front of errors which end the iteration, so they are returned by the next call to `next_str()`.
+    pub(crate) fn skip(&mut self, n: usize) -> bool {
+        for _ in 0..n {
+            if self.pushed_back.pop().is_some() {
+                continue;
+            }
+            match self.skip_one(|_| {}) {
+                Skipped::Line | Skipped::Failed(_) => {}
+                Skipped::Stop => return true,
+                Skipped::End => return false,
+            }
+        }
+        true
+    }
+
+    /// Like `skip()`, but counts the `Incomplete` parts of a line as one
+    #[cfg(feature = "std")]
+    pub(crate) fn skip_lines(&mut self, n: u64) -> bool {
+        let mut started = 0;
+        let mut in_pushed_line = false;
+        while let Some(line) = self.pushed_back.last() {
+            if !in_pushed_line {
+                if started == n {
+                    return true;
+                }
+                started += 1;
+            }
+            in_pushed_line = matches!(line, Err(crate::Error::Incomplete(_)));
+            self.pushed_back.pop();
+        }
+        loop {
+            if !self.pending_incomplete {
+                if started == n {
+                    return true;
+                }
+                started += 1;
+            }
+            match self.skip_one(|_| {}) {
+                Skipped::Line | Skipped::Failed(_) => {}
+                Skipped::Stop => return true,
+                Skipped::End => return false,
+            }
+        }
+    }
+
+    pub(crate) fn count(mut self) -> usize {
+        let mut count = self.pushed_back.len();
+        loop {
+            match self.skip_one(|_| {}) {
+                Skipped::Line | Skipped::Failed(_) => count += 1,
+                Skipped::Stop => return count + 1,
+                Skipped::End => return count,
+            }
+        }
+    }
+
+    /// Counts the remaining lines, counting `Incomplete` parts of a line once. Errors which end the iteration are returned instead.
+    pub(crate) fn count_lines(&mut self) -> io::Result<u64> {
+        // Parts of a pushed back line are counted with its last part
+        let pushed_back = self
+            .pushed_back
+            .drain(..)
+            .filter(|x| !matches!(x, Err(crate::Error::Incomplete(_))))
+            .count() as u64;
+        let mut counter = crate::count::LineCounter::default();
+        loop {
+            match self.skip_one(|x| counter.add(x)) {
+                Skipped::Line => {}
+                Skipped::Failed(e) => return Err(e),
+                Skipped::Stop => {
+                    self.finished = true;
+                    let stop = self.stop.take().unwrap_or(Stop::Cancelled);
+                    return Err(stop.into_io_error());
+                }
+                Skipped::End => return Ok(pushed_back + counter.finish()),
+            }
+        }
+    }
+
+    /// Consumes the next chunk without validating it and passes it to `on_chunk`
+    fn skip_one(&mut self, on_chunk: impl FnOnce(&[u8])) -> Skipped {
+        if self.finished {
+            self.ended = true;
+            return Skipped::End;
+        }
+        self.detect_binary_content();
+        if self.stop.is_some() || self.is_cancelled() {
+            return Skipped::Stop;
+        }
+        #[cfg(feature = "unicode")]
+        if let Some(graphemes) = &mut self.graphemes {
+            graphemes.clear();
+        }
+        match read_line(&mut self.source, self.read_timeout) {
+            Some(Ok(line)) => {
+                if let Some(index) = &mut self.index {
+                    index.record(self.stats.bytes, self.pending_incomplete);
+                }
+                on_chunk(line);
+                let (_, is_full) = consume(
+                    line,
+                    self.max_size,
+                    &mut self.current_length,
+                    &mut self.stats,
+                );
+                self.pending_incomplete = is_full;
+                self.stop = self.quotas.exceeded(&self.stats, is_full);
+            }
+            Some(Err(e)) => {
+                self.stats.lines += 1;
+                self.stats.io_errors += 1;
+                let timed_out = e.kind() == io::ErrorKind::TimedOut && self.read_timeout.is_some();
+                if !self.stop_on_io_error || timed_out {
+                    report_progress(&mut self.progress, &self.stats);
+                    return Skipped::Failed(e);
+                }
+                self.stop = Some(Stop::Io(e));
+            }
+            None => {
+                self.finished = true;
+                self.ended = true;
+                return Skipped::End;
+            }
+        }
+        report_progress(&mut self.progress, &self.stats);
+        if self.stop.is_some() {
+            Skipped::Stop
+        } else {
+            Skipped::Line
+        }
+    }
+
+    /// Ends the iteration if the first bytes look binary. Errors are left to the next read.
+    fn detect_binary_content(&mut self) {
+        if !core::mem::take(&mut self.detect_binary) {
+            return;
+        }
+        if let Some(timeout) = self.read_timeout {
+            self.source.set_deadline(source::deadline_after(timeout));
+        }
+        let is_binary = self
+            .source
+            .peek()
+            .is_ok_and(|x| crate::utf8::looks_binary(&x[0..x.len().min(BINARY_SAMPLE_SIZE)]));
+        if is_binary {
+            self.stats.lines += 1;
+            self.stop = Some(Stop::BinaryContent);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|x| x.load(Ordering::Relaxed))
+    }
+}
+
+enum Skipped {
+    Line,
+    /// The reader failed, which ends the iteration if `stop_on_io_error` is set
+    Failed(io::Error),
+    /// An error which ends the iteration is pending
+    Stop,
+    End,
+}
+
+/// Error which ends the iteration
+enum Stop {
+    Io(io::Error),
+    Cancelled,
+    QuotaExceeded,
+    TooManyLines,
+    BinaryContent,
+    LineTooLong,
+}
+
+impl Stop {
+    fn into_error<T: core::fmt::Debug>(self) -> crate::Error<T> {
+        match self {
+            Stop::Io(e) => crate::Error::Io(e),
+            Stop::Cancelled => crate::Error::Cancelled,
+            Stop::QuotaExceeded => crate::Error::QuotaExceeded,
+            Stop::TooManyLines => crate::Error::TooManyLines,
+            Stop::BinaryContent => crate::Error::BinaryContent,
+            Stop::LineTooLong => crate::Error::LineTooLong,
+        }
+    }
+
+    fn into_io_error(self) -> io::Error {
+        match self {
+            Stop::Io(e) => e,
+            stop => io::Error::other(stop.into_error::<String>()),
         }
     }
 }
 
-impl<TRead: Read> Iterator for RcLineIterator<TRead> {
-    type Item = Result<Rc<String>, crate::Error<Rc<String>>>;
-    fn next(&mut self) -> Option<Result<Rc<String>, crate::Error<Rc<String>>>> {
-        let buffer = &mut self.buffer;
-        let max_size = self.max_size;
-        let pending_incomplete = &mut self.pending_incomplete;
-
-        self.line_reader.next_line().map(move |line| {
-            let mut line = line?;
-            let contains_delimiter = line.last() == Some(&b'\n');
-            if contains_delimiter {
-                line = &line[0..line.len() - 1];
-                if line.last() == Some(&b'\r') {
-                    line = &line[0..line.len() - 1];
-                }
-            }
-            let owned = if let Some(r) = Rc::get_mut(buffer) {
-                r.clear();
-                r
-            } else {
-                *buffer = Rc::new(String::with_capacity(line.len()));
-                Rc::get_mut(buffer).unwrap()
-            };
-            let line_str = std::str::from_utf8(line)?;
-            owned.push_str(line_str);
-
-            if max_size == buffer.len() {
-                *pending_incomplete = true;
-                Err(crate::Error::Incomplete(buffer.clone()))
-            } else if *pending_incomplete {
-                *pending_incomplete = false;
-                Err(crate::Error::Incomplete(buffer.clone()))
+/// Number of bytes inspected by `with_binary_detection()`
+const BINARY_SAMPLE_SIZE: usize = 8 * 1024;
+
+#[derive(Default)]
+struct Quotas {
+    max_total_bytes: Option<u64>,
+    max_lines: Option<u64>,
+    /// Whether a line reaching the capacity ends the iteration
+    strict_line_length: bool,
+}
+
+impl Quotas {
+    /// Returns the error which replaces the last line, whose `is_full` tells whether it reached the capacity
+    fn exceeded(&self, stats: &Stats, is_full: bool) -> Option<Stop> {
+        if self.max_total_bytes.is_some_and(|max| stats.bytes > max) {
+            Some(Stop::QuotaExceeded)
+        } else if self.max_lines.is_some_and(|max| stats.lines > max) {
+            Some(Stop::TooManyLines)
+        } else if is_full && self.strict_line_length {
+            Some(Stop::LineTooLong)
+        } else {
+            None
+        }
+    }
+}
+
+fn read_line<T: LineSource>(
+    source: &mut T,
+    read_timeout: Option<Duration>,
+) -> Option<io::Result<&[u8]>> {
+    if let Some(timeout) = read_timeout {
+        source.set_deadline(source::deadline_after(timeout));
+    }
+    source.next_line()
+}
+
+fn report_progress(progress: &mut Option<ProgressHook>, stats: &Stats) {
+    if let Some(x) = progress {
+        x.report(stats);
+    }
+}
+
+/// Strips the delimiter and updates `stats`. Returns whether the line was cut at `max_size`
+fn consume<'a>(
+    line: &'a [u8],
+    max_size: usize,
+    current_length: &mut usize,
+    stats: &mut Stats,
+) -> (&'a [u8], bool) {
+    stats.lines += 1;
+    stats.bytes += line.len() as u64;
+    let line = strip_delimiter(line);
+    let is_full = max_size == line.len();
+    *current_length += line.len();
+    stats.longest_line = stats.longest_line.max(*current_length);
+    if !is_full {
+        *current_length = 0;
+    }
+    (line, is_full)
+}
+
+pub(crate) fn strip_delimiter(mut line: &[u8]) -> &[u8] {
+    if line.last() == Some(&b'\n') {
+        line = &line[0..line.len() - 1];
+        if line.last() == Some(&b'\r') {
+            line = &line[0..line.len() - 1];
+        }
+    }
+    line
+}
+
+/// Buffers used to share lines with the consumer
+#[derive(Default)]
+pub(crate) struct SharedBuffers {
+    current: Rc<String>,
+    pool: Vec<Rc<String>>,
+    pool_size: usize,
+}
+
+impl SharedBuffers {
+    /// Copies `line` into the current buffer, which is only replaced if it is still referenced by the consumer
+    pub(crate) fn share(&mut self, line: &str) -> Rc<String> {
+        if Rc::get_mut(&mut self.current).is_none() {
+            let is_free = |x: &&mut Rc<String>| Rc::strong_count(x) == 1 && Rc::weak_count(x) == 0;
+            if let Some(free) = self.pool.iter_mut().find(is_free) {
+                core::mem::swap(&mut self.current, free);
             } else {
-                Ok(buffer.clone())
+                let retained = core::mem::replace(
+                    &mut self.current,
+                    Rc::new(String::with_capacity(line.len())),
+                );
+                if self.pool_size > 0 {
+                    if self.pool.len() == self.pool_size {
+                        self.pool.remove(0);
+                    }
+                    self.pool.push(retained);
+                }
             }
+        }
+        let owned = Rc::get_mut(&mut self.current).unwrap();
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(owned);
+        owned.clear();
+        owned.push_str(line);
+        self.current.clone()
+    }
+}
+
+/// Wipes all buffers which aren't referenced by the consumer anymore
+#[cfg(feature = "zeroize")]
+impl Drop for SharedBuffers {
+    fn drop(&mut self) {
+        for buffer in core::iter::once(&mut self.current).chain(self.pool.iter_mut()) {
+            if let Some(x) = Rc::get_mut(buffer) {
+                zeroize::Zeroize::zeroize(x);
+            }
+        }
+    }
+}
+
+/// Once the source ended, `next()` doesn't read from it again and `unread()` fails
+impl<TSource: LineSource> core::iter::FusedIterator for RcLineIterator<TSource> {}
+
+impl<TSource: LineSource> Iterator for RcLineIterator<TSource> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Result<Rc<String>, crate::Error<Rc<String>>>> {
+        if let Some(line) = self.lines.pushed_back.pop() {
+            return Some(line);
+        }
+        let buffers = &mut self.buffers;
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(buffers.share(x)),
+            Err(e) => Err(e.map_incomplete(|x| buffers.share(x))),
         })
     }
+
+    /// Skips lines without validating or copying them
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        while let Some(line) = self.lines.pushed_back.pop() {
+            if n == 0 {
+                return Some(line);
+            }
+            n -= 1;
+        }
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating or copying them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pushed_back = self.lines.pushed_back.len();
+        match self.end {
+            _ if self.lines.finished => (pushed_back, Some(pushed_back)),
+            Some(end) => {
+                let remaining = end.saturating_sub(self.lines.stats.bytes);
+                let lower = match self.lines.may_stop_early() {
+                    true => 0,
+                    false => remaining.div_ceil(self.lines.max_size as u64 + 2),
+                };
+                let upper: Option<usize> = core::convert::TryFrom::try_from(remaining + 1).ok();
+                (lower as usize + pushed_back, upper.map(|x| x + pushed_back))
+            }
+            None => (pushed_back, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::ReadExt,
+        std::sync::{atomic::AtomicBool, Arc},
+    };
+
+    #[test]
+    fn count_includes_errors() {
+        let input = [b'a', b'\n', 254, b'\n', b'1', b'2', b'3', b'4', b'5', b'6'];
+        let expected = std::io::Cursor::new(input)
+            .lines_rc_with_capacity(5)
+            .fold(0, |acc, _| acc + 1);
+        assert_eq!(4, expected);
+        assert_eq!(
+            expected,
+            std::io::Cursor::new(input)
+                .lines_rc_with_capacity(5)
+                .count()
+        );
+    }
+
+    #[test]
+    fn nth_behaves_like_next() {
+        let input = [b'1', b'2', b'3', 254, b'5', b'6', b'\n', b'a', b'\n', b'b'];
+        for n in 0..5 {
+            let mut lines = std::io::Cursor::new(input).lines_rc_with_capacity(4);
+            let mut expected = std::io::Cursor::new(input).lines_rc_with_capacity(4);
+            for _ in 0..n {
+                expected.next();
+            }
+            assert_eq!(
+                format!("{:?}", expected.next()),
+                format!("{:?}", lines.nth(n))
+            );
+            assert_eq!(expected.count(), lines.count());
+        }
+    }
+
+    #[test]
+    fn pool_reuses_released_buffers() {
+        let mut lines = std::io::Cursor::new("a\nb\nc\nd\ne\nf\ng")
+            .lines_rc()
+            .with_buffer_pool(2);
+        let mut window = std::collections::VecDeque::new();
+        let mut addresses = std::collections::HashSet::new();
+        for line in lines.by_ref() {
+            let line = line.unwrap();
+            addresses.insert(line.as_ptr());
+            window.push_back(line);
+            if window.len() > 2 {
+                window.pop_front();
+            }
+        }
+        assert_eq!(3, addresses.len());
+        assert_eq!(
+            vec!["f", "g"],
+            window.iter().map(|x| x.as_str()).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn next_in_outlives_next_line() {
+        let arena = bumpalo::Bump::new();
+        let mut lines = std::io::Cursor::new("first\nsecond").lines_rc();
+        let first = lines.next_in(&arena).unwrap().unwrap();
+        let second = lines.next_in(&arena).unwrap().unwrap();
+        assert_eq!(("first", "second"), (first, second));
+        assert!(lines.next_in(&arena).is_none());
+    }
+
+    #[test]
+    fn stats_count_errors() {
+        let mut lines = std::io::Cursor::new([b'a', b'\n', 254, b'\n', b'b']).lines_rc();
+        lines.by_ref().for_each(drop);
+        let stats = lines.stats();
+        assert_eq!(
+            (3, 5, 1, 0, 1),
+            (
+                stats.lines,
+                stats.bytes,
+                stats.encoding_errors,
+                stats.io_errors,
+                stats.longest_line
+            )
+        );
+    }
+
+    #[test]
+    fn count_and_nth_stop_at_cancellation() {
+        let token = Arc::new(AtomicBool::new(true));
+        let lines = || {
+            std::io::Cursor::new("a\nb\nc")
+                .lines_rc()
+                .with_cancellation(token.clone())
+        };
+        assert_eq!(1, lines().count());
+        assert!(matches!(lines().nth(2), Some(Err(crate::Error::Cancelled))));
+        assert!(lines().count_lines().is_err());
+    }
+
+    #[test]
+    fn count_and_nth_stop_at_quota() {
+        let lines = || {
+            std::io::Cursor::new("a\nb\nc\nd")
+                .lines_rc()
+                .with_max_total_bytes(3)
+        };
+        assert_eq!(2, lines().count());
+        assert!(matches!(
+            lines().nth(3),
+            Some(Err(crate::Error::QuotaExceeded))
+        ));
+        let mut skipped = lines();
+        assert!(matches!(
+            skipped.nth(1),
+            Some(Err(crate::Error::QuotaExceeded))
+        ));
+        assert_eq!(2, skipped.stats().lines);
+        let error = lines().count_lines().unwrap_err();
+        assert!(matches!(
+            error.get_ref().and_then(|x| x.downcast_ref()),
+            Some(crate::Error::<String>::QuotaExceeded)
+        ));
+    }
+
+    #[test]
+    fn count_lines_stops_at_max_lines() {
+        let lines = |max| {
+            std::io::Cursor::new("a\nb\nc\nd")
+                .lines_rc()
+                .with_max_lines(max)
+        };
+        assert_eq!(3, lines(2).count());
+        let error = lines(2).count_lines().unwrap_err();
+        assert!(matches!(
+            error.get_ref().and_then(|x| x.downcast_ref()),
+            Some(crate::Error::<String>::TooManyLines)
+        ));
+        assert_eq!(4, lines(4).count_lines().unwrap());
+    }
+
+    #[test]
+    fn nth_stops_at_strict_line_length() {
+        let lines = || {
+            std::io::Cursor::new("a\nbcdef\ng")
+                .lines_rc_with_capacity(3)
+                .with_strict_line_length()
+        };
+        assert_eq!(2, lines().count());
+        assert!(matches!(
+            lines().nth(2),
+            Some(Err(crate::Error::LineTooLong))
+        ));
+    }
+
+    #[test]
+    fn nth_stops_at_io_error() {
+        /// Fails on every read after "a\nb\n"
+        struct Broken(std::io::Cursor<&'static str>);
+        impl std::io::Read for Broken {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.read(buf)? {
+                    0 => Err(std::io::ErrorKind::ConnectionReset.into()),
+                    n => Ok(n),
+                }
+            }
+        }
+        let lines = || Broken(std::io::Cursor::new("a\nb\n")).lines_rc();
+        assert!(matches!(lines().nth(5), Some(Err(crate::Error::Io(_)))));
+        let mut stopping = lines().with_stop_on_io_error();
+        assert!(matches!(stopping.nth(5), Some(Err(crate::Error::Io(_)))));
+        assert!(stopping.next().is_none());
+        assert_eq!(3, lines().with_stop_on_io_error().count());
+    }
+
+    #[test]
+    fn fold_str_reports_incomplete() {
+        let incomplete = std::io::Cursor::new("1234567\n12")
+            .lines_rc_with_capacity(5)
+            .fold_str(Vec::new(), |mut acc, line| {
+                if let Err(crate::Error::Incomplete(x)) = line {
+                    acc.push(x.to_string());
+                }
+                acc
+            });
+        assert_eq!(vec!["12345", "67"], incomplete);
+    }
+
+    #[test]
+    fn forbidden_bytes_in_incomplete_parts() {
+        let mut lines = std::io::Cursor::new("1234\x005\nab")
+            .lines_rc_with_capacity(4)
+            .with_forbidden_bytes(b"\0");
+        assert!(matches!(lines.next(), Some(Err(crate::Error::Incomplete(x))) if *x == "1234"));
+        assert!(matches!(
+            lines.next(),
+            Some(Err(crate::Error::ForbiddenByte {
+                byte: 0,
+                position: 0
+            }))
+        ));
+        assert_eq!("ab", *lines.next().unwrap().unwrap());
+        assert_eq!(1, lines.stats().forbidden_lines);
+    }
+
+    #[test]
+    fn binary_detection_passes_text() {
+        let lines = || {
+            std::io::Cursor::new("a\nb\n")
+                .lines_rc()
+                .with_binary_detection()
+        };
+        assert_eq!(2, lines().count());
+        assert_eq!("b", *lines().nth(1).unwrap().unwrap());
+        let binary = std::io::Cursor::new(b"a\0b\nc\n")
+            .lines_rc()
+            .with_binary_detection();
+        assert_eq!(1, binary.count());
+        assert_eq!(2, lines().count_lines().unwrap());
+        let binary = std::io::Cursor::new(b"a\0b\nc\n")
+            .lines_rc()
+            .with_binary_detection();
+        assert!(binary.count_lines().is_err());
+    }
+
+    #[test]
+    fn peeked_line_is_part_of_nth_and_fold() {
+        let lines = || std::io::Cursor::new("a\nb\nc").lines_rc();
+        let mut peeked = lines();
+        peeked.peek_line();
+        assert_eq!("b", *peeked.nth(1).unwrap().unwrap());
+        let mut peeked = lines();
+        peeked.peek_line();
+        let joined = peeked.fold_str(String::new(), |acc, x| acc + x.unwrap());
+        assert_eq!("abc", joined);
+    }
+
+    #[test]
+    fn size_hint_allows_early_stops() {
+        let data = "a\nb\nc";
+        let lines = || {
+            std::io::Cursor::new(data)
+                .lines_rc_with_capacity(2)
+                .with_size_hint(data.len() as u64)
+        };
+        assert_eq!((0, Some(6)), lines().with_max_lines(1).size_hint());
+        assert_eq!((0, Some(6)), lines().with_binary_detection().size_hint());
+        let token = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        assert_eq!((0, Some(6)), lines().with_cancellation(token).size_hint());
+    }
+
+    #[test]
+    fn rejects_unread_lines_once_finished() {
+        let mut lines = std::io::Cursor::new("a").lines_rc();
+        let a = lines.next().unwrap();
+        lines.unread(a).unwrap();
+        let a = lines.next().unwrap();
+        assert!(lines.next().is_none());
+        assert_eq!("a", *lines.unread(a).unwrap_err().unwrap());
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn conversions_return_pushed_back_lines() {
+        let lines = || {
+            let mut lines = std::io::Cursor::new("a\nb\nc").lines_rc();
+            let a = lines.next().unwrap();
+            lines.peek_line();
+            lines.unread(a).unwrap();
+            lines
+        };
+        let rc_str = lines().into_rc_str().map(|x| x.unwrap().to_string());
+        assert_eq!(vec!["a", "b", "c"], rc_str.collect::<Vec<_>>());
+        let chunks = lines().chunks_of(2).next().unwrap();
+        assert_eq!(
+            vec!["a", "b"],
+            chunks.into_iter().map(Result::unwrap).collect::<Vec<_>>()
+        );
+        let small = lines().into_small().map(|x| x.unwrap().to_string());
+        assert_eq!(vec!["a", "b", "c"], small.collect::<Vec<_>>());
+        assert_eq!(3, lines().into_rc_str().count());
+        assert_eq!(3, lines().count_lines().unwrap());
+        assert_eq!("c", &*lines().into_small().nth(2).unwrap().unwrap());
+    }
 }
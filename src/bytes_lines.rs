@@ -0,0 +1,176 @@
+use {
+    crate::{
+        bound::BorrowedLines,
+        source::{check_deadline, is_retryable, LineSource},
+    },
+    bytes::{Bytes, BytesMut},
+    std::{
+        io::{self, Read},
+        time::Instant,
+    },
+};
+
+/// Iterator over the lines of a reader as `bytes::Bytes`, created by `ReadExt::lines_bytes()`.
+///
+/// Lines are slices of a shared refill buffer, which is only released after all lines referencing it are dropped.
+/// The content of each line is guaranteed to be valid UTF8.
+pub struct BytesLineIterator<TRead: Read> {
+    lines: BorrowedLines<BytesSource<TRead>>,
+}
+
+impl<T: Read> BytesLineIterator<T> {
+    pub(crate) fn new(inner: T, max_size: usize) -> Self {
+        let source = BytesSource {
+            inner,
+            buffer: BytesMut::with_capacity(max_size),
+            max_size,
+            last: Bytes::new(),
+            deadline: None,
+        };
+        Self {
+            lines: BorrowedLines::new(source, max_size),
+        }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<TRead: Read> std::iter::FusedIterator for BytesLineIterator<TRead> {}
+
+impl<TRead: Read> Iterator for BytesLineIterator<TRead> {
+    type Item = Result<Bytes, crate::Error<Bytes>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        // Lines are borrowed from `last`, so they are converted into ranges to release the borrow
+        let to_range = |x: &str| (x.as_ptr() as usize, x.len());
+        let line = self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(to_range(x)),
+            Err(e) => Err(e.map_incomplete(to_range)),
+        });
+        let last = &self.lines.source().last;
+        let to_bytes = |(ptr, len): (usize, usize)| {
+            let start = ptr - last.as_ptr() as usize;
+            last.slice(start..start + len)
+        };
+        line.map(|line| match line {
+            Ok(x) => Ok(to_bytes(x)),
+            Err(e) => Err(e.map_incomplete(to_bytes)),
+        })
+    }
+
+    /// Skips lines without validating them
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+}
+
+/// Splits lines from a `BytesMut`, so they can be shared without copying
+struct BytesSource<TRead: Read> {
+    inner: TRead,
+    buffer: BytesMut,
+    max_size: usize,
+    last: Bytes,
+    deadline: Option<Instant>,
+}
+
+impl<T: Read> LineSource for BytesSource<T> {
+    fn next_line(&mut self) -> Option<io::Result<&[u8]>> {
+        loop {
+            let window = &self.buffer[0..self.buffer.len().min(self.max_size)];
+            if let Some(len) = memchr::memchr(b'\n', window)
+                .map(|pos| pos + 1)
+                .or(Some(self.max_size).filter(|x| *x == window.len()))
+            {
+                self.last = self.buffer.split_to(len).freeze();
+                return Some(Ok(&self.last));
+            }
+
+            if let Err(e) = check_deadline(self.deadline) {
+                return Some(Err(e));
+            }
+            let filled = self.buffer.len();
+            self.buffer.resize(filled + self.max_size, 0);
+            match self.inner.read(&mut self.buffer[filled..]) {
+                Ok(0) => {
+                    self.buffer.truncate(filled);
+                    if filled == 0 {
+                        return None;
+                    }
+                    self.last = self.buffer.split_to(filled).freeze();
+                    return Some(Ok(&self.last));
+                }
+                Ok(n) => self.buffer.truncate(filled + n),
+                Err(e) => {
+                    self.buffer.truncate(filled);
+                    if !is_retryable(&e, self.deadline) {
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+    }
+
+    fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    fn peek(&mut self) -> io::Result<&[u8]> {
+        while self.buffer.is_empty() {
+            check_deadline(self.deadline)?;
+            self.buffer.resize(self.max_size, 0);
+            match self.inner.read(&mut self.buffer) {
+                Ok(n) => {
+                    self.buffer.truncate(n);
+                    if n == 0 {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    self.buffer.clear();
+                    if !is_retryable(&e, self.deadline) {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(&self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn lines_share_buffer() {
+        let mut lines = std::io::Cursor::new("ab\r\ncd\n").lines_bytes();
+        let first = lines.next().unwrap().unwrap();
+        let second = lines.next().unwrap().unwrap();
+        assert_eq!(&b"ab"[..], first);
+        assert_eq!(&b"cd"[..], second);
+        assert_eq!(first.as_ptr() as usize + 4, second.as_ptr() as usize);
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn splits_long_lines() {
+        let lines = std::io::Cursor::new("1234567\n12")
+            .lines_bytes_with_capacity(5)
+            .collect::<Vec<_>>();
+        assert!(matches!(&lines[0], Err(crate::Error::Incomplete(x)) if x == &b"12345"[..]));
+        assert!(matches!(&lines[1], Err(crate::Error::Incomplete(x)) if x == &b"67"[..]));
+        assert_eq!(&b"12"[..], lines[2].as_ref().unwrap());
+        assert_eq!(3, lines.len());
+    }
+}
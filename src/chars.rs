@@ -0,0 +1,107 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Limits lines to `max_chars` Unicode scalar values, for formats whose limits are specified in characters instead of bytes.
+    /// Longer lines are split into `Incomplete` parts of at most `max_chars` characters, like lines exceeding the capacity are.
+    ///
+    /// The capacity in bytes still bounds the memory, so lines exceeding it are `Incomplete` even if they have fewer characters,
+    /// and their parts are split independently. Choose a capacity of at least `4 * max_chars` bytes to only limit by characters.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{Error, ReadExt};
+    ///
+    /// let mut lines = std::io::Cursor::new("äöü\näöüa").lines_rc().limit_chars(3);
+    /// assert_eq!("äöü", *lines.next().unwrap().unwrap());
+    /// assert!(matches!(lines.next(), Some(Err(Error::Incomplete(x))) if *x == "äöü"));
+    /// assert!(matches!(lines.next(), Some(Err(Error::Incomplete(x))) if *x == "a"));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn limit_chars(self, max_chars: usize) -> CharLimited<T> {
+        assert!(max_chars > 0, "max_chars must be greater than 0");
+        CharLimited {
+            lines: self,
+            max_chars,
+            rest: None,
+        }
+    }
+}
+
+/// Iterator over lines limited to a number of characters, created by `RcLineIterator::limit_chars()`
+pub struct CharLimited<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_chars: usize,
+    /// Line which is split and the byte offset of its next part
+    rest: Option<(Rc<String>, usize)>,
+}
+
+impl<T: LineSource> CharLimited<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Returns the next part of the split line
+    fn next_part(&mut self) -> Option<Rc<String>> {
+        let (line, start) = self.rest.take()?;
+        let rest = &line[start..];
+        let end = rest
+            .char_indices()
+            .nth(self.max_chars)
+            .map_or(rest.len(), |(i, _)| i);
+        let part = Rc::new(rest[..end].to_string());
+        if end < rest.len() {
+            self.rest = Some((line, start + end));
+        }
+        Some(part)
+    }
+}
+
+impl<T: LineSource> Iterator for CharLimited<T> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(part) = self.next_part() {
+            return Some(Err(crate::Error::Incomplete(part)));
+        }
+        let (line, complete) = match self.lines.next()? {
+            Ok(x) => (x, true),
+            Err(crate::Error::Incomplete(x)) => (x, false),
+            Err(e) => return Some(Err(e)),
+        };
+        // Lines with at most `max_chars` bytes can't have more characters
+        let fits = line.len() <= self.max_chars || line.chars().nth(self.max_chars).is_none();
+        match (fits, complete) {
+            (true, true) => Some(Ok(line)),
+            (true, false) => Some(Err(crate::Error::Incomplete(line))),
+            (false, _) => {
+                self.rest = Some((line, 0));
+                self.next_part().map(|x| Err(crate::Error::Incomplete(x)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn splits_parts_of_the_byte_capacity() {
+        let parts = std::io::Cursor::new("abcdefg\nhi")
+            .lines_rc_with_capacity(5)
+            .limit_chars(2)
+            .map(|x| match x {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("{}..", x),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["ab..", "cd..", "e..", "fg..", "hi"], parts);
+    }
+}
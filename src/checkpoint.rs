@@ -0,0 +1,140 @@
+use crate::Stats;
+#[cfg(feature = "std")]
+use {
+    crate::{bound::RcLineIterator, source::BufReadSource, BufReadExt, ReadExt},
+    std::io::{self, BufReader, Read, Seek, SeekFrom},
+};
+
+/// Position within a reader to resume iterating after a crash or restart, returned by `RcLineIterator::checkpoint()`.
+/// If the `serde` feature is enabled, it can be serialized to be persisted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint {
+    pub(crate) stats: Stats,
+    pub(crate) max_size: usize,
+    pub(crate) pending_incomplete: bool,
+    pub(crate) current_length: usize,
+}
+
+impl Checkpoint {
+    /// Number of bytes consumed from the reader since the iteration started.
+    /// The start of a line which was interrupted by an error isn't included, so it is read again.
+    pub fn offset(&self) -> u64 {
+        self.stats.bytes
+    }
+}
+
+/// Seeks to the offset of `checkpoint` and continues iterating from there
+#[cfg(feature = "std")]
+pub(crate) fn resume<T: Read + Seek>(
+    mut reader: T,
+    checkpoint: &Checkpoint,
+) -> io::Result<RcLineIterator<BufReadSource<BufReader<T>>>> {
+    reader.seek(SeekFrom::Start(checkpoint.offset()))?;
+    let mut lines = ReadExt::lines_rc_with_capacity(reader, checkpoint.max_size);
+    lines.restore(checkpoint);
+    Ok(lines)
+}
+
+#[cfg(feature = "std")]
+impl<T: Read + Seek + Clone> RcLineIterator<BufReadSource<BufReader<T>>> {
+    /// Returns an independent iterator over a clone of the reader, which continues after the last returned line like `SeekExt::resume_lines_rc()` does.
+    /// Speculative parsers can consume the fork and only advance this iterator if their interpretation succeeded, e.g. by skipping the lines the fork consumed.
+    ///
+    /// Lines which were peeked or unread are returned by the fork first, so it continues exactly where this iterator does.
+    /// Settings like quotas or deadlines aren't copied.
+    /// Readers like `Cursor<&[u8]>` are cheap to clone, while `Cursor<Vec<u8>>` copies its data.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new(b"BEGIN\n1\nx\nEND\n".as_ref()).lines_rc();
+    /// let mut attempt = lines.fork().unwrap();
+    /// let numbers = attempt.by_ref().skip(1).map_while(|x| x.ok()?.parse::<u32>().ok()).count();
+    /// assert_eq!(1, numbers);
+    /// assert_eq!("BEGIN", *lines.next().unwrap().unwrap());
+    /// assert_eq!(1, lines.stats().lines);
+    /// ```
+    pub fn fork(&self) -> io::Result<Self> {
+        let mut lines =
+            BufReadExt::lines_rc_with_capacity(self.source().fork_reader()?, self.max_size());
+        lines.restore(&self.position());
+        lines.copy_pushed_back(self);
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{ReadExt, SeekExt},
+        std::io::Cursor,
+    };
+
+    const TEXT: &str = "12345678\nab\n";
+
+    #[test]
+    fn resumes_incomplete_line() {
+        let mut lines = Cursor::new(TEXT).lines_rc_with_capacity(5);
+        assert!(matches!(
+            lines.next(),
+            Some(Err(crate::Error::Incomplete(_)))
+        ));
+        let checkpoint = lines.checkpoint().unwrap();
+        let mut resumed = Cursor::new(TEXT).resume_lines_rc(&checkpoint).unwrap();
+        assert!(matches!(resumed.next(), Some(Err(crate::Error::Incomplete(x))) if *x == "678"));
+        assert_eq!("ab", *resumed.next().unwrap().unwrap());
+        lines.by_ref().for_each(drop);
+        assert_eq!(lines.stats(), resumed.stats());
+    }
+
+    #[test]
+    fn forks_within_incomplete_line() {
+        let mut lines = Cursor::new(TEXT.as_bytes()).lines_rc_with_capacity(5);
+        lines.next();
+        let mut fork = lines.fork().unwrap();
+        assert!(matches!(fork.next(), Some(Err(crate::Error::Incomplete(x))) if *x == "678"));
+        assert_eq!("ab", *fork.next().unwrap().unwrap());
+        assert!(fork.next().is_none());
+        assert!(matches!(lines.next(), Some(Err(crate::Error::Incomplete(x))) if *x == "678"));
+        let mut refork = lines.fork().unwrap();
+        lines.by_ref().for_each(drop);
+        refork.by_ref().for_each(drop);
+        assert_eq!(lines.stats(), fork.stats());
+        assert_eq!(lines.stats(), refork.stats());
+    }
+
+    #[test]
+    fn refuses_checkpoint_with_peeked_lines() {
+        let mut lines = Cursor::new(TEXT).lines_rc();
+        lines.peek_line();
+        assert!(lines.checkpoint().is_none());
+        assert_eq!("12345678", *lines.next().unwrap().unwrap());
+        let checkpoint = lines.checkpoint().unwrap();
+        let mut resumed = Cursor::new(TEXT).resume_lines_rc(&checkpoint).unwrap();
+        assert_eq!("ab", *resumed.next().unwrap().unwrap());
+    }
+
+    #[test]
+    fn forks_with_peeked_lines() {
+        let mut lines = Cursor::new(TEXT.as_bytes()).lines_rc();
+        lines.peek_line();
+        let mut fork = lines.fork().unwrap();
+        assert_eq!("12345678", *fork.next().unwrap().unwrap());
+        assert_eq!("ab", *fork.next().unwrap().unwrap());
+        assert!(fork.next().is_none());
+        lines.by_ref().for_each(drop);
+        assert_eq!(lines.stats(), fork.stats());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_checkpoint() {
+        let mut lines = Cursor::new(TEXT).lines_rc_with_capacity(5);
+        lines.next();
+        let checkpoint = lines.checkpoint().unwrap();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert_eq!(checkpoint, serde_json::from_str(&json).unwrap());
+    }
+}
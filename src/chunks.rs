@@ -0,0 +1,67 @@
+use {
+    crate::{bound::BorrowedLines, source::LineSource},
+    alloc::{borrow::ToOwned, string::String, vec::Vec},
+};
+
+/// Item of `LineChunks`, which owns its lines so it can be sent to other threads
+pub type OwnedLine = Result<String, crate::Error<String>>;
+
+/// Iterator over batches of up to n lines, created by `RcLineIterator::chunks_of()`
+pub struct LineChunks<TSource: LineSource> {
+    lines: BorrowedLines<TSource>,
+    size: usize,
+}
+
+impl<T: LineSource> LineChunks<T> {
+    pub(crate) fn new(lines: BorrowedLines<T>, size: usize) -> Self {
+        assert!(size > 0, "Chunk size must be greater than 0");
+        Self { lines, size }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<TSource: LineSource> core::iter::FusedIterator for LineChunks<TSource> {}
+
+impl<TSource: LineSource> Iterator for LineChunks<TSource> {
+    type Item = Vec<OwnedLine>;
+    fn next(&mut self) -> Option<Self::Item> {
+        // Huge sizes are valid, so the chunk grows beyond a bounded preallocation
+        let mut chunk = Vec::with_capacity(self.size.min(1024));
+        while chunk.len() < self.size {
+            match self.lines.next_str() {
+                Some(Ok(x)) => chunk.push(Ok(x.to_owned())),
+                Some(Err(e)) => chunk.push(Err(e.map_incomplete(str::to_owned))),
+                None => break,
+            }
+        }
+        Some(chunk).filter(|x| !x.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn last_chunk_is_shorter() {
+        let chunks = std::io::Cursor::new("a\nb\nc\nd\ne")
+            .lines_rc()
+            .chunks_of(2)
+            .map(|x| x.into_iter().map(Result::unwrap).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]], chunks);
+    }
+
+    #[test]
+    fn huge_size_collects_all_lines() {
+        let mut chunks = std::io::Cursor::new("a\nb")
+            .lines_rc()
+            .chunks_of(usize::MAX);
+        assert_eq!(2, chunks.next().unwrap().len());
+        assert!(chunks.next().is_none());
+    }
+}
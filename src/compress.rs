@@ -0,0 +1,114 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek},
+};
+
+/// Opens a decoder reading the decompressed content of a file
+type Open = fn(File) -> io::Result<Box<dyn Read + Send>>;
+
+/// Magic bytes at the start of compressed files and their decoders. Concatenated streams are decoded like by the command line tools.
+const DECOMPRESSORS: &[(&[u8], Open)] = &[
+    (&[0x1f, 0x8b], |x| {
+        Ok(Box::new(flate2::read::MultiGzDecoder::new(x)))
+    }),
+    (&[0x28, 0xb5, 0x2f, 0xfd], |x| {
+        Ok(Box::new(zstd::stream::read::Decoder::new(x)?))
+    }),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0], |x| {
+        Ok(Box::new(xz2::read::XzDecoder::new_multi_decoder(x)))
+    }),
+    (b"BZh", |x| {
+        Ok(Box::new(bzip2::read::MultiBzDecoder::new(x)))
+    }),
+];
+
+/// Decompressed content of a compressed file
+pub(crate) struct Decompressor(Box<dyn Read + Send>);
+
+impl Decompressor {
+    /// Opens the decoder for the magic bytes at the start of `file`, or returns `file` if it isn't compressed.
+    /// `file` has to be seekable, as it is rewound after sniffing.
+    pub fn open(mut file: File) -> io::Result<Result<Self, File>> {
+        let mut magic = [0; 6];
+        let mut len = 0;
+        while len < magic.len() {
+            match file.read(&mut magic[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        file.rewind()?;
+        let magic = &magic[..len];
+        match DECOMPRESSORS.iter().find(|(x, _)| magic.starts_with(x)) {
+            Some((_, open)) => Ok(Ok(Self(open(file)?))),
+            None => Ok(Err(file)),
+        }
+    }
+}
+
+impl Read for Decompressor {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {crate::lines_from_path, std::io::Write};
+
+    type Encode = fn(&[u8]) -> Vec<u8>;
+
+    #[test]
+    fn decodes_concatenated_streams() {
+        let path =
+            std::env::temp_dir().join(format!("simple_lines_compress_all_{}", std::process::id()));
+        let encoders: Vec<Encode> = vec![
+            |x| {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+                encoder.write_all(x).unwrap();
+                encoder.finish().unwrap()
+            },
+            |x| zstd::encode_all(x, 0).unwrap(),
+            |x| {
+                let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+                encoder.write_all(x).unwrap();
+                encoder.finish().unwrap()
+            },
+            |x| {
+                let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), Default::default());
+                encoder.write_all(x).unwrap();
+                encoder.finish().unwrap()
+            },
+        ];
+        for encode in encoders {
+            let mut content = encode(b"a\nb");
+            content.extend(encode(b"c\n"));
+            std::fs::write(&path, content).unwrap();
+            let lines = lines_from_path(&path)
+                .unwrap()
+                .map(|x| x.unwrap().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(vec!["a", "bc"], lines);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sniffs_magic_bytes() {
+        let path =
+            std::env::temp_dir().join(format!("simple_lines_compress_{}", std::process::id()));
+        let lines = |content: &[u8]| {
+            std::fs::write(&path, content).unwrap();
+            lines_from_path(&path).unwrap().collect::<Vec<_>>()
+        };
+        assert!(matches!(
+            lines(b"\x1f\x8bcorrupt").as_slice(),
+            [Err(crate::Error::Io(_))]
+        ));
+        assert_eq!("BZ", lines(b"BZ").pop().unwrap().unwrap().as_str());
+        assert!(lines(b"").is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
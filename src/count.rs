@@ -0,0 +1,60 @@
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+/// Counts the lines of `reader` as `std::io::BufRead::lines()` would return them, without validating or copying them.
+/// Lines longer than any capacity are counted once and a last line without linebreak is counted too.
+///
+/// # Examples
+/// ```
+/// let lines = simple_lines::count_lines("a\r\nb\n\nc".as_bytes()).unwrap();
+/// assert_eq!(4, lines);
+/// ```
+#[cfg(feature = "std")]
+pub fn count_lines<R: Read>(mut reader: R) -> io::Result<u64> {
+    let mut buffer = vec![0; 64 * 1024];
+    let mut counter = LineCounter::default();
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(counter.finish()),
+            Ok(n) => counter.add(&buffer[0..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LineCounter {
+    lines: u64,
+    unterminated: bool,
+}
+
+impl LineCounter {
+    pub fn add(&mut self, chunk: &[u8]) {
+        if let Some(last) = chunk.last() {
+            self.lines += memchr::memchr_iter(b'\n', chunk).count() as u64;
+            self.unterminated = *last != b'\n';
+        }
+    }
+
+    pub fn finish(self) -> u64 {
+        self.lines + self.unterminated as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::BufRead};
+
+    #[test]
+    fn count_like_bufread() {
+        for input in ["", "\n", "a", "a\n", "a\n\nb", "\r\n\r\n "] {
+            assert_eq!(
+                input.as_bytes().lines().count() as u64,
+                count_lines(input.as_bytes()).unwrap(),
+                "{:?}",
+                input
+            );
+        }
+    }
+}
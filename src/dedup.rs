@@ -0,0 +1,239 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hash, Hasher},
+        rc::Rc,
+    },
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Drops consecutive identical lines like `uniq`. Only the previous line is retained.
+    /// Errors, including `Incomplete` parts, are returned as they are and end a run of identical lines.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("a\na\nb\na").lines_rc().dedup_adjacent();
+    /// let lines = lines.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!("aba", lines.iter().map(|x| x.as_str()).collect::<String>());
+    /// ```
+    pub fn dedup_adjacent(self) -> DedupAdjacent<T> {
+        DedupAdjacent(self.dedup_adjacent_counts())
+    }
+
+    /// Like `dedup_adjacent()`, but yields how often each line was repeated, like `uniq -c`
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\na\nb").lines_rc().dedup_adjacent_counts();
+    /// let (line, count) = lines.next().unwrap().unwrap();
+    /// assert_eq!(("a", 2), (line.as_str(), count));
+    /// ```
+    pub fn dedup_adjacent_counts(self) -> DedupAdjacentCounts<T> {
+        DedupAdjacentCounts {
+            lines: self,
+            previous: None,
+            error: None,
+        }
+    }
+}
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Drops lines which were seen before anywhere in the input, using a Bloom filter of `memory_budget` bytes instead of a set of all lines.
+    /// Each unique line is dropped with a probability of about `false_positive_rate`,
+    /// as long as there are fewer unique lines than `memory_budget * 8 * ln(2) / ceil(-log2(false_positive_rate))`.
+    /// Beyond that, the rate of dropped unique lines grows.
+    ///
+    /// The hashes are randomly seeded for each call, so the dropped lines differ between runs.
+    /// This makes it hard, but not impossible, to craft input whose unique lines collide on purpose, e.g. by probing a long running process.
+    /// Use `dedup_adjacent()` or an exact set if no unique line may be lost.
+    ///
+    /// Errors, including `Incomplete` parts, are returned as they are.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("a\nb\na\nc\nb").lines_rc().dedup_approx(1024, 0.001);
+    /// let lines = lines.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!("abc", lines.iter().map(|x| x.as_str()).collect::<String>());
+    /// ```
+    pub fn dedup_approx(self, memory_budget: usize, false_positive_rate: f64) -> DedupApprox<T> {
+        DedupApprox {
+            lines: self,
+            filter: BloomFilter::new(memory_budget, false_positive_rate),
+        }
+    }
+}
+
+/// Iterator over lines which weren't seen before, created by `RcLineIterator::dedup_approx()`
+pub struct DedupApprox<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    filter: BloomFilter,
+}
+
+impl<T: LineSource> DedupApprox<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for DedupApprox<T> {
+    type Item = Result<Rc<String>, crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next()? {
+                Ok(line) if !self.filter.insert(&line) => {}
+                x => return Some(x),
+            }
+        }
+    }
+}
+
+struct BloomFilter {
+    bits: Vec<u64>,
+    hashes: u32,
+    state: RandomState,
+}
+
+impl BloomFilter {
+    fn new(memory_budget: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false_positive_rate must be between 0 and 1"
+        );
+        Self {
+            bits: vec![0; (memory_budget / 8).max(1)],
+            hashes: (-false_positive_rate.log2()).ceil() as u32,
+            state: RandomState::new(),
+        }
+    }
+
+    /// Returns false if `line` was probably inserted before
+    fn insert(&mut self, line: &str) -> bool {
+        // Double hashing derives all positions from two independent hashes
+        let [first, second] = [0u8, 1].map(|seed| {
+            let mut hasher = self.state.build_hasher();
+            seed.hash(&mut hasher);
+            line.hash(&mut hasher);
+            hasher.finish()
+        });
+        let len = self.bits.len() as u64 * 64;
+        let mut is_new = false;
+        for i in 0..self.hashes as u64 {
+            let bit = first.wrapping_add(i.wrapping_mul(second)) % len;
+            let word = &mut self.bits[(bit / 64) as usize];
+            let mask = 1 << (bit % 64);
+            is_new |= *word & mask == 0;
+            *word |= mask;
+        }
+        is_new
+    }
+}
+
+/// Iterator over lines without consecutive duplicates, created by `RcLineIterator::dedup_adjacent()`
+pub struct DedupAdjacent<TSource: LineSource>(DedupAdjacentCounts<TSource>);
+
+impl<T: LineSource> DedupAdjacent<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.0.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for DedupAdjacent<T> {
+    type Item = Result<Rc<String>, crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next()?.map(|(line, _)| line))
+    }
+}
+
+/// Iterator over lines without consecutive duplicates and their number of repetitions, created by `RcLineIterator::dedup_adjacent_counts()`
+pub struct DedupAdjacentCounts<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    previous: Option<(Rc<String>, u64)>,
+    /// Error which is returned after `previous`
+    error: Option<crate::Error<Rc<String>>>,
+}
+
+impl<T: LineSource> DedupAdjacentCounts<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for DedupAdjacentCounts<T> {
+    type Item = Result<(Rc<String>, u64), crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        loop {
+            match self.lines.next() {
+                Some(Ok(line)) => match &mut self.previous {
+                    Some((previous, count)) if *previous == line => *count += 1,
+                    _ => {
+                        if let Some(previous) = self.previous.replace((line, 1)) {
+                            return Some(Ok(previous));
+                        }
+                    }
+                },
+                Some(Err(e)) => {
+                    return match self.previous.take() {
+                        Some(previous) => {
+                            self.error = Some(e);
+                            Some(Ok(previous))
+                        }
+                        None => Some(Err(e)),
+                    }
+                }
+                None => return self.previous.take().map(Ok),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {crate::ReadExt, std::hash::BuildHasher};
+
+    #[test]
+    fn errors_end_runs() {
+        let input = [b'a', b'\n', b'a', b'\n', 254, b'\n', b'a'];
+        let mut lines = std::io::Cursor::new(input)
+            .lines_rc()
+            .dedup_adjacent_counts();
+        assert!(matches!(lines.next(), Some(Ok((x, 2))) if *x == "a"));
+        assert!(matches!(lines.next(), Some(Err(crate::Error::Encoding(_)))));
+        assert!(matches!(lines.next(), Some(Ok((x, 1))) if *x == "a"));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn dedup_approx_keeps_unique_lines() {
+        let input = (0..1000)
+            .chain(0..1000)
+            .map(|x| format!("{}\n", x))
+            .collect::<String>();
+        let lines = std::io::Cursor::new(input)
+            .lines_rc()
+            .dedup_approx(4096, 0.001)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert!(lines.len() > 990 && lines.len() <= 1000, "{}", lines.len());
+        assert_eq!("0", *lines[0]);
+    }
+
+    #[test]
+    fn dedup_approx_seeds_each_filter() {
+        let [first, second] =
+            [0, 1].map(|_| super::BloomFilter::new(4096, 0.001).state.hash_one("a"));
+        assert_ne!(first, second);
+    }
+}
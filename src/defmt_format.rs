@@ -0,0 +1,72 @@
+use crate::{io::ErrorKind, Error};
+
+/// Formats errors for `defmt` loggers without `core::fmt`. `Io` errors are logged with their kind only.
+impl<T: core::fmt::Debug + defmt::Format> defmt::Format for Error<T> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::Io(e) => defmt::write!(f, "io: {=str}", kind_name(e.kind())),
+            Error::Encoding(e) => defmt::write!(
+                f,
+                "encoding: invalid UTF8 after {=usize} valid bytes",
+                e.valid_up_to()
+            ),
+            Error::Incomplete(x) => defmt::write!(f, "Incomplete line: {}", x),
+            Error::Cancelled => defmt::write!(f, "cancelled"),
+            Error::TimedOut => defmt::write!(f, "timed out"),
+            Error::QuotaExceeded => defmt::write!(f, "quota exceeded"),
+            Error::TooManyLines => defmt::write!(f, "too many lines"),
+            Error::ForbiddenByte { byte, position } => defmt::write!(
+                f,
+                "forbidden byte {=u8:#04x} at position {=usize}",
+                byte,
+                position
+            ),
+            Error::BinaryContent => defmt::write!(f, "binary content"),
+            Error::LineTooLong => defmt::write!(f, "line too long"),
+        }
+    }
+}
+
+/// Returns the name of `kind` like its `Debug` output, but as static string
+fn kind_name(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::NotFound => "NotFound",
+        ErrorKind::PermissionDenied => "PermissionDenied",
+        ErrorKind::ConnectionRefused => "ConnectionRefused",
+        ErrorKind::ConnectionReset => "ConnectionReset",
+        ErrorKind::ConnectionAborted => "ConnectionAborted",
+        ErrorKind::NotConnected => "NotConnected",
+        ErrorKind::AddrInUse => "AddrInUse",
+        ErrorKind::AddrNotAvailable => "AddrNotAvailable",
+        ErrorKind::BrokenPipe => "BrokenPipe",
+        ErrorKind::AlreadyExists => "AlreadyExists",
+        ErrorKind::WouldBlock => "WouldBlock",
+        ErrorKind::InvalidInput => "InvalidInput",
+        ErrorKind::InvalidData => "InvalidData",
+        ErrorKind::TimedOut => "TimedOut",
+        ErrorKind::WriteZero => "WriteZero",
+        ErrorKind::Interrupted => "Interrupted",
+        ErrorKind::Unsupported => "Unsupported",
+        ErrorKind::UnexpectedEof => "UnexpectedEof",
+        ErrorKind::OutOfMemory => "OutOfMemory",
+        _ => "Other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_kinds_like_debug() {
+        for kind in [
+            ErrorKind::NotFound,
+            ErrorKind::UnexpectedEof,
+            ErrorKind::Other,
+        ]
+        .iter()
+        {
+            assert_eq!(format!("{:?}", kind), kind_name(*kind));
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::{collections::VecDeque, rc::Rc},
+};
+
+/// Compares two iterators line by line, like `diff`, while keeping at most `window` lines of each in memory.
+/// Consecutive `Removed` and `Added` items form a hunk.
+///
+/// After a mismatch, the closest pair of equal lines within the next `window` lines of both sides is searched,
+/// and everything in front of it is reported as changed. If there is none, the whole window is reported as changed.
+/// Changes longer than the window are therefore found, but not necessarily minimal.
+///
+/// Errors, including `Incomplete` parts, are never equal to any line and are yielded in place of their `Removed` or `Added` item.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{DiffLine, ReadExt};
+///
+/// let old = std::io::Cursor::new("a\nb\nc").lines_rc();
+/// let new = std::io::Cursor::new("a\nx\nc").lines_rc();
+/// let diff = simple_lines::diff_lines(old, new, 100).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert!(matches!(&diff[1], DiffLine::Removed { old: 1, line } if line.as_str() == "b"));
+/// assert!(matches!(&diff[2], DiffLine::Added { new: 1, line } if line.as_str() == "x"));
+/// assert!(matches!(&diff[3], DiffLine::Unchanged { old: 2, new: 2, .. }));
+/// ```
+pub fn diff_lines<A: LineSource, B: LineSource>(
+    old: RcLineIterator<A>,
+    new: RcLineIterator<B>,
+    window: usize,
+) -> DiffLines<A, B> {
+    assert!(window > 0, "Window size must be greater than 0");
+    DiffLines {
+        old: Side::new(old, window),
+        new: Side::new(new, window),
+        window,
+        removed: 0,
+        added: 0,
+    }
+}
+
+/// Item of `diff_lines()` with the zero-based line numbers in the old and new input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Line contained in both inputs
+    Unchanged {
+        /// Line number in the old input
+        old: u64,
+        /// Line number in the new input
+        new: u64,
+        /// Content of the line
+        line: Rc<String>,
+    },
+    /// Line only contained in the old input
+    Removed {
+        /// Line number in the old input
+        old: u64,
+        /// Content of the line
+        line: Rc<String>,
+    },
+    /// Line only contained in the new input
+    Added {
+        /// Line number in the new input
+        new: u64,
+        /// Content of the line
+        line: Rc<String>,
+    },
+}
+
+/// Iterator over the differences of two iterators, created by `diff_lines()`
+pub struct DiffLines<A: LineSource, B: LineSource> {
+    old: Side<A>,
+    new: Side<B>,
+    window: usize,
+    /// Number of lines at the front of `old` which are known to be removed
+    removed: usize,
+    /// Number of lines at the front of `new` which are known to be added
+    added: usize,
+}
+
+struct Side<T: LineSource> {
+    lines: RcLineIterator<T>,
+    buffer: VecDeque<RcLine>,
+    /// Line number of the first buffered line
+    position: u64,
+}
+
+impl<T: LineSource> Side<T> {
+    fn new(lines: RcLineIterator<T>, window: usize) -> Self {
+        Self {
+            lines,
+            buffer: VecDeque::with_capacity(window),
+            position: 0,
+        }
+    }
+
+    fn fill(&mut self, window: usize) {
+        while self.buffer.len() < window {
+            match self.lines.next() {
+                Some(x) => self.buffer.push_back(x),
+                None => break,
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<(u64, RcLine)> {
+        let line = self.buffer.pop_front()?;
+        self.position += 1;
+        Some((self.position - 1, line))
+    }
+}
+
+fn is_equal(a: &RcLine, b: &RcLine) -> bool {
+    matches!((a, b), (Ok(a), Ok(b)) if a == b)
+}
+
+impl<A: LineSource, B: LineSource> DiffLines<A, B> {
+    /// Returns the totals of all lines consumed from the old and the new input so far
+    pub fn stats(&self) -> (crate::Stats, crate::Stats) {
+        (self.old.lines.stats(), self.new.lines.stats())
+    }
+
+    /// Finds the closest pair of equal lines in both windows
+    fn resync(&mut self) {
+        let (old, new) = (&self.old.buffer, &self.new.buffer);
+        for distance in 1..old.len() + new.len() - 1 {
+            let found = (distance.saturating_sub(new.len() - 1)..=distance.min(old.len() - 1))
+                .map(|removed| (removed, distance - removed))
+                .find(|&(removed, added)| is_equal(&old[removed], &new[added]));
+            if let Some((removed, added)) = found {
+                self.removed = removed;
+                self.added = added;
+                return;
+            }
+        }
+        self.removed = old.len();
+        self.added = new.len();
+    }
+}
+
+impl<A: LineSource, B: LineSource> Iterator for DiffLines<A, B> {
+    type Item = Result<DiffLine, crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.old.fill(self.window);
+        self.new.fill(self.window);
+        if self.removed == 0 && self.added == 0 {
+            match (self.old.buffer.front(), self.new.buffer.front()) {
+                (None, None) => return None,
+                (Some(old), Some(new)) if is_equal(old, new) => {
+                    let (old, line) = self.old.pop()?;
+                    let (new, _) = self.new.pop()?;
+                    return Some(line.map(|line| DiffLine::Unchanged { old, new, line }));
+                }
+                (Some(_), Some(_)) => self.resync(),
+                (old, new) => {
+                    self.removed = old.map_or(0, |_| self.old.buffer.len());
+                    self.added = new.map_or(0, |_| self.new.buffer.len());
+                }
+            }
+        }
+        if self.removed > 0 {
+            self.removed -= 1;
+            let (old, line) = self.old.pop()?;
+            Some(line.map(|line| DiffLine::Removed { old, line }))
+        } else {
+            self.added -= 1;
+            let (new, line) = self.new.pop()?;
+            Some(line.map(|line| DiffLine::Added { new, line }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    fn diff(old: &str, new: &str, window: usize) -> String {
+        let old = std::io::Cursor::new(old.to_owned()).lines_rc();
+        let new = std::io::Cursor::new(new.to_owned()).lines_rc();
+        diff_lines(old, new, window)
+            .map(|x| match x.unwrap() {
+                DiffLine::Unchanged { line, .. } => format!(" {}", line),
+                DiffLine::Removed { line, .. } => format!("-{}", line),
+                DiffLine::Added { line, .. } => format!("+{}", line),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn resyncs_after_changes() {
+        assert_eq!(
+            " a,-b,-c,+x, d,+y, e,-f",
+            diff("a\nb\nc\nd\ne\nf", "a\nx\nd\ny\ne", 10)
+        );
+    }
+
+    #[test]
+    fn reports_changes_longer_than_the_window() {
+        assert_eq!("-a,-b,+x,+y,-c,+z, d", diff("a\nb\nc\nd", "x\ny\nz\nd", 2));
+    }
+}
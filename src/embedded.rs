@@ -0,0 +1,163 @@
+use {
+    crate::io,
+    alloc::{boxed::Box, format, vec},
+};
+
+/// Buffered reader over an `embedded_io::Read`, e.g. a UART or the socket of an embedded TCP stack,
+/// so its lines can be iterated with `BufReadExt::lines_rc()` and all options of `RcLineIterator`.
+/// Errors are returned as `io::Error` of the corresponding kind, with their debug output as message.
+///
+/// It works without the `std` feature. With `std`, it implements `std::io::BufRead` as well.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{BufReadExt, EmbeddedReader};
+///
+/// let uart: &[u8] = b"AT\r\nOK\r\n";
+/// let lines = EmbeddedReader::new(uart).lines_rc_with_capacity(64);
+/// assert_eq!(vec!["AT", "OK"], lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>());
+/// ```
+pub struct EmbeddedReader<R> {
+    inner: R,
+    buffer: Box<[u8]>,
+    position: usize,
+    filled: usize,
+}
+
+impl<R: embedded_io::Read> EmbeddedReader<R> {
+    /// Reads from `inner` with a buffer of 512 bytes
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(512, inner)
+    }
+
+    /// Reads from `inner` with a buffer of `capacity` bytes.
+    /// Lines which are longer than the buffer are copied by `RcLineIterator`, so it doesn't limit their length.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        assert!(capacity > 0, "capacity must be greater than 0");
+        Self {
+            inner,
+            buffer: vec![0; capacity].into_boxed_slice(),
+            position: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns the wrapped reader. Buffered bytes which weren't consumed yet are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn fill(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.filled {
+            self.filled = self.inner.read(&mut self.buffer).map_err(|e| {
+                let kind = embedded_io::Error::kind(&e);
+                io::Error::new(kind.into(), format!("{:?}", e))
+            })?;
+            self.position = 0;
+        }
+        Ok(&self.buffer[self.position..self.filled])
+    }
+
+    fn advance(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.filled);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: embedded_io::Read> std::io::Read for EmbeddedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let available = self.fill()?;
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.advance(amount);
+        Ok(amount)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: embedded_io::Read> std::io::BufRead for EmbeddedReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.fill()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.advance(amount)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<R: embedded_io::Read> crate::source::FillBuf for EmbeddedReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.fill()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.advance(amount)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl From<embedded_io::ErrorKind> for io::ErrorKind {
+    fn from(kind: embedded_io::ErrorKind) -> Self {
+        use embedded_io::ErrorKind as E;
+        match kind {
+            E::NotFound => Self::NotFound,
+            E::PermissionDenied => Self::PermissionDenied,
+            E::ConnectionRefused => Self::ConnectionRefused,
+            E::ConnectionReset => Self::ConnectionReset,
+            E::ConnectionAborted => Self::ConnectionAborted,
+            E::NotConnected => Self::NotConnected,
+            E::AddrInUse => Self::AddrInUse,
+            E::AddrNotAvailable => Self::AddrNotAvailable,
+            E::BrokenPipe => Self::BrokenPipe,
+            E::AlreadyExists => Self::AlreadyExists,
+            E::InvalidInput => Self::InvalidInput,
+            E::InvalidData => Self::InvalidData,
+            E::TimedOut => Self::TimedOut,
+            E::Interrupted => Self::Interrupted,
+            E::Unsupported => Self::Unsupported,
+            E::OutOfMemory => Self::OutOfMemory,
+            E::WriteZero => Self::WriteZero,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::BufReadExt};
+
+    struct Failing;
+
+    impl embedded_io::ErrorType for Failing {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> Result<usize, Self::Error> {
+            Err(embedded_io::ErrorKind::ConnectionReset)
+        }
+    }
+
+    #[test]
+    fn maps_error_kinds() {
+        let mut lines = EmbeddedReader::new(Failing).lines_rc();
+        assert!(matches!(
+            lines.next(),
+            Some(Err(crate::Error::Io(e))) if e.kind() == io::ErrorKind::ConnectionReset
+        ));
+    }
+
+    #[test]
+    fn joins_lines_across_fills() {
+        let reader = EmbeddedReader::with_capacity(3, b"first\nsecond".as_ref());
+        let lines = reader.lines_rc_with_capacity(8);
+        assert_eq!(
+            vec!["first", "second"],
+            lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource, ParseError},
+    std::{ops::Range, rc::Rc},
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Splits each line into the fields separated by `delimiter`, like in CSV or TSV files.
+    /// The fields are ranges of the shared line, so splitting allocates a single `Vec` per line.
+    ///
+    /// Quotes have no special meaning unless `Fields::with_quotes()` is used.
+    /// Incomplete lines are yielded as errors, because their fields can't be determined.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut rows = std::io::Cursor::new("a\tb\n\tc").lines_rc().fields('\t');
+    /// let row = rows.next().unwrap().unwrap();
+    /// assert_eq!(vec!["a", "b"], row.iter().collect::<Vec<_>>());
+    /// assert_eq!(Some("c"), rows.next().unwrap().unwrap().get(1));
+    /// ```
+    pub fn fields(self, delimiter: char) -> Fields<T> {
+        Fields {
+            lines: self,
+            delimiter,
+            quotes: false,
+        }
+    }
+
+    /// Slices each line into the columns at the byte ranges `columns`, like in column-aligned exports of legacy systems.
+    /// The columns are yielded in the order of `columns` and aren't trimmed, so padding remains part of them.
+    ///
+    /// Lines which are shorter than the end of a column or which would be split within a character are rejected.
+    /// Bytes after the last column are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ColumnError, ParseError, ReadExt};
+    ///
+    /// let mut records = std::io::Cursor::new("0042Alice \n0007Bo").lines_rc().fixed_columns(&[0..4, 4..10]);
+    /// let record = records.next().unwrap().unwrap();
+    /// assert_eq!((Some("0042"), Some("Alice ")), (record.get(0), record.get(1)));
+    /// match records.next().unwrap() {
+    ///     Err(ParseError::Parse { line: 1, error: ColumnError::TooShort { length: 6 }, .. }) => {}
+    ///     x => panic!("Expected the short line to be rejected, got {:?}", x),
+    /// }
+    /// ```
+    pub fn fixed_columns(self, columns: &[Range<usize>]) -> FixedColumns<T> {
+        FixedColumns {
+            lines: self,
+            columns: columns.to_vec(),
+            number: 0,
+        }
+    }
+}
+
+/// Iterator over lines split into fields, created by `RcLineIterator::fields()`
+pub struct Fields<T: LineSource> {
+    lines: RcLineIterator<T>,
+    delimiter: char,
+    quotes: bool,
+}
+
+impl<T: LineSource> Fields<T> {
+    /// Fields enclosed in `"` may contain the delimiter, and `""` within them is an escaped quote.
+    /// Quoted fields can't span multiple lines. A quote which isn't closed extends to the end of the line,
+    /// and fields with text after the closing quote are kept unchanged as if they weren't quoted.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut rows = std::io::Cursor::new("1,\"a, \"\"b\"\"\",c").lines_rc().fields(',').with_quotes();
+    /// let row = rows.next().unwrap().unwrap();
+    /// assert_eq!(vec!["1", "a, \"b\"", "c"], row.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn with_quotes(mut self) -> Self {
+        self.quotes = true;
+        self
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    fn split(&self, line: Rc<String>) -> LineFields {
+        let mut spans = Vec::new();
+        if !self.quotes {
+            let mut start = 0;
+            for (i, _) in line.match_indices(self.delimiter) {
+                spans.push(start..i);
+                start = i + self.delimiter.len_utf8();
+            }
+            spans.push(start..line.len());
+            return LineFields { text: line, spans };
+        }
+        if !line.contains("\"\"") {
+            let mut rest = 0;
+            while let Some((span, _, next)) = split_quoted(&line, rest, self.delimiter) {
+                spans.push(span);
+                rest = next;
+            }
+            return LineFields { text: line, spans };
+        }
+        // Escaped quotes are removed, so the fields are copied into a new text
+        let mut text = String::with_capacity(line.len());
+        let mut rest = 0;
+        while let Some((span, quoted, next)) = split_quoted(&line, rest, self.delimiter) {
+            let start = text.len();
+            match quoted {
+                true => text.push_str(&line[span].replace("\"\"", "\"")),
+                false => text.push_str(&line[span]),
+            }
+            spans.push(start..text.len());
+            rest = next;
+        }
+        LineFields {
+            text: Rc::new(text),
+            spans,
+        }
+    }
+}
+
+/// Returns the span of the field starting at `start` without its quotes, whether it was quoted and the start of the next field
+fn split_quoted(line: &str, start: usize, delimiter: char) -> Option<(Range<usize>, bool, usize)> {
+    if start > line.len() {
+        return None;
+    }
+    let field_end = |from: usize| match line[from..].find(delimiter) {
+        Some(i) => (from + i, from + i + delimiter.len_utf8()),
+        None => (line.len(), line.len() + 1),
+    };
+    if let Some(quoted) = line[start..].strip_prefix('"') {
+        let mut end = 0;
+        // Skip pairs of quotes, which are escaped quotes
+        while let Some(i) = quoted[end..].find('"') {
+            end += i + 1;
+            if quoted[end..].starts_with('"') {
+                end += 1;
+                continue;
+            }
+            let after = start + 1 + end;
+            if after == line.len() || line[after..].starts_with(delimiter) {
+                return Some((start + 1..after - 1, true, after + delimiter.len_utf8()));
+            }
+            // Text after the closing quote makes the field unquoted, so nothing is lost
+            let (field, next) = field_end(after);
+            return Some((start..field, false, next));
+        }
+        return Some((start + 1..line.len(), true, line.len() + 1));
+    }
+    let (field, next) = field_end(start);
+    Some((start..field, false, next))
+}
+
+impl<T: LineSource> Iterator for Fields<T> {
+    type Item = Result<LineFields, crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        Some(line.map(|x| self.split(x)))
+    }
+}
+
+/// Iterator over lines sliced into columns, created by `RcLineIterator::fixed_columns()`
+pub struct FixedColumns<T: LineSource> {
+    lines: RcLineIterator<T>,
+    columns: Vec<Range<usize>>,
+    /// Number of the next line
+    number: u64,
+}
+
+impl<T: LineSource> FixedColumns<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    fn slice(&self, line: &str) -> Result<Vec<Range<usize>>, ColumnError> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(column, x)| {
+                if x.end > line.len() {
+                    Err(ColumnError::TooShort { length: line.len() })
+                } else if x.start > x.end || line.get(x.clone()).is_none() {
+                    Err(ColumnError::NotCharBoundary { column })
+                } else {
+                    Ok(x.clone())
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T: LineSource> Iterator for FixedColumns<T> {
+    type Item = Result<LineFields, ParseError<ColumnError>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        let number = self.number;
+        if !self.lines.continues_line() {
+            self.number += 1;
+        }
+        let text = match line {
+            Ok(x) => x,
+            Err(e) => return Some(Err(ParseError::Read(e))),
+        };
+        Some(match self.slice(&text) {
+            Ok(spans) => Ok(LineFields { text, spans }),
+            Err(error) => Err(ParseError::Parse {
+                line: number,
+                content: text,
+                error,
+            }),
+        })
+    }
+}
+
+/// Reason why `RcLineIterator::fixed_columns()` rejected a line
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ColumnError {
+    /// The line ends before the end of a column
+    #[error("line of length {length} too short")]
+    TooShort {
+        /// Length of the line in bytes
+        length: usize,
+    },
+    /// A column starts or ends within a multibyte character
+    #[error("column {column} not on a character boundary")]
+    NotCharBoundary {
+        /// Index of the column
+        column: usize,
+    },
+}
+
+/// Fields of a line, created by `Fields` or `FixedColumns`
+#[derive(Debug, Clone)]
+pub struct LineFields {
+    text: Rc<String>,
+    spans: Vec<Range<usize>>,
+}
+
+impl LineFields {
+    /// Returns the field at `index`
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.spans.get(index).map(|x| &self.text[x.clone()])
+    }
+
+    /// Returns the number of fields, which is at least 1 even for empty lines
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Always false, because even an empty line has an empty field
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Iterates all fields in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.spans.iter().map(move |x| &self.text[x.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    fn split(line: &str, quotes: bool) -> Vec<String> {
+        let fields = std::io::Cursor::new(line.to_string())
+            .lines_rc()
+            .fields(';');
+        let mut fields = match quotes {
+            true => fields.with_quotes(),
+            false => fields,
+        };
+        let row = fields.next().unwrap().unwrap();
+        row.iter().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn splits_empty_and_quoted_fields() {
+        assert_eq!(vec!["", "\"a", "b\"", ""], split(";\"a;b\";", false));
+        assert_eq!(vec!["", "a;b", ""], split(";\"a;b\";", true));
+        assert_eq!(vec!["a\"", "b"], split("\"a\"\"\";b", true));
+        assert_eq!(vec!["\"a\"b", "c"], split("\"a\"b;c", true));
+        assert_eq!(vec!["a\"b;c"], split("\"a\"\"b;c", true));
+        assert_eq!(vec!["a;b"], split("\"a;b", true));
+        assert_eq!(vec![""], split("\n", true));
+    }
+
+    #[test]
+    fn rejects_columns_within_characters() {
+        let mut records = std::io::Cursor::new("aäb\näb")
+            .lines_rc()
+            .fixed_columns(&[0..2, 2..3]);
+        assert!(matches!(
+            records.next(),
+            Some(Err(crate::ParseError::Parse {
+                line: 0,
+                error: super::ColumnError::NotCharBoundary { column: 0 },
+                ..
+            }))
+        ));
+        assert_eq!(Some("b"), records.next().unwrap().unwrap().get(1));
+    }
+}
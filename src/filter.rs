@@ -0,0 +1,124 @@
+use crate::{
+    bound::{RcLine, RcLineIterator},
+    source::LineSource,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Skips lines starting with one of `prefixes` after leading whitespace, e.g. `#` or `;` in config files.
+    /// The remaining lines are yielded with their zero-based line number for error messages.
+    ///
+    /// `Incomplete` parts are skipped along with the first part of their line. Other errors are always yielded.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let config = std::io::Cursor::new("# comment\n\n  ; other\nkey=value").lines_rc();
+    /// let mut lines = config.skip_comments(&["#", ";"]).skip_blank();
+    /// let (number, line) = lines.next().unwrap();
+    /// assert_eq!((3, "key=value"), (number, line.unwrap().as_str()));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn skip_comments(self, prefixes: &[&str]) -> FilteredLines<T> {
+        FilteredLines {
+            lines: self,
+            number: 0,
+            skipping: None,
+            comment_prefixes: Vec::new(),
+            skip_blank: false,
+        }
+        .skip_comments(prefixes)
+    }
+
+    /// Skips lines which are empty or contain whitespace only.
+    /// The remaining lines are yielded with their zero-based line number like in `skip_comments()`.
+    pub fn skip_blank(self) -> FilteredLines<T> {
+        self.skip_comments(&[]).skip_blank()
+    }
+}
+
+/// Iterator over lines without comments or blank lines and their zero-based line numbers,
+/// created by `RcLineIterator::skip_comments()` and `RcLineIterator::skip_blank()`
+pub struct FilteredLines<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    /// Number of the next line
+    number: u64,
+    /// Whether the following `Incomplete` parts of the current line are skipped
+    skipping: Option<bool>,
+    comment_prefixes: Vec<String>,
+    skip_blank: bool,
+}
+
+impl<T: LineSource> FilteredLines<T> {
+    /// Additionally skips lines starting with one of `prefixes`, see `RcLineIterator::skip_comments()`
+    pub fn skip_comments(mut self, prefixes: &[&str]) -> Self {
+        self.comment_prefixes
+            .extend(prefixes.iter().map(|x| x.to_string()));
+        self
+    }
+
+    /// Additionally skips blank lines, see `RcLineIterator::skip_blank()`
+    pub fn skip_blank(mut self) -> Self {
+        self.skip_blank = true;
+        self
+    }
+
+    /// Returns the totals of all lines consumed so far, including the skipped ones
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    fn is_skipped(&self, line: &str) -> bool {
+        let line = line.trim_start();
+        (self.skip_blank && line.is_empty())
+            || self
+                .comment_prefixes
+                .iter()
+                .any(|x| line.starts_with(x.as_str()))
+    }
+}
+
+impl<T: LineSource> Iterator for FilteredLines<T> {
+    type Item = (u64, RcLine);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            let number = self.number;
+            let skip = match (self.skipping.take(), &line) {
+                (Some(skip), Ok(_) | Err(crate::Error::Incomplete(_))) => skip,
+                (None, Ok(x) | Err(crate::Error::Incomplete(x))) => self.is_skipped(x),
+                _ => false,
+            };
+            if self.lines.continues_line() {
+                self.skipping = Some(skip);
+            } else {
+                self.number += 1;
+            }
+            if !skip {
+                return Some((number, line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn skips_incomplete_parts_of_comments() {
+        let lines = std::io::Cursor::new("#abcdef\nabcdef\n \t\nx")
+            .lines_rc_with_capacity(4)
+            .skip_comments(&["#"])
+            .skip_blank();
+        assert_eq!(
+            vec![(1, "abcd".to_string()), (1, "ef".into()), (3, "x".into())],
+            lines
+                .map(|(n, x)| match x {
+                    Ok(x) | Err(crate::Error::Incomplete(x)) => (n, x.to_string()),
+                    Err(e) => panic!("{}", e),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use {
+    crate::{
+        bound::BorrowedLines,
+        source::{check_deadline, is_retryable, LineSource},
+    },
+    std::{
+        io::{self, Read},
+        time::Instant,
+    },
+};
+
+/// Line reader whose buffer is an inline array of `N` bytes, so reading lines doesn't allocate at all.
+/// Lines are borrowed from the buffer and at most `N` bytes long. Longer lines are split into `Incomplete` parts like in `RcLineIterator`.
+///
+/// As the lines borrow the reader, it can't implement `Iterator`. Use `next_line()` in a `while let` loop instead.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{Error, FixedLineReader};
+///
+/// let mut reader = FixedLineReader::<_, 4>::new(std::io::Cursor::new("boot\nok\n"));
+/// assert!(matches!(reader.next_line(), Some(Err(Error::Incomplete("boot")))));
+/// assert!(matches!(reader.next_line(), Some(Err(Error::Incomplete("")))));
+/// assert!(matches!(reader.next_line(), Some(Ok("ok"))));
+/// assert!(reader.next_line().is_none());
+/// ```
+pub struct FixedLineReader<R: Read, const N: usize> {
+    lines: BorrowedLines<FixedSource<R, N>>,
+}
+
+impl<R: Read, const N: usize> FixedLineReader<R, N> {
+    /// Creates a reader whose lines are at most `N` bytes long
+    pub fn new(inner: R) -> Self {
+        assert!(N > 0, "N must be greater than 0");
+        let source = FixedSource {
+            inner,
+            buffer: [0; N],
+            start: 0,
+            end: 0,
+            deadline: None,
+        };
+        Self {
+            lines: BorrowedLines::new(source, N),
+        }
+    }
+
+    /// Returns the next line, which is borrowed from the buffer until the next call
+    pub fn next_line(&mut self) -> Option<Result<&str, crate::Error<&str>>> {
+        self.lines.next_str()
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+/// Splits lines from an array, moving the start of the next line to its front before refilling
+struct FixedSource<R: Read, const N: usize> {
+    inner: R,
+    buffer: [u8; N],
+    /// Start of the buffered bytes which weren't returned yet
+    start: usize,
+    end: usize,
+    deadline: Option<Instant>,
+}
+
+impl<R: Read, const N: usize> FixedSource<R, N> {
+    /// Reads once into the free end of the buffer. Returns 0 at the end of the reader.
+    fn fill(&mut self) -> io::Result<usize> {
+        if self.start > 0 {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        loop {
+            check_deadline(self.deadline)?;
+            match self.inner.read(&mut self.buffer[self.end..]) {
+                Ok(n) => {
+                    self.end += n;
+                    return Ok(n);
+                }
+                Err(e) if is_retryable(&e, self.deadline) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: Read, const N: usize> LineSource for FixedSource<R, N> {
+    fn next_line(&mut self) -> Option<io::Result<&[u8]>> {
+        loop {
+            let window = &self.buffer[self.start..self.end];
+            let len = match memchr::memchr(b'\n', window) {
+                Some(pos) => Some(pos + 1),
+                None if window.len() == N => Some(N),
+                None => None,
+            };
+            if let Some(len) = len {
+                self.start += len;
+                return Some(Ok(&self.buffer[self.start - len..self.start]));
+            }
+            match self.fill() {
+                Ok(0) if self.start == self.end => return None,
+                Ok(0) => {
+                    let start = std::mem::replace(&mut self.start, self.end);
+                    return Some(Ok(&self.buffer[start..self.end]));
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    fn peek(&mut self) -> io::Result<&[u8]> {
+        if self.start == self.end {
+            self.fill()?;
+        }
+        Ok(&self.buffer[self.start..self.end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refills_around_partial_lines() {
+        let input = "ab\ncdefg\r\nh".as_bytes().chunks(2).map(|x| x.to_vec());
+        let reader = input.fold(Box::new(io::empty()) as Box<dyn Read>, |x, chunk| {
+            Box::new(x.chain(io::Cursor::new(chunk)))
+        });
+        let mut lines = FixedLineReader::<_, 4>::new(reader);
+        let mut read = Vec::new();
+        while let Some(line) = lines.next_line() {
+            read.push(match line {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("{}..", x),
+                Err(e) => panic!("{}", e),
+            });
+        }
+        assert_eq!(vec!["ab", "cdef..", "g..", "h"], read);
+        assert_eq!(4, lines.stats().lines);
+    }
+}
@@ -0,0 +1,232 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator, SharedBuffers},
+        source::LineSource,
+    },
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Joins lines ending with `continuation` with the next line into one logical line, like `\` in Makefiles and shell scripts.
+    /// The continuation character is removed and nothing is inserted in its place.
+    ///
+    /// Logical lines longer than the capacity of this iterator are split into `Incomplete` parts.
+    /// Errors, including `Incomplete` parts of physical lines, end the current logical line,
+    /// which is yielded as `Incomplete` in front of them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("all: a \\\n  b\nclean:").lines_rc().fold_continuations('\\');
+    /// assert_eq!("all: a   b", *lines.next().unwrap().unwrap());
+    /// assert_eq!("clean:", *lines.next().unwrap().unwrap());
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn fold_continuations(self, continuation: char) -> FoldedLines<T> {
+        let max_size = self.max_size();
+        self.fold(Folding::Continuation(continuation), max_size)
+    }
+
+    /// Joins the lines of a quoted-printable body (RFC 2045) which end with a soft linebreak `=` into one logical line.
+    /// The `=` and whitespace after it are removed, but escape sequences like `=3D` aren't decoded.
+    ///
+    /// Logical lines longer than the capacity of this iterator are split into `Incomplete` parts, and errors end a line like in `fold_continuations()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let body = "a long line which is wr=\r\napped =3D=  \r\nend\r\n";
+    /// let mut lines = std::io::Cursor::new(body).lines_rc().join_soft_breaks();
+    /// assert_eq!("a long line which is wrapped =3Dend", *lines.next().unwrap().unwrap());
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn join_soft_breaks(self) -> FoldedLines<T> {
+        let max_size = self.max_size();
+        self.fold(Folding::SoftBreaks, max_size)
+    }
+
+    /// Unfolds header fields as defined by RFC 5322, by joining lines starting with a space or tab to the previous line.
+    /// Their linebreak is removed, but the whitespace is kept. Unfolding ends at the first empty line, which separates the headers from the body.
+    ///
+    /// Fields longer than `max_header_size` are split into `Incomplete` parts in front of the line exceeding it.
+    /// Errors end a field like in `fold_continuations()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mail = "Subject: a\r\n long subject\r\nTo: b\r\n\r\n body";
+    /// let lines = std::io::Cursor::new(mail).lines_rc().unfold_headers(998);
+    /// let lines = lines.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(vec!["Subject: a long subject", "To: b", "", " body"], lines.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+    /// ```
+    pub fn unfold_headers(self, max_header_size: usize) -> FoldedLines<T> {
+        self.fold(Folding::Headers { ended: false }, max_header_size)
+    }
+
+    fn fold(self, folding: Folding, max_size: usize) -> FoldedLines<T> {
+        FoldedLines {
+            lines: self,
+            buffers: SharedBuffers::default(),
+            folding,
+            max_size,
+            joined: String::new(),
+            joining: false,
+            overflowed: false,
+            pending: None,
+        }
+    }
+}
+
+/// Rule which decides whether a line is continued by the next one
+enum Folding {
+    /// Lines ending with the character are continued
+    Continuation(char),
+    /// Lines ending with `=` and optional whitespace are continued
+    SoftBreaks,
+    /// Lines followed by one starting with whitespace are continued, until the first empty line
+    Headers { ended: bool },
+}
+
+/// Iterator over logical lines joined from multiple lines, created by `RcLineIterator::fold_continuations()`, `RcLineIterator::join_soft_breaks()`
+/// and `RcLineIterator::unfold_headers()`
+pub struct FoldedLines<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    buffers: SharedBuffers,
+    folding: Folding,
+    max_size: usize,
+    /// Logical line which was joined so far
+    joined: String,
+    /// Whether the last line was continued
+    joining: bool,
+    /// Whether parts of the current logical line were yielded as `Incomplete` already
+    overflowed: bool,
+    /// Item which is yielded after the current one
+    pending: Option<RcLine>,
+}
+
+impl<T: LineSource> FoldedLines<T> {
+    /// Returns the totals of all physical lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Yields the joined logical line, which is `Incomplete` if its former parts were yielded already
+    fn flush(&mut self) -> RcLine {
+        let line = self.buffers.share(&self.joined);
+        self.joined.clear();
+        self.joining = false;
+        match std::mem::take(&mut self.overflowed) {
+            true => Err(crate::Error::Incomplete(line)),
+            false => Ok(line),
+        }
+    }
+
+    /// Returns the length of `line` without its continuation marker and whether it is continued
+    fn split(&mut self, line: &str) -> (usize, bool) {
+        match &mut self.folding {
+            Folding::Continuation(continuation) => match line.strip_suffix(*continuation) {
+                Some(x) => (x.len(), true),
+                None => (line.len(), false),
+            },
+            Folding::SoftBreaks => match line.trim_end_matches([' ', '\t']).strip_suffix('=') {
+                Some(x) => (x.len(), true),
+                None => (line.len(), false),
+            },
+            Folding::Headers { ended: true } => (line.len(), false),
+            Folding::Headers { ended } if line.is_empty() => {
+                *ended = true;
+                (0, false)
+            }
+            Folding::Headers { .. } => {
+                let is_folded = |x: &Rc<String>| x.starts_with([' ', '\t']);
+                let continues = matches!(self.lines.peek_line(), Some(Ok(x)) if is_folded(x));
+                (line.len(), continues)
+            }
+        }
+    }
+
+    /// Appends `text` to the logical line and returns the next item, if there is one
+    fn join(&mut self, text: &str, continues: bool) -> Option<RcLine> {
+        if !self.joined.is_empty() && self.joined.len() + text.len() > self.max_size {
+            let part = self.buffers.share(&self.joined);
+            self.joined.clear();
+            self.joined.push_str(text);
+            self.overflowed = true;
+            if !continues {
+                self.pending = Some(self.flush());
+            }
+            return Some(Err(crate::Error::Incomplete(part)));
+        }
+        self.joined.push_str(text);
+        self.joining = true;
+        match continues {
+            true => None,
+            false => Some(self.flush()),
+        }
+    }
+}
+
+impl<T: LineSource> Iterator for FoldedLines<T> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(line) = self.pending.take() {
+            return Some(line);
+        }
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(x)) => x,
+                Some(Err(e)) if self.joining => {
+                    self.overflowed = true;
+                    self.pending = Some(Err(e));
+                    return Some(self.flush());
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None if self.joining => return Some(self.flush()),
+                None => return None,
+            };
+            let (length, continues) = self.split(&line);
+            if !self.joining && !continues {
+                return Some(Ok(line));
+            }
+            if let Some(x) = self.join(&line[0..length], continues) {
+                return Some(x);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn splits_logical_lines_at_capacity() {
+        let lines = std::io::Cursor::new("ab\\\ncd\\\nef\ng\\")
+            .lines_rc_with_capacity(4)
+            .fold_continuations('\\')
+            .map(|x| match x {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("~{}", x),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["~abcd", "~ef", "g"], lines);
+    }
+
+    #[test]
+    fn unfolding_stops_at_body() {
+        let lines = std::io::Cursor::new("A: 1\n 2\n\t3\nB: 4567\n 89\n\n 10")
+            .lines_rc()
+            .unfold_headers(8)
+            .map(|x| match x {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("~{}", x),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["A: 1 2\t3", "~B: 4567", "~ 89", "", " 10"], lines);
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fs::{File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Reader which waits for new data at the end of its input instead of returning EOF, like `tail -f`.
+/// Lines which are only partially written are completed as soon as the rest is appended.
+///
+/// The iterator never ends, so consumers have to break out of the loop on their own, pass a token to `Follow::with_cancellation()`
+/// or let the iterator's own options interrupt the wait with `Follow::with_would_block()`.
+///
+/// # Examples
+/// ```no_run
+/// use simple_lines::{Follow, ReadExt};
+///
+/// let file = std::fs::File::open("/var/log/syslog").unwrap();
+/// for line in Follow::new(file).lines_rc() {
+///     println!("{}", line.unwrap());
+/// }
+/// ```
+pub struct Follow<TRead: Read> {
+    inner: TRead,
+    poll_interval: Duration,
+    position: u64,
+    rotation: Option<RotationCheck<TRead>>,
+    on_rotation: Option<Box<dyn FnMut(Rotation) + Send>>,
+    cancellation: Option<Arc<AtomicBool>>,
+    would_block: bool,
+}
+
+/// Reopens or rewinds the reader if the followed file was rotated, given the position reached in it
+type RotationCheck<T> = Box<dyn FnMut(&mut T, u64) -> io::Result<Option<Rotation>> + Send>;
+
+/// How the followed file was rotated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// The file got shorter than the position reached, so it is read from the start again
+    Truncated,
+    /// The path refers to a new file (e.g. after `logrotate` renamed the old one), which is opened and read from the start
+    Replaced,
+}
+
+impl<T: Read> Follow<T> {
+    /// Follows `inner`, checking for new data every 100ms at its end
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            poll_interval: Duration::from_millis(100),
+            position: 0,
+            rotation: None,
+            on_rotation: None,
+            cancellation: None,
+            would_block: false,
+        }
+    }
+
+    /// Changes how long to wait at the end of the input before reading again
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Stops waiting and ends the input as soon as `token` is set, which is checked every poll interval.
+    /// The iterator ends like at the end of a file, so a partially written last line is returned as is.
+    ///
+    /// # Examples
+    /// ```
+    /// use {
+    ///     simple_lines::{Follow, ReadExt},
+    ///     std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration},
+    /// };
+    ///
+    /// let token = Arc::new(AtomicBool::new(false));
+    /// let lines = Follow::new(std::io::Cursor::new("a\nb"))
+    ///     .with_poll_interval(Duration::from_millis(1))
+    ///     .with_cancellation(token.clone())
+    ///     .lines_rc();
+    /// let stop = std::thread::spawn(move || {
+    ///     std::thread::sleep(Duration::from_millis(10));
+    ///     token.store(true, Ordering::Relaxed);
+    /// });
+    /// assert_eq!(2, lines.count());
+    /// stop.join().unwrap();
+    /// ```
+    pub fn with_cancellation(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Returns `io::ErrorKind::WouldBlock` after each poll interval without new data instead of waiting in `read()`,
+    /// so `RcLineIterator::with_read_timeout()` can end the wait with `Error::TimedOut` and `RcLineIterator::try_next_line()` returns `Poll::Pending`.
+    /// Without either of them, the `WouldBlock` errors are returned by the iterator.
+    pub fn with_would_block(mut self) -> Self {
+        self.would_block = true;
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|x| x.load(Ordering::Relaxed))
+    }
+
+    /// Calls `callback` whenever the followed file was rotated. Only files opened by `Follow::open()` detect rotations.
+    pub fn on_rotation(mut self, callback: impl FnMut(Rotation) + Send + 'static) -> Self {
+        self.on_rotation = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Follow<File> {
+    /// Follows the file at `path` across rotations, like `tail -F`. At the end of the file, it is reopened if `path` refers to a new file.
+    /// If the file got shorter, it is read from the start again. Truncations are missed if the file grows beyond the position reached until then.
+    ///
+    /// Detecting a new file requires Unix, other platforms only detect truncations.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use simple_lines::{Follow, ReadExt};
+    ///
+    /// let follow = Follow::open("/var/log/syslog")
+    ///     .unwrap()
+    ///     .on_rotation(|rotation| eprintln!("log rotated: {:?}", rotation));
+    /// for line in follow.lines_rc() {
+    ///     println!("{}", line.unwrap());
+    /// }
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let file = File::open(&path)?;
+        let mut identity = file_identity(&file.metadata()?);
+        let check = move |file: &mut File, position: u64| {
+            let metadata = match std::fs::metadata(&path) {
+                // The new file isn't created yet
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                x => x?,
+            };
+            if file_identity(&metadata) != identity {
+                *file = File::open(&path)?;
+                identity = file_identity(&file.metadata()?);
+                Ok(Some(Rotation::Replaced))
+            } else if metadata.len() < position {
+                file.seek(SeekFrom::Start(0))?;
+                Ok(Some(Rotation::Truncated))
+            } else {
+                Ok(None)
+            }
+        };
+        let mut follow = Self::new(file);
+        follow.rotation = Some(Box::new(check));
+        Ok(follow)
+    }
+}
+
+#[cfg(unix)]
+fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+impl<T: Read> Read for Follow<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.inner.read(buf)? {
+                0 if !buf.is_empty() => {
+                    let rotation = match &mut self.rotation {
+                        Some(check) => check(&mut self.inner, self.position)?,
+                        None => None,
+                    };
+                    match rotation {
+                        Some(rotation) => {
+                            self.position = 0;
+                            if let Some(callback) = &mut self.on_rotation {
+                                callback(rotation);
+                            }
+                        }
+                        None if self.is_cancelled() => return Ok(0),
+                        None => {
+                            std::thread::sleep(self.poll_interval);
+                            if self.would_block {
+                                return Err(io::ErrorKind::WouldBlock.into());
+                            }
+                        }
+                    }
+                }
+                n => {
+                    self.position += n as u64;
+                    return Ok(n);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    /// Returns the chunks in order, where an empty chunk means EOF
+    struct Growing(Vec<&'static [u8]>);
+
+    impl Read for Growing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let chunk = self.0.remove(0);
+            buf[0..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn completes_partial_line_after_eof() {
+        let file = Growing(vec![b"ab", b"", b"", b"c\nd", b"", b"\n"]);
+        let mut lines = Follow::new(file)
+            .with_poll_interval(Duration::from_millis(1))
+            .lines_rc();
+        assert_eq!("abc", *lines.next().unwrap().unwrap());
+        assert_eq!("d", *lines.next().unwrap().unwrap());
+    }
+
+    #[test]
+    fn read_timeout_interrupts_wait() {
+        let mut chunks = vec![&b""[..]; 100];
+        chunks[0] = b"ab";
+        let file = Growing(chunks);
+        let mut lines = Follow::new(file)
+            .with_poll_interval(Duration::from_millis(1))
+            .with_would_block()
+            .lines_rc()
+            .with_read_timeout(Duration::from_millis(20));
+        assert!(matches!(lines.next(), Some(Err(crate::Error::TimedOut))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reopens_rotated_file() {
+        use std::{
+            io::Write,
+            sync::{Arc, Mutex},
+        };
+
+        let path = std::env::temp_dir().join(format!("simple_lines_follow_{}", std::process::id()));
+        let rotated = path.with_extension("1");
+        std::fs::write(&path, "a\n").unwrap();
+        let rotations = Arc::new(Mutex::new(Vec::new()));
+        let reported = rotations.clone();
+        let mut lines = Follow::open(&path)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(1))
+            .on_rotation(move |x| reported.lock().unwrap().push(x))
+            .lines_rc();
+        assert_eq!("a", *lines.next().unwrap().unwrap());
+
+        std::fs::rename(&path, &rotated).unwrap();
+        std::fs::write(&path, "bbb\n").unwrap();
+        assert_eq!("bbb", *lines.next().unwrap().unwrap());
+
+        File::create(&path).unwrap().write_all(b"c\n").unwrap();
+        assert_eq!("c", *lines.next().unwrap().unwrap());
+        assert_eq!(
+            vec![Rotation::Replaced, Rotation::Truncated],
+            *rotations.lock().unwrap()
+        );
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+}
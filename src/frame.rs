@@ -0,0 +1,418 @@
+use std::{
+    io::{self, BufRead},
+    rc::Rc,
+};
+
+/// Error of a frame, which ends the iteration because the following frames can't be found anymore
+#[derive(thiserror::Error, Debug)]
+pub enum FrameError {
+    /// Forwarded errors from the underlying reader
+    #[error("io")]
+    Io(#[from] io::Error),
+    /// The length of the frame isn't a valid number
+    #[error("invalid length")]
+    InvalidLength,
+    /// The payload is longer than the maximum
+    #[error("frame of {length} bytes too large")]
+    TooLarge {
+        /// Announced length of the payload
+        length: u64,
+    },
+    /// The payload isn't followed by the expected terminator
+    #[error("missing terminator")]
+    MissingTerminator,
+    /// The reader ended within a frame
+    #[error("unexpected end of frame")]
+    UnexpectedEof,
+}
+
+/// Iterates the payloads of netstrings like `5:hello,`, which are at most `max_payload` bytes long.
+/// Longer payloads are rejected before they are read.
+///
+/// `Netstrings::into_inner()` returns the reader positioned after the last frame, e.g. to continue with `BufReadExt::lines_rc()` on the same socket.
+///
+/// # Examples
+/// ```
+/// use simple_lines::BufReadExt;
+///
+/// let mut frames = simple_lines::netstrings(std::io::Cursor::new("5:hello,0:,rest\n"), 1024);
+/// assert_eq!(b"hello", &frames.next().unwrap().unwrap()[..]);
+/// assert_eq!(b"", &frames.next().unwrap().unwrap()[..]);
+///
+/// let mut lines = frames.into_inner().lines_rc();
+/// assert_eq!("rest", *lines.next().unwrap().unwrap());
+/// ```
+pub fn netstrings<B: BufRead>(reader: B, max_payload: usize) -> Netstrings<B> {
+    Netstrings {
+        reader,
+        max_payload,
+        finished: false,
+    }
+}
+
+/// Iterator over the payloads of netstrings, created by `netstrings()`
+pub struct Netstrings<B: BufRead> {
+    reader: B,
+    max_payload: usize,
+    finished: bool,
+}
+
+impl<B: BufRead> Netstrings<B> {
+    /// Returns the reader positioned after the last returned frame
+    pub fn into_inner(self) -> B {
+        self.reader
+    }
+
+    fn read_frame(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let length = read_decimal(&mut self.reader, b':')?;
+        if length > self.max_payload as u64 {
+            return Err(FrameError::TooLarge { length });
+        }
+        let payload = read_payload(&mut self.reader, length as usize)?;
+        match read_byte(&mut self.reader)? {
+            b',' => Ok(Some(payload)),
+            _ => Err(FrameError::MissingTerminator),
+        }
+    }
+}
+
+impl<B: BufRead> Iterator for Netstrings<B> {
+    type Item = Result<Vec<u8>, FrameError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let frame = self.read_frame().transpose();
+        self.finished = !matches!(frame, Some(Ok(_)));
+        frame
+    }
+}
+
+/// Encoding of the length in front of each record of `length_prefixed()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// 2 bytes, most significant first
+    U16Be,
+    /// 2 bytes, least significant first
+    U16Le,
+    /// 4 bytes, most significant first
+    U32Be,
+    /// 4 bytes, least significant first
+    U32Le,
+    /// Unsigned LEB128 as used by Protocol Buffers, with 7 bits per byte and the least significant group first
+    Varint,
+}
+
+/// Iterates records which are prefixed by their length encoded as `prefix`, and which are at most `max_record` bytes long.
+/// Longer records are rejected before they are read.
+///
+/// The returned `Rc<Vec<u8>>` is reused for the next record, if the consumer doesn't keep a reference to it.
+///
+/// # Examples
+/// ```
+/// use simple_lines::LengthPrefix;
+///
+/// let input: &[u8] = b"\x00\x02hi\x00\x00";
+/// let records = simple_lines::length_prefixed(input, LengthPrefix::U16Be, 1024);
+/// let records = records.collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec![&b"hi"[..], b""], records.iter().map(|x| &x[..]).collect::<Vec<_>>());
+/// ```
+pub fn length_prefixed<B: BufRead>(
+    reader: B,
+    prefix: LengthPrefix,
+    max_record: usize,
+) -> LengthPrefixed<B> {
+    LengthPrefixed {
+        reader,
+        prefix,
+        max_record,
+        current: Rc::default(),
+        finished: false,
+    }
+}
+
+/// Iterator over length-prefixed records, created by `length_prefixed()`
+pub struct LengthPrefixed<B: BufRead> {
+    reader: B,
+    prefix: LengthPrefix,
+    max_record: usize,
+    current: Rc<Vec<u8>>,
+    finished: bool,
+}
+
+impl<B: BufRead> LengthPrefixed<B> {
+    /// Returns the reader positioned after the last returned record
+    pub fn into_inner(self) -> B {
+        self.reader
+    }
+
+    fn read_length(&mut self) -> Result<u64, FrameError> {
+        let reader = &mut self.reader;
+        Ok(match self.prefix {
+            LengthPrefix::U16Be => u16::from_be_bytes(read_array(reader)?) as u64,
+            LengthPrefix::U16Le => u16::from_le_bytes(read_array(reader)?) as u64,
+            LengthPrefix::U32Be => u32::from_be_bytes(read_array(reader)?) as u64,
+            LengthPrefix::U32Le => u32::from_le_bytes(read_array(reader)?) as u64,
+            LengthPrefix::Varint => {
+                let mut value = 0;
+                for shift in (0..64).step_by(7) {
+                    let byte = read_byte(reader)?;
+                    if shift == 63 && byte > 1 {
+                        break;
+                    }
+                    value |= ((byte & 0x7f) as u64) << shift;
+                    if byte & 0x80 == 0 {
+                        return Ok(value);
+                    }
+                }
+                return Err(FrameError::InvalidLength);
+            }
+        })
+    }
+
+    fn read_record(&mut self) -> Result<Option<Rc<Vec<u8>>>, FrameError> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let length = self.read_length()?;
+        if length > self.max_record as u64 {
+            return Err(FrameError::TooLarge { length });
+        }
+        let buffer = unique_buffer(&mut self.current);
+        buffer.resize(length as usize, 0);
+        read_exact(&mut self.reader, buffer)?;
+        Ok(Some(self.current.clone()))
+    }
+}
+
+impl<B: BufRead> Iterator for LengthPrefixed<B> {
+    type Item = Result<Rc<Vec<u8>>, FrameError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let record = self.read_record().transpose();
+        self.finished = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+/// Framing of syslog messages over TCP according to RFC 6587, detected by `syslog_frames()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFraming {
+    /// Each message is prefixed by its length in decimal and a space
+    OctetCounting,
+    /// Each message is terminated by LF
+    NonTransparent,
+}
+
+/// Iterates syslog messages received over TCP, which are at most `max_message` bytes long.
+/// The framing is detected from the first byte of the connection: A digit starts an octet counted frame, anything else a LF terminated one.
+///
+/// Octet counted messages longer than `max_message` are rejected before they are read.
+/// LF terminated ones are rejected as soon as `max_message` bytes were read without finding the LF.
+/// The returned `Rc<Vec<u8>>` is reused for the next message, if the consumer doesn't keep a reference to it.
+///
+/// # Examples
+/// ```
+/// use simple_lines::SyslogFraming;
+///
+/// let mut frames = simple_lines::syslog_frames(&b"9 <34>1 a\nb7 <34>1 c"[..], 1024);
+/// assert_eq!(b"<34>1 a\nb", &frames.next().unwrap().unwrap()[..]);
+/// assert_eq!(Some(SyslogFraming::OctetCounting), frames.framing());
+///
+/// let mut frames = simple_lines::syslog_frames(&b"<34>1 a\n<34>1 b"[..], 1024);
+/// assert_eq!(b"<34>1 a", &frames.next().unwrap().unwrap()[..]);
+/// assert_eq!(b"<34>1 b", &frames.next().unwrap().unwrap()[..]);
+/// assert_eq!(Some(SyslogFraming::NonTransparent), frames.framing());
+/// ```
+pub fn syslog_frames<B: BufRead>(reader: B, max_message: usize) -> SyslogFrames<B> {
+    SyslogFrames {
+        reader,
+        max_message,
+        framing: None,
+        current: Rc::default(),
+        finished: false,
+    }
+}
+
+/// Iterator over syslog messages, created by `syslog_frames()`
+pub struct SyslogFrames<B: BufRead> {
+    reader: B,
+    max_message: usize,
+    framing: Option<SyslogFraming>,
+    current: Rc<Vec<u8>>,
+    finished: bool,
+}
+
+impl<B: BufRead> SyslogFrames<B> {
+    /// Returns the framing of the connection, once the first byte was read
+    pub fn framing(&self) -> Option<SyslogFraming> {
+        self.framing
+    }
+
+    /// Returns the reader positioned after the last returned message
+    pub fn into_inner(self) -> B {
+        self.reader
+    }
+
+    fn read_message(&mut self) -> Result<Option<Rc<Vec<u8>>>, FrameError> {
+        let first = match self.reader.fill_buf()?.first() {
+            Some(x) => *x,
+            None => return Ok(None),
+        };
+        let framing = *self.framing.get_or_insert(match first {
+            b'0'..=b'9' => SyslogFraming::OctetCounting,
+            _ => SyslogFraming::NonTransparent,
+        });
+        let buffer = unique_buffer(&mut self.current);
+        match framing {
+            SyslogFraming::OctetCounting => {
+                let length = read_decimal(&mut self.reader, b' ')?;
+                if length == 0 {
+                    return Err(FrameError::InvalidLength);
+                } else if length > self.max_message as u64 {
+                    return Err(FrameError::TooLarge { length });
+                }
+                buffer.resize(length as usize, 0);
+                read_exact(&mut self.reader, buffer)?;
+            }
+            SyslogFraming::NonTransparent => loop {
+                let available = self.reader.fill_buf()?;
+                let (length, found) = match memchr::memchr(b'\n', available) {
+                    Some(position) => (position, true),
+                    None => (available.len(), false),
+                };
+                if buffer.len() + length > self.max_message {
+                    let length = (buffer.len() + length) as u64;
+                    return Err(FrameError::TooLarge { length });
+                }
+                buffer.extend_from_slice(&available[0..length]);
+                self.reader.consume(length + found as usize);
+                if found || length == 0 {
+                    break;
+                }
+            },
+        }
+        Ok(Some(self.current.clone()))
+    }
+}
+
+impl<B: BufRead> Iterator for SyslogFrames<B> {
+    type Item = Result<Rc<Vec<u8>>, FrameError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let message = self.read_message().transpose();
+        self.finished = !matches!(message, Some(Ok(_)));
+        message
+    }
+}
+
+/// Returns the buffer of `current` cleared, which is replaced if it is still referenced by the consumer
+fn unique_buffer(current: &mut Rc<Vec<u8>>) -> &mut Vec<u8> {
+    if Rc::get_mut(current).is_none() {
+        *current = Rc::default();
+    }
+    let buffer = Rc::get_mut(current).unwrap();
+    buffer.clear();
+    buffer
+}
+
+/// Reads a decimal number without leading zeros up to `delimiter`, which is consumed too
+fn read_decimal(reader: &mut impl BufRead, delimiter: u8) -> Result<u64, FrameError> {
+    let mut value: u64 = 0;
+    let mut digits = 0;
+    loop {
+        match read_byte(reader)? {
+            x if x == delimiter && digits > 0 => return Ok(value),
+            x @ b'0'..=b'9' if !(digits == 1 && value == 0) => {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add((x - b'0') as u64))
+                    .ok_or(FrameError::InvalidLength)?;
+                digits += 1;
+            }
+            _ => return Err(FrameError::InvalidLength),
+        }
+    }
+}
+
+fn read_byte(reader: &mut impl BufRead) -> Result<u8, FrameError> {
+    let mut byte = [0];
+    read_exact(reader, &mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_array<const N: usize>(reader: &mut impl BufRead) -> Result<[u8; N], FrameError> {
+    let mut bytes = [0; N];
+    read_exact(reader, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_payload(reader: &mut impl BufRead, length: usize) -> Result<Vec<u8>, FrameError> {
+    let mut payload = vec![0; length];
+    read_exact(reader, &mut payload)?;
+    Ok(payload)
+}
+
+fn read_exact(reader: &mut impl BufRead, buffer: &mut [u8]) -> Result<(), FrameError> {
+    reader.read_exact(buffer).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => FrameError::UnexpectedEof,
+        _ => e.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_malformed_frames() {
+        let first_error = |input: &str| {
+            netstrings(input.as_bytes(), 5)
+                .find_map(Result::err)
+                .map(|x| x.to_string())
+        };
+        assert_eq!(None, first_error("3:abc,5:hello,"));
+        assert_eq!(
+            Some("frame of 6 bytes too large".into()),
+            first_error("6:hello!,")
+        );
+        assert_eq!(Some("invalid length".into()), first_error("03:abc,"));
+        assert_eq!(Some("invalid length".into()), first_error(":abc,"));
+        assert_eq!(Some("missing terminator".into()), first_error("2:abc,"));
+        assert_eq!(Some("unexpected end of frame".into()), first_error("3:ab"));
+    }
+
+    #[test]
+    fn reads_varint_prefixes() {
+        let mut input = vec![0xac, 0x02];
+        input.extend(std::iter::repeat_n(b'x', 300));
+        input.extend([0x01, b'y', 0x80]);
+        let mut records = length_prefixed(&input[..], LengthPrefix::Varint, 300);
+        assert_eq!(300, records.next().unwrap().unwrap().len());
+        assert_eq!(b"y", &records.next().unwrap().unwrap()[..]);
+        assert!(matches!(
+            records.next(),
+            Some(Err(FrameError::UnexpectedEof))
+        ));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn rejects_long_lf_terminated_messages_early() {
+        let input = "a".repeat(100);
+        let reader = std::io::BufReader::with_capacity(8, input.as_bytes());
+        let mut frames = syslog_frames(reader, 20);
+        assert!(matches!(
+            frames.next(),
+            Some(Err(FrameError::TooLarge { length: 24 }))
+        ));
+        assert!(frames.next().is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Moves the last grapheme cluster of full parts to the next part
+#[derive(Default)]
+pub(crate) struct GraphemeJoin {
+    /// Bytes moved from the previous part
+    held: Vec<u8>,
+    /// Held bytes followed by the current part
+    joined: Vec<u8>,
+}
+
+impl GraphemeJoin {
+    /// Returns `line` preceded by the held bytes. If it is full, its last cluster is held back for the next part.
+    pub fn regroup<'a>(&'a mut self, line: &'a [u8], is_full: bool) -> &'a [u8] {
+        let Self { held, joined } = self;
+        let line = if held.is_empty() {
+            line
+        } else {
+            joined.clear();
+            joined.extend_from_slice(held);
+            joined.extend_from_slice(line);
+            held.clear();
+            joined.as_slice()
+        };
+        if !is_full {
+            return line;
+        }
+        // A char cut at the end belongs to the last cluster, or starts a new one if the part is a single cluster
+        let valid = match std::str::from_utf8(line) {
+            Ok(x) => x,
+            Err(e) if e.error_len().is_none() => {
+                std::str::from_utf8(&line[..e.valid_up_to()]).unwrap_or_default()
+            }
+            Err(_) => return line,
+        };
+        let cut = match valid.grapheme_indices(true).next_back() {
+            Some((0, _)) | None if valid.len() < line.len() => valid.len(),
+            Some((i, _)) => i,
+            None => 0,
+        };
+        if cut == 0 {
+            return line;
+        }
+        held.extend_from_slice(&line[cut..]);
+        &line[..cut]
+    }
+
+    /// Returns the bytes held back from the last part of the input
+    pub fn take_held(&mut self) -> Option<&[u8]> {
+        if self.held.is_empty() {
+            return None;
+        }
+        std::mem::swap(&mut self.held, &mut self.joined);
+        self.held.clear();
+        Some(&self.joined)
+    }
+
+    /// Drops the bytes held back from a skipped part
+    pub fn clear(&mut self) {
+        self.held.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    fn parts(input: &str, capacity: usize) -> Vec<String> {
+        std::io::Cursor::new(input.to_string())
+            .lines_rc_with_capacity(capacity)
+            .with_grapheme_boundaries()
+            .map(|x| match x {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("~{}", x),
+                Err(e) => format!("{:?}", e),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_clusters_within_parts() {
+        assert_eq!(vec!["~ab", "~e\u{301}fg", "h"], parts("abe\u{301}fg\nh", 4));
+        assert_eq!(vec!["~한", "~국"], parts("한국", 4));
+        assert_eq!(vec!["~aaa", "~aaa"], parts("aaaaaa", 4));
+        assert_eq!(
+            vec!["~e\u{301}\u{301}", "~\u{301}"],
+            parts("e\u{301}\u{301}\u{301}", 5)
+        );
+    }
+}
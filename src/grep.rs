@@ -0,0 +1,241 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    memchr::memmem,
+    std::{collections::VecDeque, rc::Rc},
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields only lines containing `pattern`, like `grep -F`, together with their zero-based line number.
+    ///
+    /// `Incomplete` parts are searched one by one and yielded if they match, so matches across their boundaries are missed.
+    /// Other errors are always yielded.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("info: a\nerror: b\ninfo: c").lines_rc().matching("error");
+    /// let (number, line) = lines.next().unwrap();
+    /// assert_eq!((1, "error: b"), (number, line.unwrap().as_str()));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn matching(self, pattern: &str) -> MatchingLines<T> {
+        self.matching_by(Pattern::Substring(Box::new(
+            memmem::Finder::new(pattern.as_bytes()).into_owned(),
+        )))
+    }
+
+    /// Like `matching()`, but yields lines matching the regular expression `regex`
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let regex = regex::Regex::new("^[0-9]+$").unwrap();
+    /// let mut lines = std::io::Cursor::new("a1\n42\n").lines_rc().matching_regex(regex);
+    /// let (number, line) = lines.next().unwrap();
+    /// assert_eq!((1, "42"), (number, line.unwrap().as_str()));
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn matching_regex(self, regex: regex::Regex) -> MatchingLines<T> {
+        self.matching_by(Pattern::Regex(regex))
+    }
+
+    fn matching_by(self, pattern: Pattern) -> MatchingLines<T> {
+        MatchingLines {
+            lines: self,
+            pattern,
+            number: 0,
+        }
+    }
+}
+
+enum Pattern {
+    Substring(Box<memmem::Finder<'static>>),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Pattern {
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Pattern::Substring(x) => x.find(line.as_bytes()).is_some(),
+            #[cfg(feature = "regex")]
+            Pattern::Regex(x) => x.is_match(line),
+        }
+    }
+}
+
+/// Iterator over matching lines and their zero-based line numbers, created by `RcLineIterator::matching()`
+pub struct MatchingLines<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    pattern: Pattern,
+    /// Number of the next line
+    number: u64,
+}
+
+impl<T: LineSource> MatchingLines<T> {
+    /// Returns the totals of all lines consumed so far, including the ones which didn't match
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Also yields up to `before` lines in front of and `after` lines behind each match, like `grep -B before -A after`.
+    /// Only the last `before` lines are buffered. Lines are yielded once, even if they are in the context of multiple matches.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("a\nb\nerror\nc\nd").lines_rc().matching("error").with_context(1, 1);
+    /// let lines = lines.map(|x| (x.number, x.is_match)).collect::<Vec<_>>();
+    /// assert_eq!(vec![(1, false), (2, true), (3, false)], lines);
+    /// ```
+    pub fn with_context(self, before: usize, after: usize) -> ContextLines<T> {
+        ContextLines {
+            matching: self,
+            ring: VecDeque::with_capacity(before),
+            pending: VecDeque::new(),
+            before: before as u64,
+            after,
+            after_remaining: 0,
+        }
+    }
+
+    /// Returns the next item with its line number and whether it matched
+    fn next_item(&mut self) -> Option<ContextLine> {
+        let line = self.lines.next()?;
+        let number = self.number;
+        if !self.lines.continues_line() {
+            self.number += 1;
+        }
+        let is_match = match &line {
+            Ok(x) | Err(crate::Error::Incomplete(x)) => self.pattern.is_match(x),
+            Err(_) => true,
+        };
+        Some(ContextLine {
+            number,
+            line,
+            is_match,
+        })
+    }
+}
+
+impl<T: LineSource> Iterator for MatchingLines<T> {
+    type Item = (u64, RcLine);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.next_item()?;
+            if item.is_match {
+                return Some((item.number, item.line));
+            }
+        }
+    }
+}
+
+/// Item of `ContextLines`
+#[derive(Debug)]
+pub struct ContextLine {
+    /// Zero-based line number
+    pub number: u64,
+    /// Content of the line
+    pub line: Result<Rc<String>, crate::Error<Rc<String>>>,
+    /// Whether the line matched or is only part of the context of a match
+    pub is_match: bool,
+}
+
+/// Iterator over matching lines and the lines around them, created by `MatchingLines::with_context()`.
+/// Non-adjacent groups can be separated by comparing the line numbers, like `grep` prints `--` between them.
+pub struct ContextLines<TSource: LineSource> {
+    matching: MatchingLines<TSource>,
+    /// Non-matching lines which may become context of the next match
+    ring: VecDeque<ContextLine>,
+    /// Context lines and the match, which are yielded next
+    pending: VecDeque<ContextLine>,
+    before: u64,
+    after: usize,
+    /// Number of lines which are yielded as context of the last match
+    after_remaining: usize,
+}
+
+impl<T: LineSource> ContextLines<T> {
+    /// Returns the totals of all lines consumed so far, including the ones which weren't yielded
+    pub fn stats(&self) -> crate::Stats {
+        self.matching.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for ContextLines<T> {
+    type Item = ContextLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+            let item = self.matching.next_item()?;
+            if item.is_match {
+                self.after_remaining = self.after;
+                self.pending.append(&mut self.ring);
+                self.pending.push_back(item);
+                continue;
+            }
+            if self.after_remaining > 0 {
+                if !self.matching.lines.continues_line() {
+                    self.after_remaining -= 1;
+                }
+                return Some(item);
+            }
+            let number = item.number;
+            self.ring.push_back(item);
+            while self
+                .ring
+                .front()
+                .is_some_and(|x| x.number + self.before <= number)
+            {
+                self.ring.pop_front();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn numbers_incomplete_parts_by_their_line() {
+        let lines = std::io::Cursor::new("abcdxy\nx\nxyz")
+            .lines_rc_with_capacity(4)
+            .matching("x");
+        assert_eq!(
+            vec![(0, "xy".to_string()), (1, "x".into()), (2, "xyz".into())],
+            lines
+                .map(|(n, x)| match x {
+                    Ok(x) | Err(crate::Error::Incomplete(x)) => (n, x.to_string()),
+                    Err(e) => panic!("{}", e),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn yields_overlapping_context_once() {
+        let input = "a\nx1\nb\nc\nx2\nd\ne\nf\ng\nx3";
+        let lines = std::io::Cursor::new(input)
+            .lines_rc()
+            .matching("x")
+            .with_context(2, 1);
+        assert_eq!(
+            vec!["a", "X1", "b", "c", "X2", "d", "f", "g", "X3"],
+            lines
+                .map(|x| match x.is_match {
+                    true => x.line.unwrap().to_uppercase(),
+                    false => x.line.unwrap().to_string(),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use {
+    crate::BufReadExt,
+    std::io::{self, BufRead},
+};
+
+/// Limits of `read_header_block()`
+#[derive(Debug, Clone)]
+pub struct HeaderLimits {
+    max_line_length: usize,
+    max_headers: usize,
+    max_total_bytes: u64,
+}
+
+impl Default for HeaderLimits {
+    fn default() -> Self {
+        Self {
+            max_line_length: 8 * 1024,
+            max_headers: 100,
+            max_total_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl HeaderLimits {
+    /// Maximum length of a line without CRLF, including the request or status line. Defaults to 8kb.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Maximum number of lines in front of the blank line, including the request or status line. Defaults to 100.
+    pub fn with_max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = max_headers;
+        self
+    }
+
+    /// Maximum number of bytes of the whole block including all CRLFs. Defaults to 64kb.
+    pub fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+}
+
+/// Violation found by `read_header_block()`. Line numbers are zero-based and count the request or status line too.
+#[derive(thiserror::Error, Debug)]
+pub enum HeaderError {
+    /// Forwarded errors from the underlying reader
+    #[error("io")]
+    Io(#[from] io::Error),
+    /// The reader ended before the blank line
+    #[error("unexpected end of header block")]
+    UnexpectedEof,
+    /// A line contains invalid UTF8
+    #[error("encoding in line {line}")]
+    Encoding {
+        /// Number of the line
+        line: usize,
+    },
+    /// A line is longer than `HeaderLimits::with_max_line_length()`
+    #[error("line {line} too long")]
+    LineTooLong {
+        /// Number of the line
+        line: usize,
+    },
+    /// A line ends with a bare LF instead of CRLF, which can be used to smuggle requests past proxies
+    #[error("bare LF in line {line}")]
+    BareLineFeed {
+        /// Number of the line
+        line: usize,
+    },
+    /// A line contains a CR outside of its CRLF, which RFC 9112 forbids like a bare LF
+    #[error("bare CR in line {line}")]
+    BareCarriageReturn {
+        /// Number of the line
+        line: usize,
+    },
+    /// A line starts with whitespace, which is obsolete line folding rejected by RFC 9112
+    #[error("obsolete line folding in line {line}")]
+    ObsoleteFolding {
+        /// Number of the line
+        line: usize,
+    },
+    /// There are more lines than allowed by `HeaderLimits::with_max_headers()`
+    #[error("too many headers")]
+    TooManyHeaders,
+    /// The block is larger than allowed by `HeaderLimits::with_max_total_bytes()`
+    #[error("header block too large")]
+    TooLarge,
+}
+
+/// Reads the lines of an HTTP/1.1 header block up to the blank line, rejecting everything which violates `limits` or RFC 9112.
+/// The request or status line is returned as the first line. The blank line isn't returned.
+///
+/// `reader` is positioned after the blank line afterwards, so the body can be read from it.
+/// At most `max_line_length` bytes are buffered per line, so limits are enforced before the offending line is read completely.
+///
+/// # Examples
+/// ```
+/// use {simple_lines::HeaderLimits, std::io::Read};
+///
+/// let mut request = std::io::Cursor::new("GET / HTTP/1.1\r\nHost: a\r\n\r\nbody");
+/// let lines = simple_lines::read_header_block(&mut request, &HeaderLimits::default()).unwrap();
+/// assert_eq!(vec!["GET / HTTP/1.1", "Host: a"], lines);
+///
+/// let mut body = String::new();
+/// request.read_to_string(&mut body).unwrap();
+/// assert_eq!("body", body);
+///
+/// let mut smuggled = std::io::Cursor::new("GET / HTTP/1.1\r\nHost: a\n\r\n");
+/// let error = simple_lines::read_header_block(&mut smuggled, &HeaderLimits::default()).unwrap_err();
+/// assert!(matches!(error, simple_lines::HeaderError::BareLineFeed { line: 1 }));
+/// ```
+pub fn read_header_block<B: BufRead>(
+    reader: &mut B,
+    limits: &HeaderLimits,
+) -> Result<Vec<String>, HeaderError> {
+    let mut lines = BufReadExt::lines_rc_with_capacity(reader, limits.max_line_length + 2)
+        .with_max_lines(limits.max_headers as u64 + 1)
+        .with_max_total_bytes(limits.max_total_bytes);
+    let mut result = Vec::new();
+    let mut consumed = 0;
+    loop {
+        let number = result.len();
+        let line = match lines.next() {
+            Some(Ok(x)) => x,
+            Some(Err(e)) => {
+                return Err(match e {
+                    crate::Error::Io(e) => HeaderError::Io(e),
+                    crate::Error::Encoding(_) => HeaderError::Encoding { line: number },
+                    crate::Error::TooManyLines => HeaderError::TooManyHeaders,
+                    crate::Error::QuotaExceeded => HeaderError::TooLarge,
+                    _ => HeaderError::LineTooLong { line: number },
+                })
+            }
+            None => return Err(HeaderError::UnexpectedEof),
+        };
+        let bytes = lines.stats().bytes;
+        match bytes - std::mem::replace(&mut consumed, bytes) - line.len() as u64 {
+            2 => {}
+            1 => return Err(HeaderError::BareLineFeed { line: number }),
+            _ => return Err(HeaderError::UnexpectedEof),
+        }
+        if line.is_empty() {
+            break;
+        } else if line.len() > limits.max_line_length {
+            return Err(HeaderError::LineTooLong { line: number });
+        } else if line.starts_with([' ', '\t']) {
+            return Err(HeaderError::ObsoleteFolding { line: number });
+        } else if line.contains('\r') {
+            return Err(HeaderError::BareCarriageReturn { line: number });
+        }
+        result.push(line.to_string());
+    }
+    lines.into_source().into_inner();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str, limits: HeaderLimits) -> Result<Vec<String>, HeaderError> {
+        read_header_block(&mut input.as_bytes(), &limits)
+    }
+
+    #[test]
+    fn reports_violated_limit() {
+        let input = "GET / HTTP/1.1\r\nA: 1\r\nB: 22\r\n\r\n";
+        let limits = HeaderLimits::default;
+        assert!(read(input, limits()).is_ok());
+        assert!(matches!(
+            read(input, limits().with_max_line_length(4)),
+            Err(HeaderError::LineTooLong { line: 0 })
+        ));
+        assert!(matches!(
+            read(input, limits().with_max_headers(2)),
+            Err(HeaderError::TooManyHeaders)
+        ));
+        assert!(matches!(
+            read(input, limits().with_max_total_bytes(30)),
+            Err(HeaderError::TooLarge)
+        ));
+        assert!(matches!(
+            read("GET / HTTP/1.1\r\nA: 1\r\n", limits()),
+            Err(HeaderError::UnexpectedEof)
+        ));
+        assert!(matches!(
+            read("GET / HTTP/1.1\r\nA: 1\r\n 2\r\n\r\n", limits()),
+            Err(HeaderError::ObsoleteFolding { line: 2 })
+        ));
+    }
+    #[test]
+    fn rejects_bare_carriage_returns() {
+        for input in ["Host: a\rb\r\n", "Host: a\r\r\n"] {
+            let input = format!("GET / HTTP/1.1\r\n{}\r\n", input);
+            assert!(matches!(
+                read(&input, HeaderLimits::default()),
+                Err(HeaderError::BareCarriageReturn { line: 1 })
+            ));
+        }
+    }
+}
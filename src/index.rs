@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use {
+    crate::{bound::RcLine, ReadExt},
+    std::{
+        io::{self, Read, Seek, SeekFrom},
+        ops::Range,
+    },
+};
+
+/// Start offsets of every n-th line, recorded by `RcLineIterator::with_line_index()`.
+/// `Incomplete` parts belong to the line they were cut from, so only the first part of a line starts a new one.
+/// Offsets are relative to where the iteration started, like `Stats::bytes`.
+///
+/// If the `serde` feature is enabled, it can be serialized to be persisted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineIndex {
+    every: u64,
+    lines: u64,
+    offsets: Vec<u64>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(every: u64) -> Self {
+        assert!(every > 0, "Index interval must be greater than 0");
+        Self {
+            every,
+            lines: 0,
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Records the chunk at `offset`, which starts a new line unless it `continues_line`
+    pub(crate) fn record(&mut self, offset: u64, continues_line: bool) {
+        if continues_line {
+            return;
+        }
+        if self.lines.is_multiple_of(self.every) {
+            self.offsets.push(offset);
+        }
+        self.lines += 1;
+    }
+
+    /// Number of lines seen so far
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    /// Interval of the indexed lines
+    pub fn every(&self) -> u64 {
+        self.every
+    }
+
+    /// Returns the start offset of `line`, if it is indexed
+    pub fn offset(&self, line: u64) -> Option<u64> {
+        if line.is_multiple_of(self.every) {
+            self.offsets.get((line / self.every) as usize).copied()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the last indexed line at or before `line` with its start offset.
+    /// Reading from there and skipping the lines in between reaches `line`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("a\nbb\nccc\ndddd\n").lines_rc().with_line_index(2);
+    /// lines.by_ref().for_each(drop);
+    /// let index = lines.line_index().unwrap();
+    /// assert_eq!(Some((2, 5)), index.nearest(3));
+    /// assert_eq!(None, index.nearest(4));
+    /// ```
+    pub fn nearest(&self, line: u64) -> Option<(u64, u64)> {
+        if line >= self.lines {
+            return None;
+        }
+        let position = line / self.every;
+        Some((position * self.every, self.offsets[position as usize]))
+    }
+
+    /// Returns the last indexed line starting at or before `offset` with its start offset
+    pub fn line_at(&self, offset: u64) -> Option<(u64, u64)> {
+        let position = self
+            .offsets
+            .partition_point(|x| *x <= offset)
+            .checked_sub(1)?;
+        Some((position as u64 * self.every, self.offsets[position]))
+    }
+}
+
+/// Random access to the lines of a seekable reader, e.g. to serve pages of a large file.
+/// Lines are read from the nearest indexed line, so memory and time per access are bounded by the index interval.
+///
+/// # Examples
+/// ```
+/// use simple_lines::IndexedLines;
+///
+/// let mut lines = IndexedLines::new(std::io::Cursor::new("a\nb\nc\nd\n"), 2).unwrap();
+/// assert_eq!(4, lines.len());
+/// assert_eq!("d", *lines.get_line(3).unwrap().unwrap());
+/// let page = lines.range(1..3).into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec!["b", "c"], page.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+/// ```
+#[cfg(feature = "std")]
+pub struct IndexedLines<TRead: Read + Seek> {
+    reader: TRead,
+    index: LineIndex,
+    max_size: usize,
+}
+
+#[cfg(feature = "std")]
+impl<T: Read + Seek> IndexedLines<T> {
+    /// Indexes every `every`-th line of `reader` in one pass, with lines of at most 64kb
+    pub fn new(reader: T, every: u64) -> io::Result<Self> {
+        Self::with_capacity(reader, every, 64 * 1024)
+    }
+
+    /// Indexes every `every`-th line of `reader` in one pass, with lines of at most `max_line_length` bytes.
+    /// Only `Error::Io` aborts indexing, other errors are indexed as lines.
+    pub fn with_capacity(mut reader: T, every: u64, max_line_length: usize) -> io::Result<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut lines =
+            ReadExt::lines_rc_with_capacity(&mut reader, max_line_length).with_line_index(every);
+        for line in lines.by_ref() {
+            if let Err(crate::Error::Io(e)) = line {
+                return Err(e);
+            }
+        }
+        let index = lines.into_line_index().expect("Index is enabled");
+        Ok(Self::from_index(reader, index, max_line_length))
+    }
+
+    /// Uses an `index` which was recorded from the start of `reader` before, e.g. by `RcLineIterator::with_line_index()`
+    pub fn from_index(reader: T, index: LineIndex, max_line_length: usize) -> Self {
+        Self {
+            reader,
+            index,
+            max_size: max_line_length,
+        }
+    }
+
+    /// Returns the index, e.g. to persist it
+    pub fn index(&self) -> &LineIndex {
+        &self.index
+    }
+
+    /// Number of indexed lines
+    pub fn len(&self) -> u64 {
+        self.index.lines()
+    }
+
+    /// Returns true if the reader contained no lines
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the first item of `line`, which is `Incomplete` if the line is longer than the capacity
+    pub fn get_line(&mut self, line: u64) -> Option<RcLine> {
+        let (start, offset) = self.index.nearest(line)?;
+        if let Err(e) = self.reader.seek(SeekFrom::Start(offset)) {
+            return Some(Err(e.into()));
+        }
+        let mut iter = ReadExt::lines_rc_with_capacity(&mut self.reader, self.max_size);
+        match iter.skip_lines(line - start) {
+            true => iter.next(),
+            false => None,
+        }
+    }
+
+    /// Returns the items of `lines`, including all `Incomplete` parts
+    pub fn range(&mut self, lines: Range<u64>) -> Vec<RcLine> {
+        let (start, offset) = match self.index.nearest(lines.start) {
+            Some(x) if !lines.is_empty() => x,
+            _ => return Vec::new(),
+        };
+        if let Err(e) = self.reader.seek(SeekFrom::Start(offset)) {
+            return vec![Err(e.into())];
+        }
+        let mut iter = ReadExt::lines_rc_with_capacity(&mut self.reader, self.max_size);
+        let mut result = Vec::new();
+        if iter.skip_lines(lines.start - start) {
+            let mut remaining = lines.end.min(self.index.lines()) - lines.start;
+            while remaining > 0 {
+                let Some(item) = iter.next() else { break };
+                result.push(item);
+                if !iter.continues_line() {
+                    remaining -= 1;
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn indexes_lines_instead_of_parts() {
+        let mut lines = std::io::Cursor::new("1234567\nab\n\ncd")
+            .lines_rc_with_capacity(5)
+            .with_line_index(1);
+        lines.by_ref().for_each(drop);
+        let index = lines.line_index().unwrap();
+        assert_eq!(4, index.lines());
+        assert_eq!(
+            vec![Some(0), Some(8), Some(11), Some(12), None],
+            (0..5).map(|x| index.offset(x)).collect::<Vec<_>>()
+        );
+        assert_eq!(Some((1, 8)), index.line_at(10));
+    }
+
+    #[test]
+    fn range_contains_incomplete_parts() {
+        let reader = std::io::Cursor::new("a\n1234567\nb\nc");
+        let mut lines = super::IndexedLines::with_capacity(reader, 3, 5).unwrap();
+        assert_eq!(4, lines.len());
+        let items = lines
+            .range(1..10)
+            .into_iter()
+            .map(|x| match x {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x.to_string(),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["12345", "67", "b", "c"], items);
+        assert!(matches!(
+            lines.get_line(1),
+            Some(Err(crate::Error::Incomplete(_)))
+        ));
+        assert!(lines.get_line(4).is_none());
+    }
+
+    #[test]
+    fn reads_only_the_first_part_of_long_lines() {
+        let text = format!("a\n{}\nb", "x".repeat(1 << 20));
+        let mut lines =
+            super::IndexedLines::with_capacity(std::io::Cursor::new(text), 1, 4).unwrap();
+        assert!(
+            matches!(lines.get_line(1), Some(Err(crate::Error::Incomplete(x))) if *x == "xxxx")
+        );
+        assert!(lines.reader.position() < 1 << 16);
+        assert_eq!("b", *lines.get_line(2).unwrap().unwrap());
+    }
+}
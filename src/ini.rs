@@ -0,0 +1,178 @@
+use {
+    crate::{bound::RcLineIterator, filter::FilteredLines, source::LineSource, ParseError},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields the `key=value` entries of an INI or `.env` file together with their section and line number.
+    ///
+    /// - `[section]` lines set the section of the following entries, which is empty in front of the first one
+    /// - Lines starting with `#` or `;` and blank lines are skipped
+    /// - Whitespace around keys and values is removed, as well as an `export ` in front of the key
+    /// - Values in `'` are taken literally. Values in `"` may contain the escapes `\"`, `\\` and `\n`
+    ///
+    /// Lines longer than the capacity of this iterator are yielded as a single `Incomplete` error.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{IniError, ParseError, ReadExt};
+    ///
+    /// let config = "export HOME=/root\n[server]\n# comment\nname = \"a \\\"b\\\"\"\nport\n";
+    /// let mut entries = std::io::Cursor::new(config).lines_rc().ini_entries();
+    /// let entry = entries.next().unwrap().unwrap();
+    /// assert_eq!(("", "HOME", "/root"), (entry.section.as_str(), entry.key.as_str(), entry.value.as_str()));
+    /// let entry = entries.next().unwrap().unwrap();
+    /// assert_eq!(("server", "name", "a \"b\"", 3), (entry.section.as_str(), entry.key.as_str(), entry.value.as_str(), entry.line));
+    /// assert!(matches!(entries.next(), Some(Err(ParseError::Parse { line: 4, error: IniError::MissingEquals, .. }))));
+    /// ```
+    pub fn ini_entries(self) -> IniEntries<T> {
+        IniEntries {
+            lines: self.skip_comments(&["#", ";"]).skip_blank(),
+            section: Rc::default(),
+            incomplete: None,
+        }
+    }
+}
+
+/// Entry of an INI file, created by `IniEntries`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IniEntry {
+    /// Name of the section, which is shared by all of its entries
+    pub section: Rc<String>,
+    /// Key in front of `=`
+    pub key: String,
+    /// Unquoted value after `=`
+    pub value: String,
+    /// Zero-based number of the line
+    pub line: u64,
+}
+
+/// Reason why `RcLineIterator::ini_entries()` rejected a line
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IniError {
+    /// The line is neither a section nor contains `=`
+    #[error("expected '='")]
+    MissingEquals,
+    /// A section doesn't end with `]`
+    #[error("unclosed section")]
+    UnclosedSection,
+    /// A quoted value doesn't end with the quote it starts with
+    #[error("unclosed quote")]
+    UnclosedQuote,
+}
+
+/// Iterator over the entries of an INI file, created by `RcLineIterator::ini_entries()`
+pub struct IniEntries<T: LineSource> {
+    lines: FilteredLines<T>,
+    section: Rc<String>,
+    /// Number of the line whose `Incomplete` parts are skipped after the first one
+    incomplete: Option<u64>,
+}
+
+impl<T: LineSource> IniEntries<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+/// Removes the quotes of `value` and resolves the escapes of double quotes
+fn unquote(value: &str) -> Result<String, IniError> {
+    let quote = match value.chars().next() {
+        Some(x @ ('"' | '\'')) => x,
+        _ => return Ok(value.to_string()),
+    };
+    let inner = value[1..]
+        .strip_suffix(quote)
+        .ok_or(IniError::UnclosedQuote)?;
+    if quote == '\'' {
+        return Ok(inner.to_string());
+    }
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(x) = chars.next() {
+        match x {
+            '\\' => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some(x) => result.push(x),
+                None => return Err(IniError::UnclosedQuote),
+            },
+            '"' => return Err(IniError::UnclosedQuote),
+            x => result.push(x),
+        }
+    }
+    Ok(result)
+}
+
+impl<T: LineSource> Iterator for IniEntries<T> {
+    type Item = Result<IniEntry, ParseError<IniError>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (number, content) = match self.lines.next()? {
+                (number, Ok(x)) => (number, x),
+                (number, Err(crate::Error::Incomplete(_))) if self.incomplete == Some(number) => {
+                    continue
+                }
+                (number, Err(e)) => {
+                    self.incomplete = Some(number);
+                    return Some(Err(ParseError::Read(e)));
+                }
+            };
+            let line = content.trim();
+            let parsed = if let Some(section) = line.strip_prefix('[') {
+                match section.strip_suffix(']') {
+                    Some(x) => {
+                        self.section = Rc::new(x.trim().to_string());
+                        continue;
+                    }
+                    None => Err(IniError::UnclosedSection),
+                }
+            } else {
+                let line = line.strip_prefix("export ").unwrap_or(line);
+                match line.split_once('=') {
+                    Some((key, value)) => unquote(value.trim()).map(|value| IniEntry {
+                        section: self.section.clone(),
+                        key: key.trim().to_string(),
+                        value,
+                        line: number,
+                    }),
+                    None => Err(IniError::MissingEquals),
+                }
+            };
+            return Some(parsed.map_err(|error| ParseError::Parse {
+                line: number,
+                content,
+                error,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn rejects_unclosed_quotes_and_sections() {
+        let config = "a='x\nb=\"x\\\"\n[s\nc='x'y'\nd=\"\"";
+        let entries = std::io::Cursor::new(config)
+            .lines_rc()
+            .ini_entries()
+            .map(|x| match x {
+                Ok(x) => Ok(x.value),
+                Err(ParseError::Parse { error, .. }) => Err(error),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Err(IniError::UnclosedQuote),
+                Err(IniError::UnclosedQuote),
+                Err(IniError::UnclosedSection),
+                Ok("x'y".to_string()),
+                Ok(String::new()),
+            ],
+            entries
+        );
+    }
+}
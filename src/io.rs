@@ -0,0 +1,103 @@
+//! Replacement of the parts of `std::io` used by the `no_std` core, which is compiled without the `std` feature.
+//! With `std`, `Error::Io` contains a `std::io::Error` instead.
+
+use {
+    alloc::string::{String, ToString},
+    core::fmt,
+};
+
+/// Result of reading, like `std::io::Result`
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Error of a reader, with the kind of `std::io::Error` and an optional message
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    message: Option<String>,
+}
+
+impl Error {
+    /// Creates an error of `kind` with `message`, like `std::io::Error::new()`
+    pub fn new(kind: ErrorKind, message: impl fmt::Display) -> Self {
+        Self {
+            kind,
+            message: Some(message.to_string()),
+        }
+    }
+
+    /// Creates an error of `ErrorKind::Other` with `message`, like `std::io::Error::other()`
+    pub fn other(message: impl fmt::Display) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+
+    /// Returns the kind of the error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            message: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{:?}: {}", self.kind, message),
+            None => write!(f, "{:?}", self.kind),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Kinds of `Error`, named like the ones of `std::io::ErrorKind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An entity was not found
+    NotFound,
+    /// The operation lacked the necessary privileges
+    PermissionDenied,
+    /// The connection was refused by the remote server
+    ConnectionRefused,
+    /// The connection was reset by the remote server
+    ConnectionReset,
+    /// The connection was aborted by the remote server
+    ConnectionAborted,
+    /// The operation failed because the connection is not connected yet
+    NotConnected,
+    /// A socket address could not be bound because it is in use
+    AddrInUse,
+    /// A nonexistent interface was requested or the address is not local
+    AddrNotAvailable,
+    /// The operation failed because a pipe was closed
+    BrokenPipe,
+    /// An entity already exists
+    AlreadyExists,
+    /// The operation needs to block to complete, but it was requested not to block
+    WouldBlock,
+    /// A parameter was incorrect
+    InvalidInput,
+    /// Data not valid for the operation were encountered
+    InvalidData,
+    /// The operation's timeout expired
+    TimedOut,
+    /// A write returned `Ok(0)`
+    WriteZero,
+    /// The operation was interrupted and can be retried
+    Interrupted,
+    /// The operation is unsupported on this platform
+    Unsupported,
+    /// The reader ended before the expected data was read
+    UnexpectedEof,
+    /// The operation could not allocate enough memory
+    OutOfMemory,
+    /// Any other error
+    Other,
+}
@@ -0,0 +1,68 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields only the valid lines and passes all errors to `on_error` instead, e.g. to count or log them in scripts which only handle the happy path.
+    /// Each `Incomplete` part of a line longer than the capacity is passed as a separate error.
+    /// Combine it with `with_stop_on_io_error()` for sources which may fail on every read, as `next()` would never return otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut dropped = 0;
+    /// let lines = std::io::Cursor::new(b"a\n\xff\nb").lines_rc().ok_lines(|_| dropped += 1);
+    /// assert_eq!(vec!["a", "b"], lines.map(|x| x.to_string()).collect::<Vec<_>>());
+    /// assert_eq!(1, dropped);
+    /// ```
+    pub fn ok_lines<F: FnMut(crate::Error<Rc<String>>)>(self, on_error: F) -> OkLines<T, F> {
+        OkLines {
+            lines: self,
+            on_error,
+        }
+    }
+}
+
+/// Iterator over the valid lines, created by `RcLineIterator::ok_lines()`
+pub struct OkLines<T: LineSource, F> {
+    lines: RcLineIterator<T>,
+    on_error: F,
+}
+
+impl<T: LineSource, F> OkLines<T, F> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource, F: FnMut(crate::Error<Rc<String>>)> Iterator for OkLines<T, F> {
+    type Item = Rc<String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next()? {
+                Ok(x) => return Some(x),
+                Err(e) => (self.on_error)(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn passes_each_incomplete_part() {
+        let mut errors = Vec::new();
+        let lines = std::io::Cursor::new("abcdefg\nh")
+            .lines_rc_with_capacity(3)
+            .ok_lines(|e| errors.push(e.to_string()))
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["h"], lines);
+        assert_eq!(3, errors.len());
+    }
+}
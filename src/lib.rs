@@ -1,24 +1,250 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
 //! # Simple and secure line iterators
 //! Simple line iterator which prevents OutOfMemory if an attacker inputs very long sequences without a delimiter by applying a max_capacity.
 //! The implementation reuses the last `std::rc::Rc<String>` on calling next() if it isnt used anymore.
 //!
-//! It currently uses the linebuffer library under the hood to provid a much simpler interface with fewer pitfalls:
+//! It reads from a `std::io::BufReader` under the hood but provides a much simpler interface with fewer pitfalls:
 //!  - Implements `std::iter::Iterator`
 //!  - Incomplete lines result in `Err(Incomplete<Rc<String>>)` to force users to think about this scenario
 //!  - Ok variant should be compatible with `std::io::BufReader` (beside wrapping in Rc)
 //!  - Invalid UTF8 results in `Err(Encoding)`
-use {linereader::LineReader, std::io::Read};
+//!
+//! Sources which already implement `std::io::BufRead` can use `BufReadExt` instead, which reads from their own buffer.
+//!
+//! Without the default `std` feature, the crate is `no_std` and only needs `alloc`. `RcLineIterator` with its bounds and options
+//! is created by `BufReadExt` from byte slices or, with the `embedded_io` feature, from an `EmbeddedReader`. `slice_lines()` is available as well. `Error::Io` contains an `io::Error` of this crate then.
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::io::{self, BufReader, Read, Seek};
 
+#[cfg(feature = "allocator_api")]
+mod allocator;
+#[cfg(feature = "std")]
+mod bio;
 mod bound;
+#[cfg(feature = "bytes")]
+mod bytes_lines;
+#[cfg(feature = "std")]
+mod chars;
+mod checkpoint;
+mod chunks;
+#[cfg(feature = "compress")]
+mod compress;
+mod count;
+#[cfg(feature = "std")]
+mod dedup;
+#[cfg(feature = "defmt")]
+mod defmt_format;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "embedded_io")]
+mod embedded;
+#[cfg(feature = "std")]
+mod fields;
+#[cfg(feature = "std")]
+mod filter;
+#[cfg(feature = "std")]
+mod fixed;
+#[cfg(feature = "std")]
+mod fold;
+#[cfg(feature = "std")]
+mod follow;
+#[cfg(feature = "std")]
+mod frame;
+#[cfg(feature = "unicode")]
+mod graphemes;
+#[cfg(feature = "std")]
+mod grep;
+#[cfg(feature = "std")]
+mod http;
+mod index;
+#[cfg(feature = "std")]
+mod ini;
+#[cfg(not(feature = "std"))]
+pub mod io;
+#[cfg(feature = "std")]
+mod lenient;
+#[cfg(feature = "std")]
+mod lookup;
+#[cfg(feature = "std")]
+mod mbox;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "unicode")]
+mod normalize;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "std")]
+mod parse;
+#[cfg(feature = "std")]
+mod patch;
+#[cfg(feature = "std")]
+mod paths;
+#[cfg(feature = "std")]
+mod pem;
+mod rc_str;
+#[cfg(feature = "std")]
+mod replace;
+#[cfg(feature = "resp")]
+mod resp;
+#[cfg(feature = "std")]
+mod rev;
+#[cfg(feature = "std")]
+mod sample;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "std")]
+mod seek;
+#[cfg(feature = "async")]
+mod sink;
+mod slice;
+mod small;
+#[cfg(feature = "std")]
+mod smtp;
+#[cfg(feature = "std")]
+mod sort;
+mod source;
+#[cfg(feature = "std")]
+mod split;
+#[cfg(feature = "std")]
+mod stanza;
+mod stats;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "std")]
+mod strict;
+#[cfg(feature = "std")]
+mod take;
+#[cfg(feature = "std")]
+mod tee;
+#[cfg(feature = "std")]
+mod terminator;
+mod trim;
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+mod uring;
+mod utf8;
+#[cfg(feature = "std")]
+mod vectored;
+#[cfg(feature = "walk")]
+mod walk;
+#[cfg(feature = "std")]
+mod wc;
+#[cfg(feature = "unicode")]
+mod width;
+#[cfg(feature = "std")]
+mod windows;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "zeroize")]
+mod zeroizing;
+
+pub use {
+    bound::RcLineIterator,
+    checkpoint::Checkpoint,
+    chunks::{LineChunks, OwnedLine},
+    index::LineIndex,
+    rc_str::RcStrLineIterator,
+    slice::{slice_lines, SliceLines},
+    small::{InlineStr, SmallLine, SmallLineIterator},
+    stats::{ProgressInterval, Stats},
+    trim::Trim,
+};
+
+#[cfg(feature = "std")]
+pub use {
+    bio::{FastaRecord, FastaRecords, FastqRecord, FastqRecords, SequenceError},
+    chars::CharLimited,
+    count::count_lines,
+    dedup::{DedupAdjacent, DedupAdjacentCounts, DedupApprox},
+    diff::{diff_lines, DiffLine, DiffLines},
+    fields::{ColumnError, Fields, FixedColumns, LineFields},
+    filter::FilteredLines,
+    fixed::FixedLineReader,
+    fold::FoldedLines,
+    follow::{Follow, Rotation},
+    frame::{
+        length_prefixed, netstrings, syslog_frames, FrameError, LengthPrefix, LengthPrefixed,
+        Netstrings, SyslogFrames, SyslogFraming,
+    },
+    grep::{ContextLine, ContextLines, MatchingLines},
+    http::{read_header_block, HeaderError, HeaderLimits},
+    index::IndexedLines,
+    ini::{IniEntries, IniEntry, IniError},
+    lenient::OkLines,
+    lookup::sorted_lookup,
+    mbox::{MboxError, MboxMessage, MboxMessages},
+    merge::{merge_sorted, merge_sorted_by, MergeSorted},
+    parse::{ParseError, ParseLines},
+    patch::{Hunk, PatchError, PatchHunks, PatchItem},
+    paths::{
+        lines_from_path, lines_from_paths, stdin_lines, stdin_lines_with_capacity, PathLine,
+        PathLines, PathReader,
+    },
+    pem::{PemBlock, PemBlocks, PemError},
+    replace::{copy_lines, edit_file_lines, replace_lines, CopySummary},
+    rev::tail_last_n,
+    search::{par_search, SearchMatch, SearchOptions, SearchResults},
+    seek::SeekLines,
+    smtp::{smtp_data, SmtpData},
+    sort::{is_sorted_lines, sort_lines, SortOptions, Unsorted},
+    split::line_aligned_chunks,
+    stanza::{Stanza, StanzaError, Stanzas},
+    strict::StrictLines,
+    take::{IntoInnerError, TakeLines},
+    tee::Tee,
+    terminator::{TerminatedLines, Terminator},
+    vectored::VectoredBufReader,
+    wc::LineStats,
+    windows::LineWindows,
+    writer::{LineEncoder, LineWriter, Newlines, Overlong, WriteError},
+};
 
+#[cfg(feature = "allocator_api")]
+pub use allocator::{AllocLine, AllocLineIterator};
+#[cfg(feature = "bytes")]
+pub use bytes_lines::BytesLineIterator;
+#[cfg(feature = "embedded_io")]
+pub use embedded::EmbeddedReader;
+#[cfg(feature = "unicode")]
+pub use normalize::{Normalization, NormalizedLines};
+#[cfg(feature = "rayon")]
+pub use par::par_lines;
+#[cfg(feature = "json")]
+pub use parse::JsonLines;
+#[cfg(feature = "regex")]
+pub use replace::replace_lines_regex;
+#[cfg(feature = "resp")]
+pub use resp::{resp_frames, RespError, RespFrames, RespLimits, RespValue};
+#[cfg(feature = "async")]
+pub use sink::LineSink;
+#[cfg(feature = "async")]
+pub use stream::{stdin_lines_async, stdin_lines_async_with_capacity, LineStream};
+#[cfg(all(feature = "io_uring", target_os = "linux"))]
+pub use uring::UringReader;
+#[cfg(feature = "walk")]
+pub use walk::{walk_lines, WalkLines};
+#[cfg(feature = "unicode")]
+pub use width::WidthTruncated;
+#[cfg(feature = "zeroize")]
+pub use zeroizing::ZeroizingLineIterator;
+
+#[cfg(feature = "std")]
+/// Iterator created by `ReadExt::lines_rc()`
+type ReaderLines<R> = bound::RcLineIterator<source::BufReadSource<BufReader<R>>>;
+
+#[cfg(feature = "std")]
 /// Extensions to std::io::Read to implement simple and secure line iterators
 pub trait ReadExt {
     /// Underlying Reader
     type Read: std::io::Read;
     /// Creates a RcLineIterator with a custom buffer capacity
-    fn lines_rc_with_capacity(self, buffer_capacity: usize) -> bound::RcLineIterator<Self::Read>;
+    fn lines_rc_with_capacity(
+        self,
+        buffer_capacity: usize,
+    ) -> bound::RcLineIterator<source::BufReadSource<BufReader<Self::Read>>>;
     /// Creates a RcLineIterator with the default capacity of 64kb
     ///
     /// # Examples
@@ -39,35 +265,247 @@ pub trait ReadExt {
     /// }
     /// assert_eq!(*lines.next().unwrap().unwrap(), "123");
     /// ```
-    fn lines_rc(self) -> bound::RcLineIterator<Self::Read>;
+    fn lines_rc(self) -> bound::RcLineIterator<source::BufReadSource<BufReader<Self::Read>>>;
+    /// Creates a BytesLineIterator whose lines are at most `buffer_capacity` bytes long
+    #[cfg(feature = "bytes")]
+    fn lines_bytes_with_capacity(self, buffer_capacity: usize) -> BytesLineIterator<Self::Read>;
+    /// Creates a BytesLineIterator with the default capacity of 64kb.
+    /// Lines are slices of a shared buffer and can be kept or sent without copying them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("first\nsecond").lines_bytes();
+    /// let first: bytes::Bytes = lines.next().unwrap().unwrap();
+    /// assert_eq!(&b"second"[..], lines.next().unwrap().unwrap());
+    /// assert_eq!(&b"first"[..], first);
+    /// ```
+    #[cfg(feature = "bytes")]
+    fn lines_bytes(self) -> BytesLineIterator<Self::Read>;
 }
 
+#[cfg(feature = "std")]
 impl<T: Read> ReadExt for T {
     type Read = T;
-    fn lines_rc(self) -> bound::RcLineIterator<T> {
-        self.lines_rc_with_capacity(64 * 1024)
+    fn lines_rc(self) -> bound::RcLineIterator<source::BufReadSource<BufReader<T>>> {
+        ReadExt::lines_rc_with_capacity(self, 64 * 1024)
     }
-    fn lines_rc_with_capacity(self, buffer_capacity: usize) -> bound::RcLineIterator<Self::Read> {
-        bound::RcLineIterator::new(
-            LineReader::with_capacity(buffer_capacity, self),
+    fn lines_rc_with_capacity(
+        self,
+        buffer_capacity: usize,
+    ) -> bound::RcLineIterator<source::BufReadSource<BufReader<Self::Read>>> {
+        BufReadExt::lines_rc_with_capacity(
+            BufReader::with_capacity(buffer_capacity, self),
             buffer_capacity,
         )
     }
+    #[cfg(feature = "bytes")]
+    fn lines_bytes(self) -> BytesLineIterator<T> {
+        self.lines_bytes_with_capacity(64 * 1024)
+    }
+    #[cfg(feature = "bytes")]
+    fn lines_bytes_with_capacity(self, buffer_capacity: usize) -> BytesLineIterator<T> {
+        BytesLineIterator::new(self, buffer_capacity)
+    }
+}
+
+/// Extensions to std::io::BufRead which read lines from the sources own buffer instead of wrapping it into another one.
+/// Lines are only copied, if they span multiple calls to `BufRead::fill_buf()`.
+///
+/// As every `BufRead` is also `Read`, only import one of `ReadExt` and `BufReadExt` or call them fully qualified.
+pub trait BufReadExt {
+    /// Underlying BufReader
+    type BufRead: source::FillBuf;
+    /// Creates a RcLineIterator whose lines are at most `max_line_length` bytes long
+    fn lines_rc_with_capacity(
+        self,
+        max_line_length: usize,
+    ) -> bound::RcLineIterator<source::BufReadSource<Self::BufRead>>;
+    /// Creates a RcLineIterator with the default capacity of 64kb
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::BufReadExt;
+    ///
+    /// let reader = std::io::BufReader::new("first\r\nsecond".as_bytes());
+    /// let lines = reader.lines_rc().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(vec!["first", "second"], lines.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+    /// ```
+    fn lines_rc(self) -> bound::RcLineIterator<source::BufReadSource<Self::BufRead>>;
+}
+
+impl<T: source::FillBuf> BufReadExt for T {
+    type BufRead = T;
+    fn lines_rc(self) -> bound::RcLineIterator<source::BufReadSource<T>> {
+        BufReadExt::lines_rc_with_capacity(self, 64 * 1024)
+    }
+    fn lines_rc_with_capacity(
+        self,
+        max_line_length: usize,
+    ) -> bound::RcLineIterator<source::BufReadSource<T>> {
+        bound::RcLineIterator::new(
+            source::BufReadSource::new(self, max_line_length),
+            max_line_length,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+/// Extensions to seekable sources to iterate their lines from the last to the first, e.g. to show the newest entries of a log first,
+/// or to resume from a `Checkpoint`.
+/// Reverse iterators only keep `max_line_length` bytes and a block for reading in memory, regardless of the size of the source.
+pub trait SeekExt {
+    /// Underlying seekable reader
+    type Read: std::io::Read + std::io::Seek;
+    /// Creates a reverse RcLineIterator whose lines are at most `max_line_length` bytes long.
+    /// Longer lines are cut from their end, so the `Incomplete` parts are returned from the last to the first, too.
+    fn rev_lines_rc_with_capacity(
+        self,
+        max_line_length: usize,
+    ) -> bound::RcLineIterator<rev::RevSource<Self::Read>>;
+    /// Creates a reverse RcLineIterator with the default capacity of 64kb
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::SeekExt;
+    ///
+    /// let lines = std::io::Cursor::new("first\nsecond\r\nthird\n").rev_lines_rc();
+    /// let lines = lines.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(vec!["third", "second", "first"], lines.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+    /// ```
+    fn rev_lines_rc(self) -> bound::RcLineIterator<rev::RevSource<Self::Read>>;
+    /// Seeks to the offset of `checkpoint` and continues iterating like the RcLineIterator which returned it,
+    /// including its capacity, stats and `Incomplete` parts. The offset is relative to the start of the reader.
+    fn resume_lines_rc(
+        self,
+        checkpoint: &Checkpoint,
+    ) -> std::io::Result<bound::RcLineIterator<source::BufReadSource<BufReader<Self::Read>>>>;
+    /// Iterates the lines whose first byte is within `range`, so jobs owning adjacent ranges of a file neither duplicate nor split lines.
+    /// The first line may start after `range.start` and the last one may end after `range.end`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::SeekExt;
+    ///
+    /// let text = "first\nsecond\nthird\n";
+    /// let mut lines = std::io::Cursor::new(text).lines_in_range(3..8).unwrap();
+    /// assert_eq!("second", *lines.next().unwrap().unwrap());
+    /// assert!(lines.next().is_none());
+    /// ```
+    fn lines_in_range(
+        self,
+        range: std::ops::Range<u64>,
+    ) -> std::io::Result<ReaderLines<std::io::Take<Self::Read>>>;
+    /// Iterates the lines from the current position to the end from both sides, so `rev()`, `rfind()` or `last()`
+    /// don't read the lines in front of the match. Lines are at most `max_line_length` bytes long.
+    fn seek_lines_rc_with_capacity(self, max_line_length: usize) -> seek::SeekLines<Self::Read>;
+    /// Iterates the lines from both sides with the default capacity of 64kb
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::SeekExt;
+    ///
+    /// let mut lines = std::io::Cursor::new("GET /\nPOST /a\nGET /b\nPOST /c\n").seek_lines_rc();
+    /// let last_post = lines.rfind(|x| x.as_ref().map_or(false, |x| x.starts_with("POST")));
+    /// assert_eq!("POST /c", *last_post.unwrap().unwrap());
+    /// assert_eq!("GET /", *lines.next().unwrap().unwrap());
+    /// assert_eq!("GET /b", *lines.next_back().unwrap().unwrap());
+    /// assert_eq!("POST /a", *lines.next().unwrap().unwrap());
+    /// assert!(lines.next().is_none());
+    /// ```
+    fn seek_lines_rc(self) -> seek::SeekLines<Self::Read>;
+}
+
+#[cfg(feature = "std")]
+impl<T: Read + Seek> SeekExt for T {
+    type Read = T;
+    fn rev_lines_rc(self) -> bound::RcLineIterator<rev::RevSource<T>> {
+        self.rev_lines_rc_with_capacity(64 * 1024)
+    }
+    fn rev_lines_rc_with_capacity(
+        self,
+        max_line_length: usize,
+    ) -> bound::RcLineIterator<rev::RevSource<T>> {
+        bound::RcLineIterator::new(rev::RevSource::new(self, max_line_length), max_line_length)
+    }
+    fn resume_lines_rc(
+        self,
+        checkpoint: &Checkpoint,
+    ) -> std::io::Result<bound::RcLineIterator<source::BufReadSource<BufReader<T>>>> {
+        checkpoint::resume(self, checkpoint)
+    }
+    fn lines_in_range(
+        self,
+        range: std::ops::Range<u64>,
+    ) -> std::io::Result<ReaderLines<std::io::Take<T>>> {
+        split::lines_in_range(self, range, 64 * 1024)
+    }
+    fn seek_lines_rc_with_capacity(self, max_line_length: usize) -> seek::SeekLines<T> {
+        seek::SeekLines::new(self, max_line_length)
+    }
+    fn seek_lines_rc(self) -> seek::SeekLines<T> {
+        self.seek_lines_rc_with_capacity(64 * 1024)
+    }
 }
 
 /// Result of calling ReadExt::lines_rc
 #[derive(thiserror::Error, Debug)]
-pub enum Error<T: std::fmt::Debug> {
+pub enum Error<T: core::fmt::Debug> {
     /// Forwarded Errors from the underlying reader
     #[error("io")]
-    Io(#[from] std::io::Error),
+    Io(#[from] io::Error),
     /// If a line contains any invalid UTF8 character
     #[error("encoding")]
-    Encoding(#[from] std::str::Utf8Error),
+    Encoding(#[from] core::str::Utf8Error),
     /// If the provided buffer is full, it's content is returned as `Incomplete`.
     /// The rest of the line, including the last part containing the linebreak, will all be `Incomplete` or other errors.
     #[error("Incomplete line")]
     Incomplete(T),
+    /// The iteration was cancelled by the token passed to `RcLineIterator::with_cancellation()`. This is always the last item.
+    #[error("cancelled")]
+    Cancelled,
+    /// Reading a line took longer than the timeout passed to `RcLineIterator::with_read_timeout()`
+    #[error("timed out")]
+    TimedOut,
+    /// More bytes than allowed by `RcLineIterator::with_max_total_bytes()` were read. This is always the last item.
+    #[error("quota exceeded")]
+    QuotaExceeded,
+    /// The reader contains more lines than allowed by `RcLineIterator::with_max_lines()`. This is always the last item.
+    #[error("too many lines")]
+    TooManyLines,
+    /// The line contains a byte rejected by `RcLineIterator::with_forbidden_bytes()`
+    #[error("forbidden byte {byte:#04x} at position {position}")]
+    ForbiddenByte {
+        /// Rejected byte
+        byte: u8,
+        /// Position of the first rejected byte within the line
+        position: usize,
+    },
+    /// The input looks binary according to `RcLineIterator::with_binary_detection()`. This is always the only item.
+    #[error("binary content")]
+    BinaryContent,
+    /// A line is longer than the capacity while `RcLineIterator::with_strict_line_length()` is enabled. This is always the last item.
+    #[error("line too long")]
+    LineTooLong,
+}
+
+impl<T: core::fmt::Debug> Error<T> {
+    /// Converts the content of `Incomplete` and keeps all other variants
+    pub fn map_incomplete<U: core::fmt::Debug>(self, f: impl FnOnce(T) -> U) -> Error<U> {
+        match self {
+            Error::Io(e) => Error::Io(e),
+            Error::Encoding(e) => Error::Encoding(e),
+            Error::Incomplete(x) => Error::Incomplete(f(x)),
+            Error::Cancelled => Error::Cancelled,
+            Error::TimedOut => Error::TimedOut,
+            Error::QuotaExceeded => Error::QuotaExceeded,
+            Error::TooManyLines => Error::TooManyLines,
+            Error::ForbiddenByte { byte, position } => Error::ForbiddenByte { byte, position },
+            Error::BinaryContent => Error::BinaryContent,
+            Error::LineTooLong => Error::LineTooLong,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -97,13 +535,38 @@ mod tests {
 
     #[test]
     fn assert_non_ascii_returns_error() {
-        let buf = ['a' as u8, 'b' as u8, 254];
+        let buf = [b'a', b'b', 254];
         assert_behave_same(&buf);
     }
 
+    #[test]
+    fn bufread_splits_long_lines_like_read() {
+        let input = "12345678\r\n123\n1234\n12345";
+        let read = ReadExt::lines_rc_with_capacity(Cursor::new(input), 5);
+        let buf_read =
+            BufReadExt::lines_rc_with_capacity(BufReader::with_capacity(3, input.as_bytes()), 5);
+        for (a, b) in read.zip(buf_read) {
+            match (a, b) {
+                (Ok(a), Ok(b)) => assert_eq!(a, b),
+                (Err(Error::Incomplete(a)), Err(Error::Incomplete(b))) => assert_eq!(a, b),
+                (a, b) => panic!("{:?} != {:?}", a, b),
+            }
+        }
+    }
+
     fn assert_behave_same<T: AsRef<[u8]>>(input: &T) {
+        assert_behave_same_as(input, ReadExt::lines_rc(Cursor::new(input)));
+        assert_behave_same_as(
+            input,
+            BufReadExt::lines_rc(BufReader::with_capacity(1, input.as_ref())),
+        );
+    }
+
+    fn assert_behave_same_as<T: AsRef<[u8]>>(
+        input: &T,
+        mut rc_iter: impl Iterator<Item = Result<std::rc::Rc<String>, Error<std::rc::Rc<String>>>>,
+    ) {
         let mut own_iter = BufReader::new(Cursor::new(input)).lines();
-        let mut rc_iter = std::io::Cursor::new(input).lines_rc();
         for (own_line, rc_line) in own_iter.by_ref().zip(rc_iter.by_ref()) {
             match own_line {
                 Ok(o) => assert_eq!(o, *rc_line.unwrap()),
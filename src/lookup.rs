@@ -0,0 +1,112 @@
+use {
+    crate::{
+        bound::RcLineIterator,
+        source::{BufReadSource, LineSource},
+        ReadExt,
+    },
+    std::io::{self, BufReader, Read, Seek, SeekFrom},
+};
+
+/// Binary searches a seekable reader whose lines are sorted bytewise for the lines starting with `prefix`, like the `look` utility.
+/// Only a few lines are read per step, so lookups in files of multiple gigabytes stay fast.
+///
+/// Lines are compared by their first 64kb, which is also the capacity of the returned iterator.
+/// Line boundaries are found with bounded reads, so very long lines don't exhaust memory.
+///
+/// # Examples
+/// ```
+/// let words = std::io::Cursor::new("apple\nbanana\nbandana\nbank\ncherry\n");
+/// let found = simple_lines::sorted_lookup(words, "band").unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec!["bandana"], found.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+/// ```
+pub fn sorted_lookup<R: Read + Seek>(
+    mut reader: R,
+    prefix: &str,
+) -> io::Result<RcLineIterator<BufReadSource<BufReader<io::Take<R>>>>> {
+    const MAX_SIZE: usize = 64 * 1024;
+    let prefix = prefix.as_bytes();
+    let len = reader.seek(SeekFrom::End(0))?;
+    let start = partition_point(&mut reader, len, MAX_SIZE, |line| line < prefix)?;
+    let end = partition_point(&mut reader, len, MAX_SIZE, |line| {
+        line < prefix || line.starts_with(prefix)
+    })?;
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(ReadExt::lines_rc_with_capacity(
+        reader.take(end.max(start) - start),
+        MAX_SIZE,
+    ))
+}
+
+/// Returns the offset of the first line for which `is_before` returns false, or `len` if there is none.
+/// `is_before` has to be true for all lines in front of that line and false for all after it.
+fn partition_point<R: Read + Seek>(
+    reader: &mut R,
+    len: u64,
+    max_size: usize,
+    is_before: impl Fn(&[u8]) -> bool,
+) -> io::Result<u64> {
+    let mut low = 0;
+    let mut high = len;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match first_line_from(reader, mid, max_size)? {
+            Some((_, line)) if is_before(&line) => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+    Ok(first_line_from(reader, low, max_size)?.map_or(len, |(start, _)| start))
+}
+
+/// Returns the offset and the first chunk of the first line starting at or after `offset`
+pub(crate) fn first_line_from<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    max_size: usize,
+) -> io::Result<Option<(u64, Vec<u8>)>> {
+    // A line starts at `offset` if the byte in front of it is a linebreak
+    let mut position = offset.saturating_sub(1);
+    reader.seek(SeekFrom::Start(position))?;
+    let mut source = BufReadSource::new(BufReader::with_capacity(max_size, reader), max_size);
+    if offset > 0 {
+        loop {
+            match source.next_line().transpose()? {
+                Some(chunk) => {
+                    position += chunk.len() as u64;
+                    if chunk.last() == Some(&b'\n') {
+                        break;
+                    }
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+    let line = source.next_line().transpose()?.map(|chunk| {
+        let line = chunk.strip_suffix(b"\n").unwrap_or(chunk);
+        line.strip_suffix(b"\r").unwrap_or(line).to_vec()
+    });
+    Ok(line.map(|x| (position, x)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(text: &str, prefix: &str) -> Vec<String> {
+        sorted_lookup(std::io::Cursor::new(text), prefix)
+            .unwrap()
+            .map(|x| x.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn finds_all_lines_with_prefix() {
+        let text = (0..1000).map(|x| format!("{:04}\n", x)).collect::<String>();
+        assert_eq!(vec!["0420"], lookup(&text, "0420"));
+        assert_eq!(10, lookup(&text, "012").len());
+        assert_eq!(1000, lookup(&text, "").len());
+        assert!(lookup(&text, "1").is_empty());
+        assert!(lookup(&text, "0420x").is_empty());
+        assert_eq!(vec!["a"], lookup("a", "a"));
+        assert!(lookup("", "a").is_empty());
+    }
+}
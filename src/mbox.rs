@@ -0,0 +1,196 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Splits an mbox file into its messages, which start with a `From ` separator line.
+    /// The quoting of `>From ` lines is removed like in the mboxrd format, so `>>From ` becomes `>From `.
+    ///
+    /// Each message is collected with `\n` linebreaks, without the separator and without the blank line in front of the next separator.
+    /// Messages larger than `max_message_size` bytes are rejected without being buffered, and the iteration continues with the next message.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mbox = "From a@b Sat Jan  3 01:05:34 1996\nSubject: a\n\n>From here\n\nFrom c@d Sat Jan  3 01:06:00 1996\nSubject: b\n";
+    /// let messages = std::io::Cursor::new(mbox).lines_rc().mbox_messages(1024 * 1024);
+    /// let messages = messages.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!("a@b Sat Jan  3 01:05:34 1996", messages[0].from);
+    /// assert_eq!("Subject: a\n\nFrom here\n", messages[0].body);
+    /// assert_eq!("Subject: b\n", messages[1].body);
+    /// ```
+    pub fn mbox_messages(self, max_message_size: usize) -> MboxMessages<T> {
+        MboxMessages {
+            lines: self,
+            max_message_size,
+            number: 0,
+            is_first_part: true,
+            message: None,
+            rejected_start: false,
+        }
+    }
+}
+
+/// Message of an mbox file, created by `MboxMessages`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MboxMessage {
+    /// Separator line without the leading `From `, which usually contains the envelope sender and a date
+    pub from: String,
+    /// Unquoted lines of the message
+    pub body: String,
+}
+
+/// Error of `MboxMessages`. Line numbers are zero-based.
+#[derive(thiserror::Error, Debug)]
+pub enum MboxError {
+    /// The line couldn't be read
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The message starting in `line` is larger than allowed
+    #[error("message in line {line} too large")]
+    TooLarge {
+        /// Number of the separator line
+        line: u64,
+    },
+    /// The file doesn't start with a separator line. Lines up to the first separator are skipped.
+    #[error("missing separator in line {line}")]
+    MissingSeparator {
+        /// Number of the first line which isn't blank
+        line: u64,
+    },
+}
+
+/// Message which is currently read
+struct OpenMessage {
+    line: u64,
+    from: String,
+    body: String,
+    /// Whether the following parts belong to the separator line
+    in_separator: bool,
+    /// Whether the message is too large and its lines are skipped
+    overflowed: bool,
+}
+
+impl OpenMessage {
+    fn finish(mut self) -> Result<MboxMessage, MboxError> {
+        if self.overflowed {
+            return Err(MboxError::TooLarge { line: self.line });
+        }
+        // The blank line in front of the next separator isn't part of the message
+        if self.body.ends_with("\n\n") || self.body == "\n" {
+            self.body.pop();
+        }
+        Ok(MboxMessage {
+            from: self.from,
+            body: self.body,
+        })
+    }
+}
+
+/// Iterator over the messages of an mbox file, created by `RcLineIterator::mbox_messages()`
+pub struct MboxMessages<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_message_size: usize,
+    /// Number of the next line
+    number: u64,
+    /// Whether the next item starts a line
+    is_first_part: bool,
+    message: Option<OpenMessage>,
+    /// Whether content in front of the first separator was reported already
+    rejected_start: bool,
+}
+
+impl<T: LineSource> MboxMessages<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for MboxMessages<T> {
+    type Item = Result<MboxMessage, MboxError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(line) = self.lines.next() else {
+                return self.message.take().map(OpenMessage::finish);
+            };
+            let number = self.number;
+            let ends_line = !self.lines.continues_line();
+            let is_first_part = std::mem::replace(&mut self.is_first_part, ends_line);
+            if ends_line {
+                self.number += 1;
+            }
+            let part = match line {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x,
+                Err(e) => return Some(Err(MboxError::Read(e))),
+            };
+            if let Some(from) = part.strip_prefix("From ").filter(|_| is_first_part) {
+                let next = OpenMessage {
+                    line: number,
+                    from: from.to_string(),
+                    body: String::new(),
+                    in_separator: !ends_line,
+                    overflowed: false,
+                };
+                match self.message.replace(next) {
+                    Some(finished) => return Some(finished.finish()),
+                    None => continue,
+                }
+            }
+            let Some(message) = self.message.as_mut() else {
+                if is_first_part && !part.trim().is_empty() && !self.rejected_start {
+                    self.rejected_start = true;
+                    return Some(Err(MboxError::MissingSeparator { line: number }));
+                }
+                continue;
+            };
+            let content = match part.strip_prefix('>') {
+                Some(x) if is_first_part && x.trim_start_matches('>').starts_with("From ") => x,
+                _ => &part,
+            };
+            let size = message.from.len() + message.body.len() + content.len() + 1;
+            if message.overflowed || size > self.max_message_size {
+                message.overflowed = true;
+                message.body = String::new();
+                continue;
+            }
+            match message.in_separator {
+                true => message.from.push_str(content),
+                false => message.body.push_str(content),
+            }
+            if ends_line && !std::mem::replace(&mut message.in_separator, false) {
+                message.body.push('\n');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn skips_rejected_messages() {
+        let mbox = "garbage\nmore\nFrom a\na very long line\n\nFrom b\n>>From x\nx";
+        let messages = std::io::Cursor::new(mbox)
+            .lines_rc_with_capacity(8)
+            .mbox_messages(12)
+            .map(|x| match x {
+                Ok(x) => Ok(x.body),
+                Err(MboxError::MissingSeparator { line }) => Err(("missing", line)),
+                Err(MboxError::TooLarge { line }) => Err(("large", line)),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Err(("missing", 0)),
+                Err(("large", 2)),
+                Ok(">From x\nx\n".to_string())
+            ],
+            messages
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::cmp::Ordering,
+};
+
+/// Merges iterators whose lines are sorted bytewise into one sorted iterator, e.g. to combine pre-sorted shards without loading them.
+/// See `merge_sorted_by()` for details.
+///
+/// # Examples
+/// ```
+/// use simple_lines::ReadExt;
+///
+/// let shards = ["a\nc\ne", "b\nd", "f"].iter().map(|x| std::io::Cursor::new(*x).lines_rc());
+/// let merged = simple_lines::merge_sorted(shards).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!("abcdef", merged.iter().map(|x| x.as_str()).collect::<String>());
+/// ```
+pub fn merge_sorted<S: LineSource>(
+    iterators: impl IntoIterator<Item = RcLineIterator<S>>,
+) -> MergeSorted<S, fn(&str, &str) -> Ordering> {
+    merge_sorted_by(iterators, Ord::cmp)
+}
+
+/// Merges iterators whose lines are sorted by `compare` into one sorted iterator. Only the next line of each iterator is kept in memory.
+/// Equal lines are returned in the order of their iterators.
+///
+/// `Incomplete` parts are compared by their first part, and the following parts are returned before any other line.
+/// Other errors can't be compared and are returned as soon as they are read.
+///
+/// # Examples
+/// ```
+/// use simple_lines::ReadExt;
+///
+/// let shards = ["c\nb", "d\na"].iter().map(|x| std::io::Cursor::new(*x).lines_rc());
+/// let merged = simple_lines::merge_sorted_by(shards, |a, b| b.cmp(a));
+/// let merged = merged.collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!("dcba", merged.iter().map(|x| x.as_str()).collect::<String>());
+/// ```
+pub fn merge_sorted_by<S: LineSource, F: FnMut(&str, &str) -> Ordering>(
+    iterators: impl IntoIterator<Item = RcLineIterator<S>>,
+    compare: F,
+) -> MergeSorted<S, F> {
+    let iterators = iterators.into_iter().collect::<Vec<_>>();
+    MergeSorted {
+        heads: iterators.iter().map(|_| None).collect(),
+        iterators,
+        compare,
+        continued: None,
+        started: false,
+    }
+}
+
+/// Iterator over the merged lines of sorted iterators, created by `merge_sorted()` and `merge_sorted_by()`
+pub struct MergeSorted<S: LineSource, F> {
+    iterators: Vec<RcLineIterator<S>>,
+    /// Next item of each iterator and whether it is continued by the following one
+    heads: Vec<Option<(RcLine, bool)>>,
+    compare: F,
+    /// Iterator whose line has more `Incomplete` parts
+    continued: Option<usize>,
+    started: bool,
+}
+
+impl<S: LineSource, F> MergeSorted<S, F> {
+    fn advance(&mut self, index: usize) {
+        let iterator = &mut self.iterators[index];
+        self.heads[index] = iterator.next().map(|x| (x, iterator.continues_line()));
+    }
+
+    fn take(&mut self, index: usize) -> Option<RcLine> {
+        let (line, continues) = self.heads[index].take()?;
+        self.continued = Some(index).filter(|_| continues);
+        self.advance(index);
+        Some(line)
+    }
+}
+
+impl<S: LineSource, F: FnMut(&str, &str) -> Ordering> Iterator for MergeSorted<S, F> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if !std::mem::replace(&mut self.started, true) {
+            (0..self.iterators.len()).for_each(|x| self.advance(x));
+        }
+        if let Some(index) = self.continued {
+            return self.take(index);
+        }
+        let compare = &mut self.compare;
+        let mut min: Option<(usize, &str)> = None;
+        for (index, head) in self.heads.iter().enumerate() {
+            let text = match head {
+                Some((Ok(x) | Err(crate::Error::Incomplete(x)), _)) => x.as_str(),
+                Some(_) => {
+                    min = Some((index, ""));
+                    break;
+                }
+                None => continue,
+            };
+            if min.is_none_or(|(_, min)| compare(text, min) == Ordering::Less) {
+                min = Some((index, text));
+            }
+        }
+        let (index, _) = min?;
+        self.take(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn keeps_incomplete_parts_together() {
+        let shards = ["a12zzz\nb", "a2\nc"]
+            .iter()
+            .map(|x| std::io::Cursor::new(*x).lines_rc_with_capacity(5));
+        let merged = super::merge_sorted(shards)
+            .map(|x| match x {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x.to_string(),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["a12zz", "z", "a2", "b", "c"], merged);
+    }
+}
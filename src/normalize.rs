@@ -0,0 +1,95 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::rc::Rc,
+    unicode_normalization::{is_nfc_quick, is_nfd_quick, IsNormalized, UnicodeNormalization},
+};
+
+/// Unicode normalization form of `RcLineIterator::normalize()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition, e.g. `e` followed by a combining acute accent becomes `é`
+    Nfc,
+    /// Canonical decomposition, e.g. `é` becomes `e` followed by a combining acute accent
+    Nfd,
+}
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Normalizes each line to `form` with [unicode-normalization](https://crates.io/crates/unicode-normalization),
+    /// so lines from differently normalized sources compare, sort and deduplicate equal.
+    /// Lines which are normalized already are yielded as they are without copying them.
+    ///
+    /// `Incomplete` parts are normalized on their own, so a combining mark at the start of a part isn't composed with the end of the previous one.
+    /// Normalized lines can be longer than the capacity, as decomposition adds characters.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{Normalization, ReadExt};
+    ///
+    /// let lines = std::io::Cursor::new("caf\u{e9}\ncafe\u{301}").lines_rc().normalize(Normalization::Nfc);
+    /// let lines = lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>();
+    /// assert_eq!(lines[0], lines[1]);
+    /// ```
+    pub fn normalize(self, form: Normalization) -> NormalizedLines<T> {
+        NormalizedLines { lines: self, form }
+    }
+}
+
+/// Iterator over lines normalized to a Unicode normalization form, created by `RcLineIterator::normalize()`
+pub struct NormalizedLines<T: LineSource> {
+    lines: RcLineIterator<T>,
+    form: Normalization,
+}
+
+impl<T: LineSource> NormalizedLines<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    fn normalize(&self, line: Rc<String>) -> Rc<String> {
+        let quick = match self.form {
+            Normalization::Nfc => is_nfc_quick(line.chars()),
+            Normalization::Nfd => is_nfd_quick(line.chars()),
+        };
+        if quick == IsNormalized::Yes {
+            return line;
+        }
+        Rc::new(match self.form {
+            Normalization::Nfc => line.nfc().collect(),
+            Normalization::Nfd => line.nfd().collect(),
+        })
+    }
+}
+
+impl<T: LineSource> Iterator for NormalizedLines<T> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.lines.next()? {
+            Ok(x) => Ok(self.normalize(x)),
+            Err(crate::Error::Incomplete(x)) => Err(crate::Error::Incomplete(self.normalize(x))),
+            Err(e) => Err(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn keeps_normalized_lines() {
+        let mut lines = std::io::Cursor::new("caf\u{e9}\n")
+            .lines_rc()
+            .normalize(Normalization::Nfd);
+        assert_eq!("cafe\u{301}", *lines.next().unwrap().unwrap());
+
+        let lines = std::io::Cursor::new("plain\n")
+            .lines_rc()
+            .normalize(Normalization::Nfc);
+        let original = Rc::new("plain".to_string());
+        assert!(Rc::ptr_eq(&original, &lines.normalize(original.clone())));
+    }
+}
@@ -0,0 +1,46 @@
+use {
+    crate::{chunks::OwnedLine, split::line_aligned_chunks, SeekExt},
+    rayon::iter::{Either, IntoParallelIterator, ParallelIterator},
+    std::{fs::File, io, path::Path},
+};
+
+/// Reads the lines of the file at `path` on the rayon thread pool.
+/// The file is split into line aligned chunks, which are read by independent iterators on their own file handle.
+/// Lines are returned as `String`, because the shared `Rc<String>` of `RcLineIterator` can't be sent between threads.
+///
+/// Lines are at most 64kb long. Errors opening the file for a chunk are returned as `Error::Io` item.
+///
+/// # Examples
+/// ```no_run
+/// use rayon::iter::ParallelIterator;
+///
+/// let errors = simple_lines::par_lines("/var/log/syslog")
+///     .unwrap()
+///     .filter(|line| line.as_ref().map_or(true, |x| x.contains("error")))
+///     .count();
+/// ```
+pub fn par_lines(path: impl AsRef<Path>) -> io::Result<impl ParallelIterator<Item = OwnedLine>> {
+    let path = path.as_ref().to_owned();
+    let chunks = line_aligned_chunks(&mut File::open(&path)?, rayon::current_num_threads() * 4)?;
+    Ok(chunks.into_par_iter().flat_map_iter(move |range| {
+        match File::open(&path).and_then(|file| file.lines_in_range(range)) {
+            Ok(mut lines) => Either::Left(std::iter::from_fn(move || lines.next_owned())),
+            Err(e) => Either::Right(std::iter::once(Err(e.into()))),
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rayon::iter::ParallelIterator};
+
+    #[test]
+    fn keeps_order_of_lines() {
+        let path = std::env::temp_dir().join(format!("simple_lines_par_{}", std::process::id()));
+        let text = (0..10_000).map(|x| format!("{}\n", x)).collect::<String>();
+        std::fs::write(&path, &text).unwrap();
+        let lines = par_lines(&path).unwrap().collect::<Result<Vec<_>, _>>();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text.lines().collect::<Vec<_>>(), lines.unwrap());
+    }
+}
@@ -0,0 +1,202 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::{rc::Rc, str::FromStr},
+};
+
+/// Error of an iterator which parses each line
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError<E: std::fmt::Debug + std::fmt::Display> {
+    /// The line couldn't be read
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The line couldn't be parsed
+    #[error("line {line}: {error}")]
+    Parse {
+        /// Zero-based number of the line
+        line: u64,
+        /// Content of the line
+        content: Rc<String>,
+        /// Error of the parser
+        error: E,
+    },
+}
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Parses each line with `FromStr`. Errors of the parser contain the number and content of the offending line.
+    ///
+    /// Lines are parsed as they are, so combine it with `with_trim()` to accept surrounding whitespace.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ParseError, ReadExt, Trim};
+    ///
+    /// let mut ids = std::io::Cursor::new("1\n 2 \nthree").lines_rc().with_trim(Trim::Both).parse_lines::<u32>();
+    /// assert_eq!(1, ids.next().unwrap().unwrap());
+    /// assert_eq!(2, ids.next().unwrap().unwrap());
+    /// match ids.next().unwrap() {
+    ///     Err(ParseError::Parse { line, content, .. }) => assert_eq!((2, "three"), (line, content.as_str())),
+    ///     _ => panic!("Expected 'three' to be rejected"),
+    /// }
+    /// ```
+    pub fn parse_lines<V: FromStr>(self) -> ParseLines<T, V> {
+        ParseLines {
+            lines: self,
+            number: 0,
+            value: std::marker::PhantomData,
+        }
+    }
+
+    /// Deserializes each line as JSON, like in the NDJSON and JSON Lines formats. Blank lines are skipped.
+    ///
+    /// Errors of the deserializer contain the number and content of the offending line.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{ParseError, ReadExt};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Event {
+    ///     id: u32,
+    /// }
+    ///
+    /// let mut events = std::io::Cursor::new("{\"id\":1}\n\n{\"id\":\"x\"}").lines_rc().json_lines::<Event>();
+    /// assert_eq!(1, events.next().unwrap().unwrap().id);
+    /// match events.next().unwrap() {
+    ///     Err(ParseError::Parse { line, content, .. }) => assert_eq!((2, "{\"id\":\"x\"}"), (line, content.as_str())),
+    ///     _ => panic!("Expected the id to be rejected"),
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn json_lines<V: serde::de::DeserializeOwned>(self) -> JsonLines<T, V> {
+        JsonLines {
+            lines: self,
+            number: 0,
+            value: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Returns the next line with its zero-based number
+fn next_numbered<T: LineSource>(
+    lines: &mut RcLineIterator<T>,
+    number: &mut u64,
+) -> Option<(u64, RcLine)> {
+    let line = lines.next()?;
+    let current = *number;
+    if !lines.continues_line() {
+        *number += 1;
+    }
+    Some((current, line))
+}
+
+/// Iterator over lines parsed by `FromStr`, created by `RcLineIterator::parse_lines()`
+pub struct ParseLines<T: LineSource, V> {
+    lines: RcLineIterator<T>,
+    /// Number of the next line
+    number: u64,
+    value: std::marker::PhantomData<fn() -> V>,
+}
+
+impl<T: LineSource, V> ParseLines<T, V> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource, V: FromStr> Iterator for ParseLines<T, V>
+where
+    V::Err: std::fmt::Debug + std::fmt::Display,
+{
+    type Item = Result<V, ParseError<V::Err>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (number, line) = next_numbered(&mut self.lines, &mut self.number)?;
+        Some(match line {
+            Ok(content) => content.parse().map_err(|error| ParseError::Parse {
+                line: number,
+                content,
+                error,
+            }),
+            Err(e) => Err(ParseError::Read(e)),
+        })
+    }
+}
+
+/// Iterator over lines deserialized from JSON, created by `RcLineIterator::json_lines()`
+#[cfg(feature = "json")]
+pub struct JsonLines<T: LineSource, V> {
+    lines: RcLineIterator<T>,
+    /// Number of the next line
+    number: u64,
+    value: std::marker::PhantomData<fn() -> V>,
+}
+
+#[cfg(feature = "json")]
+impl<T: LineSource, V> JsonLines<T, V> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: LineSource, V: serde::de::DeserializeOwned> Iterator for JsonLines<T, V> {
+    type Item = Result<V, ParseError<serde_json::Error>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (number, line) = next_numbered(&mut self.lines, &mut self.number)?;
+            let content = match line {
+                Ok(x) if x.trim().is_empty() => continue,
+                Ok(x) => x,
+                Err(e) => return Some(Err(ParseError::Read(e))),
+            };
+            return Some(
+                serde_json::from_str(&content).map_err(|error| ParseError::Parse {
+                    line: number,
+                    content,
+                    error,
+                }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn reports_parse_errors_with_line() {
+        let values = std::io::Cursor::new("1\n\n-3\n")
+            .lines_rc()
+            .parse_lines::<i8>()
+            .map(|x| match x {
+                Ok(x) => Ok(x),
+                Err(ParseError::Parse { line, content, .. }) => Err((line, content.to_string())),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Ok(1), Err((1, String::new())), Ok(-3)], values);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn numbers_lines_after_incomplete_parts() {
+        let mut values = std::io::Cursor::new("[1,2,3]\n \n[4]")
+            .lines_rc_with_capacity(4)
+            .json_lines::<Vec<u32>>();
+        assert!(matches!(
+            values.next(),
+            Some(Err(ParseError::Read(crate::Error::Incomplete(_))))
+        ));
+        assert!(matches!(
+            values.next(),
+            Some(Err(ParseError::Read(crate::Error::Incomplete(_))))
+        ));
+        assert_eq!(vec![4], values.next().unwrap().unwrap());
+        assert!(values.next().is_none());
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Groups the lines of a unified diff, like the output of `diff -u` or `git diff`, into file headers and hunks.
+    /// Lines outside of them, like `diff --git` or `index` lines, are skipped.
+    ///
+    /// The lines of a hunk are read according to the ranges of its `@@` line. Hunks larger than `max_hunk_size` bytes
+    /// are rejected without being buffered, and the iteration continues after them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::{PatchItem, ReadExt};
+    ///
+    /// let diff = "--- a/x.txt\n+++ b/x.txt\n@@ -1,2 +1,2 @@ fn main\n a\n-b\n+c\n";
+    /// let mut items = std::io::Cursor::new(diff).lines_rc().patch_hunks(64 * 1024);
+    /// match items.next().unwrap().unwrap() {
+    ///     PatchItem::File { old, new, .. } => assert_eq!(("a/x.txt", "b/x.txt"), (old.as_str(), new.as_str())),
+    ///     x => panic!("Expected a file header, got {:?}", x),
+    /// }
+    /// match items.next().unwrap().unwrap() {
+    ///     PatchItem::Hunk(hunk) => {
+    ///         assert_eq!((1, 2, 1, 2), (hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines));
+    ///         assert_eq!("fn main", hunk.section);
+    ///         assert_eq!(vec![" a", "-b", "+c"], hunk.lines);
+    ///     }
+    ///     x => panic!("Expected a hunk, got {:?}", x),
+    /// }
+    /// assert!(items.next().is_none());
+    /// ```
+    pub fn patch_hunks(self, max_hunk_size: usize) -> PatchHunks<T> {
+        PatchHunks {
+            lines: self,
+            max_hunk_size,
+            number: 0,
+            pending: None,
+        }
+    }
+}
+
+/// Item of a unified diff, created by `PatchHunks`. Line numbers are zero-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchItem {
+    /// `---` and `+++` lines in front of the hunks of a file
+    File {
+        /// Path after `---`, without a timestamp separated by a tab
+        old: String,
+        /// Path after `+++`, without a timestamp separated by a tab
+        new: String,
+        /// Number of the `---` line
+        line: u64,
+    },
+    /// Hunk of changed lines
+    Hunk(Hunk),
+}
+
+/// Hunk of a unified diff, created by `PatchHunks`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// First line in the old file, which is one-based like in the diff
+    pub old_start: u64,
+    /// Number of lines in the old file
+    pub old_lines: u64,
+    /// First line in the new file, which is one-based like in the diff
+    pub new_start: u64,
+    /// Number of lines in the new file
+    pub new_lines: u64,
+    /// Text after the closing `@@`, like the enclosing function
+    pub section: String,
+    /// Lines of the hunk including their ` `, `-`, `+` or `\` prefix
+    pub lines: Vec<String>,
+    /// Zero-based number of the `@@` line
+    pub line: u64,
+}
+
+/// Error of `PatchHunks`. Line numbers are zero-based.
+#[derive(thiserror::Error, Debug)]
+pub enum PatchError {
+    /// The line couldn't be read
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The hunk starting in `line` is larger than allowed
+    #[error("hunk in line {line} too large")]
+    TooLarge {
+        /// Number of the `@@` line
+        line: u64,
+    },
+    /// The `@@` line can't be parsed, or the hunk ends before its ranges are complete
+    #[error("invalid hunk in line {line}")]
+    Invalid {
+        /// Number of the offending line
+        line: u64,
+    },
+}
+
+/// Line of a diff whose `Incomplete` parts were joined
+struct DiffLine {
+    number: u64,
+    text: String,
+    /// First character, which is kept even if the rest of the line is too large
+    kind: Option<char>,
+    overflowed: bool,
+}
+
+/// Iterator over the files and hunks of a unified diff, created by `RcLineIterator::patch_hunks()`
+pub struct PatchHunks<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_hunk_size: usize,
+    /// Number of the next line
+    number: u64,
+    /// Line which ended a hunk early and is read again
+    pending: Option<DiffLine>,
+}
+
+impl<T: LineSource> PatchHunks<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Returns the next line, which is truncated once it is longer than `max_size`
+    fn read_line(&mut self, max_size: usize) -> Option<Result<DiffLine, PatchError>> {
+        if let Some(line) = self.pending.take() {
+            return Some(Ok(line));
+        }
+        let mut line = DiffLine {
+            number: self.number,
+            text: String::new(),
+            kind: None,
+            overflowed: false,
+        };
+        loop {
+            let part = match self.lines.next()? {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x,
+                Err(e) => return Some(Err(PatchError::Read(e))),
+            };
+            line.kind = line.kind.or_else(|| part.chars().next());
+            if line.overflowed || line.text.len() + part.len() > max_size {
+                line.overflowed = true;
+            } else {
+                line.text.push_str(&part);
+            }
+            if !self.lines.continues_line() {
+                self.number += 1;
+                return Some(Ok(line));
+            }
+        }
+    }
+
+    fn read_hunk(&mut self, mut hunk: Hunk) -> Result<Hunk, PatchError> {
+        let (mut old, mut new) = (hunk.old_lines, hunk.new_lines);
+        let mut size = 0;
+        let mut overflowed = false;
+        loop {
+            if old == 0 && new == 0 {
+                // Only a marker like "\ No newline at end of file" may follow the last line
+                match self.lines.peek_line() {
+                    Some(Ok(x)) if x.starts_with('\\') => {}
+                    _ => break,
+                }
+            }
+            let line = match self.read_line(self.max_hunk_size - size) {
+                Some(x) => x?,
+                None => return Err(PatchError::Invalid { line: self.number }),
+            };
+            let remaining = match line.kind {
+                Some(' ') | None if old > 0 && new > 0 => {
+                    old -= 1;
+                    new -= 1;
+                    true
+                }
+                Some('-') if old > 0 => {
+                    old -= 1;
+                    true
+                }
+                Some('+') if new > 0 => {
+                    new -= 1;
+                    true
+                }
+                Some('\\') => true,
+                _ => false,
+            };
+            if !remaining {
+                let number = line.number;
+                self.pending = Some(line);
+                return Err(PatchError::Invalid { line: number });
+            }
+            overflowed |= line.overflowed;
+            if !overflowed {
+                size += line.text.len();
+                hunk.lines.push(line.text);
+            }
+        }
+        match overflowed {
+            true => Err(PatchError::TooLarge { line: hunk.line }),
+            false => Ok(hunk),
+        }
+    }
+}
+
+/// Parses `-1,2 +3,4 @@ section` after the leading `@@ `, where the counts default to 1
+fn parse_hunk_header(text: &str, line: u64) -> Option<Hunk> {
+    let (ranges, section) = text.split_once("@@")?;
+    let mut ranges = ranges.split_whitespace();
+    let range = |x: &str| -> Option<(u64, u64)> {
+        match x.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((x.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = range(ranges.next()?.strip_prefix('+')?)?;
+    if ranges.next().is_some() {
+        return None;
+    }
+    Some(Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: section.trim().to_string(),
+        lines: Vec::new(),
+        line,
+    })
+}
+
+/// Returns the path of a `---` or `+++` line without a trailing timestamp
+fn file_path(text: &str) -> String {
+    let path = text.split('\t').next().unwrap_or_default();
+    path.trim_end().to_string()
+}
+
+impl<T: LineSource> Iterator for PatchHunks<T> {
+    type Item = Result<PatchItem, PatchError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.read_line(self.max_hunk_size)? {
+                Ok(x) => x,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Some(text) = line.text.strip_prefix("@@ ") {
+                let Some(hunk) = parse_hunk_header(text, line.number) else {
+                    return Some(Err(PatchError::Invalid { line: line.number }));
+                };
+                return Some(self.read_hunk(hunk).map(PatchItem::Hunk));
+            }
+            let Some(old) = line.text.strip_prefix("--- ") else {
+                continue;
+            };
+            match self.lines.peek_line() {
+                Some(Ok(x)) if x.starts_with("+++ ") => {}
+                _ => continue,
+            }
+            let new = match self.read_line(self.max_hunk_size)? {
+                Ok(x) => x,
+                Err(e) => return Some(Err(e)),
+            };
+            return Some(Ok(PatchItem::File {
+                old: file_path(old),
+                new: file_path(&new.text[4..]),
+                line: line.number,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn continues_after_invalid_hunks() {
+        let diff = format!(
+            "@@ -1,2 +1 @@\n-a\n@@ -1 +1 @@\n-b\n+{}\n@@ -5,0 +6 @@\n+d\n\\ No newline at end of file\n",
+            "c".repeat(40)
+        );
+        let items = std::io::Cursor::new(diff)
+            .lines_rc()
+            .patch_hunks(32)
+            .map(|x| match x {
+                Ok(PatchItem::Hunk(x)) => Ok(x.lines),
+                Err(PatchError::Invalid { line }) => Err(("invalid", line)),
+                Err(PatchError::TooLarge { line }) => Err(("large", line)),
+                x => panic!("{:?}", x),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Err(("invalid", 2)),
+                Err(("large", 2)),
+                Ok(vec![
+                    "+d".to_string(),
+                    "\\ No newline at end of file".into()
+                ])
+            ],
+            items
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::BufReadSource,
+        BufReadExt, ReadExt, ReaderLines,
+    },
+    std::{
+        fs::File,
+        io::{self, Read, StdinLock},
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// Opens the file at `path` and iterates its lines with a buffer sized to the file, but at most the default capacity of 64kb.
+/// So small files don't allocate more than they need, while lines are as bounded as with `ReadExt::lines_rc()`.
+/// Errors of opening the file are returned as `Error::Io`.
+///
+/// As the buffer is sized when the file is opened, lines appended later may be split into `Incomplete` parts.
+///
+/// With the `compress` feature, regular files starting with the magic bytes of gzip, zstd, xz or bzip2 are decompressed while they are read,
+/// regardless of their extension. Other files are read as plain text. Their lines are bounded by the default capacity of 64kb.
+/// If the compressed data is corrupt, the last line is an `Io` error describing it.
+///
+/// # Examples
+/// ```
+/// let path = std::env::temp_dir().join(format!("simple_lines_path_doc_{}", std::process::id()));
+/// std::fs::write(&path, "first\nsecond\n").unwrap();
+///
+/// let lines = simple_lines::lines_from_path(&path).unwrap();
+/// assert_eq!(2, lines.count());
+/// assert!(matches!(simple_lines::lines_from_path(path.join("missing")), Err(simple_lines::Error::Io(_))));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+///
+/// ```
+/// # #[cfg(feature = "compress")] {
+/// let path = std::env::temp_dir().join(format!("simple_lines_path_doc_gz_{}", std::process::id()));
+/// let gzip = [
+///     31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 75, 203, 44, 42, 46, 225, 42, 78, 77, 206, 207, 75, 225, 2, 0,
+///     32, 81, 69, 8, 13, 0, 0, 0,
+/// ];
+/// std::fs::write(&path, gzip).unwrap();
+///
+/// let lines = simple_lines::lines_from_path(&path).unwrap();
+/// assert_eq!(vec!["first", "second"], lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>());
+/// # std::fs::remove_file(&path).unwrap();
+/// # }
+/// ```
+pub fn lines_from_path(
+    path: impl AsRef<Path>,
+) -> Result<ReaderLines<PathReader>, crate::Error<Rc<String>>> {
+    let file = File::open(path)?;
+    let metadata = file.metadata()?;
+    #[cfg(feature = "compress")]
+    let file = match metadata.is_file() {
+        true => match crate::compress::Decompressor::open(file)? {
+            Ok(x) => return Ok(PathReader(Opened::Decompressed(x)).lines_rc()),
+            Err(file) => file,
+        },
+        false => file,
+    };
+    let capacity = match metadata.is_file() {
+        true => metadata.len().saturating_add(1).min(64 * 1024) as usize,
+        false => 64 * 1024,
+    };
+    let lines = PathReader(Opened::File(file)).lines_rc_with_capacity(capacity);
+    match metadata.is_file() {
+        true => Ok(lines.with_size_hint(metadata.len())),
+        false => Ok(lines),
+    }
+}
+
+/// Locks stdin once and iterates its lines with the default capacity of 64kb, like a CLI filter reading piped input would.
+/// Unlike `std::io::stdin().lines_rc()`, which locks stdin for every read and copies it into another buffer, lines are read from the buffer of stdin itself.
+/// Stdin stays locked until the iterator is dropped. With the `async` feature, `stdin_lines_async()` streams the lines to async code.
+///
+/// # Examples
+/// ```no_run
+/// for line in simple_lines::stdin_lines() {
+///     println!("{}", line.unwrap().to_uppercase());
+/// }
+/// ```
+pub fn stdin_lines() -> RcLineIterator<BufReadSource<StdinLock<'static>>> {
+    stdin_lines_with_capacity(64 * 1024)
+}
+
+/// Locks stdin once and iterates its lines, which are at most `max_line_length` bytes long, like `stdin_lines()`
+pub fn stdin_lines_with_capacity(
+    max_line_length: usize,
+) -> RcLineIterator<BufReadSource<StdinLock<'static>>> {
+    BufReadExt::lines_rc_with_capacity(io::stdin().lock(), max_line_length)
+}
+
+/// Reader of the file opened by `lines_from_path()`
+pub struct PathReader(Opened);
+
+enum Opened {
+    File(File),
+    #[cfg(feature = "compress")]
+    Decompressed(crate::compress::Decompressor),
+}
+
+impl Read for PathReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            Opened::File(x) => x.read(buf),
+            #[cfg(feature = "compress")]
+            Opened::Decompressed(x) => x.read(buf),
+        }
+    }
+}
+
+/// Iterates the lines of all `paths` one after the other, tagging each with its path and zero-based line number, like `grep -n` over many files.
+/// Files are opened when their first line is read.
+///
+/// A file which can't be opened or fails while it is read yields an `Io` error, after which the iteration continues with the next file.
+/// Use `PathLines::with_stop_on_error()` to end the iteration instead.
+///
+/// # Examples
+/// ```
+/// let dir = std::env::temp_dir().join(format!("simple_lines_paths_doc_{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("a.txt"), "a1\na2\n").unwrap();
+///
+/// let mut lines = simple_lines::lines_from_paths([dir.join("missing.txt"), dir.join("a.txt")]);
+/// let missing = lines.next().unwrap();
+/// assert!(missing.path.ends_with("missing.txt") && missing.line.is_err());
+/// let line = lines.next().unwrap();
+/// assert!(line.path.ends_with("a.txt"));
+/// assert_eq!((0, "a1"), (line.number, line.line.unwrap().as_str()));
+/// assert_eq!(1, lines.next().unwrap().number);
+/// assert!(lines.next().is_none());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn lines_from_paths<P: Into<PathBuf>>(paths: impl IntoIterator<Item = P>) -> PathLines {
+    PathLines {
+        paths: paths
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .into_iter(),
+        current: None,
+        max_line_length: 64 * 1024,
+        stop_on_error: false,
+        finished: false,
+    }
+}
+
+/// Line of one of multiple files, created by `PathLines`
+#[derive(Debug)]
+pub struct PathLine {
+    /// Path of the file, which is shared by all of its lines
+    pub path: Rc<Path>,
+    /// Zero-based number of the line within its file
+    pub number: u64,
+    /// Content of the line, or the error which ended the file
+    pub line: RcLine,
+}
+
+/// File which is currently read
+pub(crate) struct OpenFile {
+    pub path: Rc<Path>,
+    /// Number of the next line
+    pub number: u64,
+    pub lines: ReaderLines<File>,
+}
+
+impl OpenFile {
+    pub fn new(path: PathBuf, lines: ReaderLines<File>) -> Self {
+        Self {
+            path: path.into(),
+            number: 0,
+            lines,
+        }
+    }
+
+    /// Returns the next line tagged with the path and number
+    pub fn next_line(&mut self) -> Option<PathLine> {
+        let line = self.lines.next()?;
+        let number = self.number;
+        if !self.lines.continues_line() {
+            self.number += 1;
+        }
+        Some(PathLine {
+            path: self.path.clone(),
+            number,
+            line,
+        })
+    }
+}
+
+/// Iterator over the lines of multiple files, created by `lines_from_paths()`
+pub struct PathLines {
+    paths: std::vec::IntoIter<PathBuf>,
+    current: Option<OpenFile>,
+    max_line_length: usize,
+    stop_on_error: bool,
+    finished: bool,
+}
+
+impl PathLines {
+    /// Lines longer than `max_line_length` are split into `Incomplete` parts. Defaults to 64kb.
+    pub fn with_capacity(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Ends the iteration after the first file which couldn't be opened or read, instead of continuing with the next one
+    pub fn with_stop_on_error(mut self) -> Self {
+        self.stop_on_error = true;
+        self
+    }
+}
+
+impl Iterator for PathLines {
+    type Item = PathLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let Some(file) = self.current.as_mut() else {
+                let path = self.paths.next()?;
+                match File::open(&path) {
+                    Ok(x) => {
+                        let lines = x.lines_rc_with_capacity(self.max_line_length);
+                        self.current = Some(OpenFile::new(path, lines));
+                    }
+                    Err(e) => {
+                        self.finished = self.stop_on_error;
+                        return Some(PathLine {
+                            path: path.into(),
+                            number: 0,
+                            line: Err(e.into()),
+                        });
+                    }
+                }
+                continue;
+            };
+            let Some(line) = file.next_line() else {
+                self.current = None;
+                continue;
+            };
+            if let Err(crate::Error::Io(_)) = line.line {
+                self.current = None;
+                self.finished = self.stop_on_error;
+            }
+            return Some(line);
+        }
+        None
+    }
+}
+
+/// Number of names tried by `create_temp_file()` before giving up
+const TEMP_FILE_ATTEMPTS: usize = 100;
+
+/// Creates a file at the first path returned by `path` for increasing ids which doesn't exist yet.
+/// The file is created exclusively, so neither existing files nor symlinks planted at a predictable name are followed.
+/// On Unix, it is only accessible by the owner.
+pub(crate) fn create_temp_file(
+    mut path: impl FnMut(usize) -> PathBuf,
+) -> io::Result<(PathBuf, File)> {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    for _ in 0..TEMP_FILE_ATTEMPTS {
+        let candidate = path(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        match options.open(&candidate) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            x => return x.map(|file| (candidate, file)),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "no unused name for a temporary file",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_on_error_if_configured() {
+        let dir = std::env::temp_dir().join(format!("simple_lines_paths_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        let paths = [dir.join("missing.txt"), dir.join("a.txt")];
+        assert_eq!(2, lines_from_paths(paths.clone()).count());
+        assert_eq!(1, lines_from_paths(paths).with_stop_on_error().count());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
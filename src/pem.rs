@@ -0,0 +1,237 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields the blocks between `-----BEGIN <label>-----` and `-----END <label>-----` lines, like certificates and keys in a PEM bundle.
+    /// Lines outside of blocks, like explanatory text, are skipped.
+    ///
+    /// Blocks whose base64 content is larger than `max_block_size` bytes are rejected without being buffered,
+    /// and the iteration continues after their end. Errors in one block don't affect the following blocks.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let bundle = "Subject: test\n-----BEGIN CERTIFICATE-----\naGVs\nbG8=\n-----END CERTIFICATE-----\n";
+    /// let mut blocks = std::io::Cursor::new(bundle).lines_rc().pem_blocks(64 * 1024);
+    /// let block = blocks.next().unwrap().unwrap();
+    /// assert_eq!(("CERTIFICATE", "aGVsbG8="), (block.label.as_str(), block.base64.as_str()));
+    /// assert_eq!(b"hello".to_vec(), block.decode().unwrap());
+    /// assert!(blocks.next().is_none());
+    /// ```
+    pub fn pem_blocks(self, max_block_size: usize) -> PemBlocks<T> {
+        PemBlocks {
+            lines: self,
+            max_block_size,
+            number: 0,
+            is_first_part: true,
+            block: None,
+        }
+    }
+}
+
+/// Block of a PEM file, created by `PemBlocks`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemBlock {
+    /// Label of the `BEGIN` and `END` lines, like `CERTIFICATE`
+    pub label: String,
+    /// Base64 encoded content without linebreaks
+    pub base64: String,
+}
+
+impl PemBlock {
+    /// Decodes the base64 content
+    pub fn decode(&self) -> Result<Vec<u8>, PemError> {
+        decode_base64(self.base64.as_bytes()).ok_or(PemError::Base64)
+    }
+}
+
+/// Error of `PemBlocks`. Line numbers are zero-based.
+#[derive(thiserror::Error, Debug)]
+pub enum PemError {
+    /// The line couldn't be read, which discards the current block
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The block starting in `line` is larger than allowed
+    #[error("block in line {line} too large")]
+    TooLarge {
+        /// Number of the `BEGIN` line
+        line: u64,
+    },
+    /// The block starting in `line` isn't ended by an `END` line with the same label
+    #[error("block in line {line} not ended")]
+    Unterminated {
+        /// Number of the `BEGIN` line
+        line: u64,
+    },
+    /// The content isn't valid base64
+    #[error("invalid base64")]
+    Base64,
+}
+
+/// Block which is currently read
+struct OpenBlock {
+    line: u64,
+    label: String,
+    base64: String,
+    /// Whether the block is too large and its content is skipped
+    overflowed: bool,
+}
+
+/// Iterator over PEM blocks, created by `RcLineIterator::pem_blocks()`
+pub struct PemBlocks<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_block_size: usize,
+    /// Number of the next line
+    number: u64,
+    /// Whether the next item starts a line
+    is_first_part: bool,
+    block: Option<OpenBlock>,
+}
+
+impl<T: LineSource> PemBlocks<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Starts a new block at `line` if it is a `BEGIN` line
+    fn begin(&mut self, line: &str, number: u64) {
+        self.block = boundary(line, "BEGIN").map(|label| OpenBlock {
+            line: number,
+            label: label.to_string(),
+            base64: String::new(),
+            overflowed: false,
+        });
+    }
+}
+
+/// Returns the label of a `-----<kind> <label>-----` line
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.trim_end()
+        .strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")
+}
+
+impl<T: LineSource> Iterator for PemBlocks<T> {
+    type Item = Result<PemBlock, PemError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(line) = self.lines.next() else {
+                return self
+                    .block
+                    .take()
+                    .map(|x| Err(PemError::Unterminated { line: x.line }));
+            };
+            let number = self.number;
+            let is_first_part =
+                std::mem::replace(&mut self.is_first_part, !self.lines.continues_line());
+            if self.is_first_part {
+                self.number += 1;
+            }
+            let (line, is_complete) = match line {
+                Ok(x) => (x, is_first_part),
+                Err(crate::Error::Incomplete(x)) => (x, false),
+                Err(e) => {
+                    self.block = None;
+                    return Some(Err(PemError::Read(e)));
+                }
+            };
+            let Some(block) = self.block.as_mut() else {
+                if is_complete {
+                    self.begin(&line, number);
+                }
+                continue;
+            };
+            if is_complete && boundary(&line, "END") == Some(block.label.as_str()) {
+                let block = self.block.take()?;
+                return Some(match block.overflowed {
+                    true => Err(PemError::TooLarge { line: block.line }),
+                    false => Ok(PemBlock {
+                        label: block.label,
+                        base64: block.base64,
+                    }),
+                });
+            }
+            if is_complete && line.starts_with("-----") {
+                // A boundary which doesn't end this block is either the start of the next one or garbage
+                let start = block.line;
+                self.begin(&line, number);
+                return Some(Err(PemError::Unterminated { line: start }));
+            }
+            let content = line.trim();
+            if block.overflowed || block.base64.len() + content.len() > self.max_block_size {
+                block.overflowed = true;
+                block.base64 = String::new();
+                continue;
+            }
+            block.base64.push_str(content);
+        }
+    }
+}
+
+/// Decodes padded standard base64 as used by PEM (RFC 7468)
+fn decode_base64(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut result = Vec::with_capacity(text.len() / 4 * 3);
+    let chunks = text.chunks_exact(4);
+    let last = chunks.len().checked_sub(1);
+    for (i, chunk) in chunks.enumerate() {
+        let padding = chunk.iter().rev().take_while(|x| **x == b'=').count();
+        if padding > 2 || (padding > 0 && Some(i) != last) {
+            return None;
+        }
+        let mut value = 0u32;
+        for x in &chunk[..4 - padding] {
+            let digit = match x {
+                b'A'..=b'Z' => x - b'A',
+                b'a'..=b'z' => x - b'a' + 26,
+                b'0'..=b'9' => x - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            value = value << 6 | digit as u32;
+        }
+        value <<= 6 * padding;
+        result.extend_from_slice(&value.to_be_bytes()[1..4 - padding]);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn continues_after_rejected_blocks() {
+        let bundle = "-----BEGIN A-----\nQUFB\nQUFB\n-----END A-----\n\
+            -----BEGIN B-----\nQg==\n-----BEGIN C-----\nQw==\n-----END C-----\n\
+            -----BEGIN D-----\nRA==";
+        let blocks = std::io::Cursor::new(bundle)
+            .lines_rc()
+            .pem_blocks(6)
+            .map(|x| match x {
+                Ok(x) => Ok(x.decode().unwrap()),
+                Err(PemError::TooLarge { line }) => Err(("large", line)),
+                Err(PemError::Unterminated { line }) => Err(("unterminated", line)),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Err(("large", 0)),
+                Err(("unterminated", 4)),
+                Ok(b"C".to_vec()),
+                Err(("unterminated", 9))
+            ],
+            blocks
+        );
+    }
+}
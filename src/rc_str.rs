@@ -0,0 +1,65 @@
+use {
+    crate::{bound::BorrowedLines, source::LineSource},
+    alloc::rc::Rc,
+};
+
+/// Iterator over the lines of a reader as `Rc<str>`, created by `RcLineIterator::into_rc_str()`
+pub struct RcStrLineIterator<TSource: LineSource> {
+    lines: BorrowedLines<TSource>,
+}
+
+impl<T: LineSource> RcStrLineIterator<T> {
+    pub(crate) fn new(lines: BorrowedLines<T>) -> Self {
+        Self { lines }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<TSource: LineSource> core::iter::FusedIterator for RcStrLineIterator<TSource> {}
+
+impl<TSource: LineSource> Iterator for RcStrLineIterator<TSource> {
+    type Item = Result<Rc<str>, crate::Error<Rc<str>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(Rc::from(x)),
+            Err(e) => Err(e.map_incomplete(Rc::from)),
+        })
+    }
+
+    /// Skips lines without validating or copying them
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating or copying them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn returns_incomplete_as_rc_str() {
+        let mut lines = std::io::Cursor::new("1234567\nab")
+            .lines_rc_with_capacity(5)
+            .into_rc_str();
+        match lines.next().unwrap() {
+            Err(crate::Error::Incomplete(x)) => assert_eq!("12345", &*x),
+            x => panic!("Expected Incomplete, got {:?}", x),
+        }
+        assert!(lines.next().unwrap().is_err());
+        assert_eq!("ab", &*lines.next().unwrap().unwrap());
+        assert!(lines.next().is_none());
+    }
+}
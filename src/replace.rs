@@ -0,0 +1,256 @@
+use {
+    crate::{
+        bound::BorrowedLines, paths::create_temp_file, source::BufReadSource,
+        terminator::Terminator,
+    },
+    std::{
+        borrow::Cow,
+        fs::File,
+        io::{BufReader, BufWriter, Read, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Maximum length of a line passed to the closure of `replace_lines()`
+const MAX_SIZE: usize = 64 * 1024;
+
+/// Writes each line of `reader` to `writer` as it is returned by `replace`, like a minimal `sed`.
+/// The original linebreak of each line, `\n`, `\r\n` or none for the last line, is written after it.
+/// Only the current line is kept in memory.
+///
+/// The first error of a line aborts the rewrite and is returned, including `Incomplete` for lines longer than 64kb.
+/// The lines in front of it are written already.
+///
+/// # Examples
+/// ```
+/// use std::borrow::Cow;
+///
+/// let mut output = Vec::new();
+/// simple_lines::replace_lines("a\r\nfoo\nb".as_bytes(), &mut output, |line| match line {
+///     "foo" => Cow::Borrowed("bar"),
+///     _ => Cow::Owned(line.to_uppercase()),
+/// })
+/// .unwrap();
+/// assert_eq!(b"A\r\nbar\nB", &output[..]);
+/// ```
+pub fn replace_lines<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    mut replace: impl FnMut(&str) -> Cow<'_, str>,
+) -> Result<(), crate::Error<String>> {
+    let source = BufReadSource::new(BufReader::with_capacity(MAX_SIZE, reader), MAX_SIZE);
+    let mut lines = BorrowedLines::new(source, MAX_SIZE);
+    let mut writer = BufWriter::new(writer);
+    let mut consumed = 0;
+    while let Some(line) = lines.next_str() {
+        let line = line.map_err(|e| e.map_incomplete(str::to_string))?;
+        let length = line.len() as u64;
+        writer.write_all(replace(line).as_bytes())?;
+        let bytes = lines.stats().bytes;
+        writer.write_all(
+            Terminator::from_len(bytes - consumed - length)
+                .as_str()
+                .as_bytes(),
+        )?;
+        consumed = bytes;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Totals of `copy_lines()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopySummary {
+    /// Lines written to the writer
+    pub copied: u64,
+    /// Lines for which the transform returned `None`
+    pub skipped: u64,
+    /// Lines which couldn't be read, like invalid UTF8 or lines longer than 64kb, or for which the transform failed
+    pub errors: u64,
+}
+
+/// Reads the lines of `reader`, passes them to `transform` and writes the results to `writer`, like a filter from one file to another.
+/// If `transform` returns `None` or an error, the line is dropped. The original linebreak is kept like in `replace_lines()`.
+///
+/// Unlike `replace_lines()`, lines which can't be read are dropped and counted in the returned summary instead of aborting the copy,
+/// so a single malformed line doesn't stop the pipeline. Only `Io` errors of the reader or writer are returned.
+///
+/// # Examples
+/// ```
+/// let mut output = Vec::new();
+/// let summary = simple_lines::copy_lines("1\nx\n-\n4\n".as_bytes(), &mut output, |line| match line {
+///     "-" => Ok(None),
+///     _ => line.parse::<u32>().map(|x| Some((x * 10).to_string().into())),
+/// })
+/// .unwrap();
+/// assert_eq!(b"10\n40\n", &output[..]);
+/// assert_eq!((2, 1, 1), (summary.copied, summary.skipped, summary.errors));
+/// ```
+pub fn copy_lines<R: Read, W: Write, E>(
+    reader: R,
+    writer: W,
+    mut transform: impl FnMut(&str) -> Result<Option<Cow<'_, str>>, E>,
+) -> std::io::Result<CopySummary> {
+    let source = BufReadSource::new(BufReader::with_capacity(MAX_SIZE, reader), MAX_SIZE);
+    let mut lines = BorrowedLines::new(source, MAX_SIZE);
+    let mut writer = BufWriter::new(writer);
+    let mut summary = CopySummary::default();
+    let mut consumed = 0;
+    while let Some(line) = lines.next_str() {
+        let line = match line {
+            Ok(x) => x,
+            Err(crate::Error::Io(e)) => return Err(e),
+            Err(_) => {
+                consumed = lines.stats().bytes;
+                if !lines.continues_line() {
+                    summary.errors += 1;
+                }
+                continue;
+            }
+        };
+        let length = line.len() as u64;
+        match transform(line) {
+            Ok(Some(x)) => {
+                writer.write_all(x.as_bytes())?;
+                writer.write_all(
+                    Terminator::from_len(lines.stats().bytes - consumed - length)
+                        .as_str()
+                        .as_bytes(),
+                )?;
+                summary.copied += 1;
+            }
+            Ok(None) => summary.skipped += 1,
+            Err(_) => summary.errors += 1,
+        }
+        consumed = lines.stats().bytes;
+    }
+    writer.flush()?;
+    Ok(summary)
+}
+
+/// Like `replace_lines()`, but replaces all matches of `regex` in each line by `replacement`, as `regex::Regex::replace_all()` does
+///
+/// # Examples
+/// ```
+/// let regex = regex::Regex::new("[0-9]+").unwrap();
+/// let mut output = Vec::new();
+/// simple_lines::replace_lines_regex("a1b22\n3".as_bytes(), &mut output, &regex, "#").unwrap();
+/// assert_eq!(b"a#b#\n#", &output[..]);
+/// ```
+#[cfg(feature = "regex")]
+pub fn replace_lines_regex<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    regex: &regex::Regex,
+    replacement: &str,
+) -> Result<(), crate::Error<String>> {
+    replace_lines(reader, writer, |line| regex.replace_all(line, replacement))
+}
+
+/// Rewrites the file at `path` with `replace_lines()`. The result is written to a temporary file in the same directory,
+/// which replaces the original by an atomic rename once it is complete, so readers see either the old or the new content.
+/// The permissions of the original file are kept. If `path` is a symlink, the file it refers to is rewritten and the symlink is kept.
+///
+/// On errors, the temporary file is removed and the original is left untouched.
+///
+/// # Examples
+/// ```
+/// let path = std::env::temp_dir().join(format!("simple_lines_edit_doc_{}", std::process::id()));
+/// std::fs::write(&path, "a\nb\n").unwrap();
+/// simple_lines::edit_file_lines(&path, |line| line.to_uppercase().into()).unwrap();
+/// assert_eq!("A\nB\n", std::fs::read_to_string(&path).unwrap());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn edit_file_lines(
+    path: impl AsRef<Path>,
+    replace: impl FnMut(&str) -> Cow<'_, str>,
+) -> Result<(), crate::Error<String>> {
+    // The symlink itself is kept, the file it refers to is replaced instead
+    let path = std::fs::canonicalize(path)?;
+    let input = File::open(&path)?;
+    let permissions = input.metadata()?.permissions();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (temp, output) = create_temp_file(|id| {
+        path.with_file_name(format!(
+            ".{}.simple_lines_{}_{}",
+            name,
+            std::process::id(),
+            id
+        ))
+    })?;
+    let temp = TempFile(temp);
+    replace_lines(input, &output, replace)?;
+    output.set_permissions(permissions)?;
+    output.sync_all()?;
+    std::fs::rename(&temp.0, &path)?;
+    std::mem::forget(temp);
+    Ok(())
+}
+
+/// Temporary file, which is removed on drop unless it is forgotten
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aborts_on_first_error_after_writing_preceding_lines() {
+        let mut output = Vec::new();
+        let result = replace_lines(&b"a\r\n\n\xffb\nc"[..], &mut output, |x| Cow::Borrowed(x));
+        assert!(matches!(result, Err(crate::Error::Encoding(_))));
+        assert_eq!(b"a\r\n\n", &output[..]);
+    }
+
+    #[test]
+    fn copy_counts_unreadable_lines() {
+        let mut output = Vec::new();
+        let mut input = b"\xff\na\r\n".to_vec();
+        input.resize(input.len() + MAX_SIZE + 1, b'x');
+        input.extend(b"\nb");
+        let summary = copy_lines(&input[..], &mut output, |x| {
+            Ok::<_, ()>(Some(Cow::Borrowed(x)))
+        })
+        .unwrap();
+        assert_eq!(b"a\r\nb", &output[..]);
+        assert_eq!((2, 0, 2), (summary.copied, summary.skipped, summary.errors));
+    }
+
+    #[test]
+    fn edit_keeps_original_on_error() {
+        let dir = std::env::temp_dir().join(format!("simple_lines_edit_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input");
+        std::fs::write(&path, b"a\n\xff\n").unwrap();
+        let result = edit_file_lines(&path, |x| Cow::Borrowed(x));
+        assert!(matches!(result, Err(crate::Error::Encoding(_))));
+        assert_eq!(b"a\n\xff\n", &std::fs::read(&path).unwrap()[..]);
+        assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn edit_rewrites_symlink_target() {
+        let dir =
+            std::env::temp_dir().join(format!("simple_lines_edit_link_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("target");
+        let link = dir.join("link");
+        std::fs::write(&target, "a\n").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        edit_file_lines(&link, |x| x.to_uppercase().into()).unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!("A\n", std::fs::read_to_string(&target).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
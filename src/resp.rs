@@ -0,0 +1,253 @@
+use {
+    crate::{bound::BorrowedLines, source::BufReadSource},
+    std::io::{self, BufRead, Read},
+};
+
+/// Limits of `resp_frames()`
+#[derive(Debug, Clone)]
+pub struct RespLimits {
+    max_line_length: usize,
+    max_bulk_length: usize,
+    max_array_length: usize,
+    max_depth: usize,
+}
+
+impl Default for RespLimits {
+    fn default() -> Self {
+        Self {
+            max_line_length: 64 * 1024,
+            max_bulk_length: 512 * 1024 * 1024,
+            max_array_length: 1024 * 1024,
+            max_depth: 32,
+        }
+    }
+}
+
+impl RespLimits {
+    /// Maximum length of simple strings, errors and integers without CRLF. Defaults to 64kb.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Maximum length of bulk strings, which are rejected before they are read. Defaults to 512mb like in Redis.
+    pub fn with_max_bulk_length(mut self, max_bulk_length: usize) -> Self {
+        self.max_bulk_length = max_bulk_length;
+        self
+    }
+
+    /// Maximum number of elements of an array. Defaults to 1048576.
+    pub fn with_max_array_length(mut self, max_array_length: usize) -> Self {
+        self.max_array_length = max_array_length;
+        self
+    }
+
+    /// Maximum nesting of arrays, where an array which doesn't contain other arrays has a depth of 1. Defaults to 32.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+/// Value of the Redis serialization protocol (RESP2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RespValue {
+    /// `+OK`
+    SimpleString(String),
+    /// `-ERR message`
+    Error(String),
+    /// `:42`
+    Integer(i64),
+    /// `$3\r\nfoo`, or `None` for the null bulk string `$-1`
+    BulkString(Option<Vec<u8>>),
+    /// `*2\r\n...`, or `None` for the null array `*-1`
+    Array(Option<Vec<RespValue>>),
+}
+
+/// Error of a RESP frame, which ends the iteration because the following frames can't be found anymore
+#[derive(thiserror::Error, Debug)]
+pub enum RespError {
+    /// Forwarded errors from the underlying reader
+    #[error("io")]
+    Io(#[from] io::Error),
+    /// A simple string or error contains invalid UTF8
+    #[error("encoding")]
+    Encoding(#[from] std::str::Utf8Error),
+    /// A line is longer than `RespLimits::with_max_line_length()`
+    #[error("line too long")]
+    LineTooLong,
+    /// A bulk string or array is longer than its limit
+    #[error("frame of length {length} too large")]
+    TooLarge {
+        /// Announced number of bytes or elements
+        length: u64,
+    },
+    /// Arrays are nested deeper than `RespLimits::with_max_depth()`
+    #[error("arrays nested too deep")]
+    TooDeep,
+    /// Unknown type, invalid number or missing CRLF after a bulk string
+    #[error("invalid frame")]
+    Invalid,
+    /// The reader ended within a frame
+    #[error("unexpected end of frame")]
+    UnexpectedEof,
+}
+
+/// Iterates the RESP values of a connection, e.g. the commands sent to a Redis compatible server.
+/// Every length is checked against `limits` before anything is allocated for it.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{RespLimits, RespValue};
+///
+/// let input = "*2\r\n$3\r\nGET\r\n$-1\r\n+OK\r\n";
+/// let values = simple_lines::resp_frames(input.as_bytes(), RespLimits::default());
+/// let values = values.collect::<Result<Vec<_>, _>>().unwrap();
+/// let get = RespValue::BulkString(Some(b"GET".to_vec()));
+/// assert_eq!(RespValue::Array(Some(vec![get, RespValue::BulkString(None)])), values[0]);
+/// assert_eq!(RespValue::SimpleString("OK".into()), values[1]);
+/// ```
+pub fn resp_frames<B: BufRead>(reader: B, limits: RespLimits) -> RespFrames<B> {
+    let max_size = limits.max_line_length + 2;
+    RespFrames {
+        lines: BorrowedLines::new(BufReadSource::new(reader, max_size), max_size),
+        limits,
+        finished: false,
+    }
+}
+
+/// Iterator over RESP values, created by `resp_frames()`
+pub struct RespFrames<B: BufRead> {
+    lines: BorrowedLines<BufReadSource<B>>,
+    limits: RespLimits,
+    finished: bool,
+}
+
+impl<B: BufRead> RespFrames<B> {
+    /// Returns the reader positioned after the last returned value
+    pub fn into_inner(self) -> B {
+        self.lines.into_source().into_inner()
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Option<RespValue>, RespError> {
+        let line = match self.lines.next_str() {
+            Some(Ok(x)) => x,
+            Some(Err(crate::Error::Io(e))) => return Err(e.into()),
+            Some(Err(crate::Error::Encoding(e))) => return Err(e.into()),
+            Some(Err(_)) => return Err(RespError::LineTooLong),
+            None if depth == 0 => return Ok(None),
+            None => return Err(RespError::UnexpectedEof),
+        };
+        let (kind, rest) = match line.as_bytes().first() {
+            Some(x) => (*x, &line[1..]),
+            None => return Err(RespError::Invalid),
+        };
+        let value = match kind {
+            b'+' => RespValue::SimpleString(rest.to_string()),
+            b'-' => RespValue::Error(rest.to_string()),
+            b':' => RespValue::Integer(rest.parse().map_err(|_| RespError::Invalid)?),
+            b'$' => match parse_length(rest, self.limits.max_bulk_length)? {
+                Some(length) => RespValue::BulkString(Some(self.read_bulk(length)?)),
+                None => RespValue::BulkString(None),
+            },
+            b'*' => {
+                if depth >= self.limits.max_depth {
+                    return Err(RespError::TooDeep);
+                }
+                match parse_length(rest, self.limits.max_array_length)? {
+                    Some(length) => RespValue::Array(Some(
+                        (0..length)
+                            .map(|_| self.read_value(depth + 1)?.ok_or(RespError::UnexpectedEof))
+                            .collect::<Result<_, _>>()?,
+                    )),
+                    None => RespValue::Array(None),
+                }
+            }
+            _ => return Err(RespError::Invalid),
+        };
+        Ok(Some(value))
+    }
+
+    /// Reads a bulk string of `length` bytes and its CRLF. The memory grows with the data received instead of being reserved
+    /// for the announced length, so a large length alone doesn't allocate.
+    fn read_bulk(&mut self, length: usize) -> Result<Vec<u8>, RespError> {
+        let mut bulk = Vec::new();
+        let inner = self.lines.source_mut().inner_mut();
+        inner
+            .by_ref()
+            .take(length as u64 + 2)
+            .read_to_end(&mut bulk)?;
+        if bulk.len() < length + 2 {
+            return Err(RespError::UnexpectedEof);
+        }
+        if !bulk.ends_with(b"\r\n") {
+            return Err(RespError::Invalid);
+        }
+        bulk.truncate(length);
+        Ok(bulk)
+    }
+}
+
+/// Parses the length of a bulk string or array, which is `None` for `-1`
+fn parse_length(text: &str, max: usize) -> Result<Option<usize>, RespError> {
+    match text.parse::<i64>().map_err(|_| RespError::Invalid)? {
+        -1 => Ok(None),
+        x if x < 0 => Err(RespError::Invalid),
+        x if x as u64 > max as u64 => Err(RespError::TooLarge { length: x as u64 }),
+        x => Ok(Some(x as usize)),
+    }
+}
+
+impl<B: BufRead> Iterator for RespFrames<B> {
+    type Item = Result<RespValue, RespError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let value = self.read_value(0).transpose();
+        self.finished = !matches!(value, Some(Ok(_)));
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_announced_bulk_incrementally() {
+        let mut frames = resp_frames("$100000000\r\nabc".as_bytes(), RespLimits::default());
+        assert!(matches!(frames.next(), Some(Err(RespError::UnexpectedEof))));
+    }
+
+    #[test]
+    fn enforces_limits_before_allocating() {
+        let first =
+            |input: &str, limits: RespLimits| resp_frames(input.as_bytes(), limits).next().unwrap();
+        let limits = RespLimits::default;
+        assert!(matches!(
+            first("$1000000000\r\n", limits()),
+            Err(RespError::TooLarge { length: 1000000000 })
+        ));
+        assert!(matches!(
+            first("*1\r\n*1\r\n:1\r\n", limits().with_max_depth(1)),
+            Err(RespError::TooDeep)
+        ));
+        assert!(matches!(
+            first("+OK OK\r\n", limits().with_max_line_length(3)),
+            Err(RespError::LineTooLong)
+        ));
+        assert!(matches!(
+            first("$3\r\nabcd\r\n", limits()),
+            Err(RespError::Invalid)
+        ));
+        assert!(matches!(
+            first("*2\r\n:1\r\n", limits()),
+            Err(RespError::UnexpectedEof)
+        ));
+        assert_eq!(
+            RespValue::Integer(-5),
+            first(":-5\r\n", limits().with_max_depth(0)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use {
+    crate::{bound::RcLine, source::LineSource, SeekExt},
+    std::{
+        fs::File,
+        io::{self, Read, Seek, SeekFrom},
+        path::Path,
+        time::Instant,
+    },
+};
+
+/// Number of bytes read at once when moving backwards
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Splits a seekable stream into lines from its end to its start.
+/// Lines longer than `max_size` are cut from their end, so their first part is the shortest one.
+/// If that part would be empty, an empty chunk is returned to end the line, like the linebreak does when reading forwards.
+pub struct RevSource<TRead: Read + Seek> {
+    inner: TRead,
+    max_size: usize,
+    buffer: Vec<u8>,
+    /// Stream position of `buffer[0]`
+    buffer_start: u64,
+    /// Stream position up to which lines are not returned yet. `None` until the end is known.
+    end: Option<u64>,
+    after_full: bool,
+}
+
+impl<T: Read + Seek> RevSource<T> {
+    pub fn new(inner: T, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be greater than 0");
+        Self {
+            inner,
+            max_size,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            end: None,
+            after_full: false,
+        }
+    }
+
+    /// Makes sure `buffer` contains the next chunk in front of `end`. Bytes which are buffered already are kept,
+    /// so only the missing block in front of them is read, and only once they don't hold a whole chunk anymore.
+    fn fill(&mut self) -> io::Result<u64> {
+        let end = match self.end {
+            Some(x) => x,
+            None => {
+                let end = self.inner.seek(SeekFrom::End(0))?;
+                self.end = Some(end);
+                self.buffer.clear();
+                self.buffer_start = end;
+                end
+            }
+        };
+        let buffered = (end - self.buffer_start) as usize;
+        if self.buffer_start == 0 || self.holds_chunk(&self.buffer[..buffered]) {
+            return Ok(end);
+        }
+        // The chunk, its linebreak and the byte in front of it
+        let needed = self.max_size + 3;
+        let missing =
+            (needed.saturating_sub(buffered).max(BLOCK_SIZE) as u64).min(self.buffer_start);
+        let start = self.buffer_start - missing;
+        self.buffer.truncate(buffered);
+        self.buffer
+            .splice(0..0, std::iter::repeat_n(0, missing as usize));
+        let read = self
+            .inner
+            .seek(SeekFrom::Start(start))
+            .and_then(|_| self.inner.read_exact(&mut self.buffer[..missing as usize]));
+        if let Err(e) = read {
+            self.buffer.drain(..missing as usize);
+            return Err(e);
+        }
+        self.buffer_start = start;
+        Ok(end)
+    }
+
+    /// Returns whether the start of the last chunk of `data` is known, so it doesn't depend on the bytes in front of it
+    fn holds_chunk(&self, data: &[u8]) -> bool {
+        let content_end = match data {
+            [.., b'\r', b'\n'] => data.len() - 2,
+            // A single linebreak may be preceded by a carriage return in front of `data`, but then there is no content to check
+            [.., b'\n'] => data.len() - 1,
+            _ => data.len(),
+        };
+        content_end >= self.max_size || memchr::memchr(b'\n', &data[..content_end]).is_some()
+    }
+}
+
+impl<T: Read + Seek> LineSource for RevSource<T> {
+    fn next_line(&mut self) -> Option<io::Result<&[u8]>> {
+        let end = match self.fill() {
+            Ok(x) => x,
+            Err(e) => return Some(Err(e)),
+        };
+        let data = &self.buffer[0..(end - self.buffer_start) as usize];
+        if std::mem::take(&mut self.after_full) && matches!(data.last(), None | Some(b'\n')) {
+            return Some(Ok(&[]));
+        }
+        if data.is_empty() {
+            return None;
+        }
+
+        let delimiter = match data {
+            [.., b'\r', b'\n'] => 2,
+            [.., b'\n'] => 1,
+            _ => 0,
+        };
+        let content_end = data.len() - delimiter;
+        let window_start = content_end.saturating_sub(self.max_size);
+        let start = match memchr::memrchr(b'\n', &data[window_start..content_end]) {
+            Some(pos) => window_start + pos + 1,
+            None => {
+                self.after_full = content_end - window_start == self.max_size;
+                window_start
+            }
+        };
+        self.end = Some(self.buffer_start + start as u64);
+        Some(Ok(&data[start..]))
+    }
+
+    fn set_deadline(&mut self, _deadline: Option<Instant>) {}
+
+    fn peek(&mut self) -> io::Result<&[u8]> {
+        let end = self.fill()?;
+        Ok(&self.buffer[0..(end - self.buffer_start) as usize])
+    }
+}
+
+/// Returns the last `n` lines of the file at `path` in their original order, without reading the lines in front of them.
+/// Parts of lines longer than 64kb are counted as separate lines.
+///
+/// # Examples
+/// ```no_run
+/// let last = simple_lines::tail_last_n("/var/log/syslog", 10).unwrap();
+/// for line in last {
+///     println!("{}", line.unwrap());
+/// }
+/// ```
+pub fn tail_last_n(path: impl AsRef<Path>, n: usize) -> io::Result<Vec<RcLine>> {
+    Ok(last_n(File::open(path)?, n))
+}
+
+fn last_n<T: Read + Seek>(reader: T, n: usize) -> Vec<RcLine> {
+    let mut lines = reader.rev_lines_rc().take(n).collect::<Vec<_>>();
+    lines.reverse();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::BLOCK_SIZE,
+        crate::SeekExt,
+        std::{
+            cell::Cell,
+            io::{Cursor, Read, Seek, SeekFrom},
+        },
+    };
+
+    /// Counts the bytes read from the wrapped reader
+    struct Counting<'a, T>(T, &'a Cell<usize>);
+
+    impl<T: Read> Read for Counting<'_, T> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.0.read(buf)?;
+            self.1.set(self.1.get() + read);
+            Ok(read)
+        }
+    }
+
+    impl<T: Seek> Seek for Counting<'_, T> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    fn numbers(linebreak: &str) -> String {
+        (0..20_000).map(|x| format!("{}{}", x, linebreak)).collect()
+    }
+
+    #[test]
+    fn cuts_long_lines_from_their_end() {
+        let lines = Cursor::new("one\r\n12345678\n\nabcde\n1234")
+            .rev_lines_rc_with_capacity(5)
+            .map(|x| match x {
+                Ok(x) => format!("Ok({})", x),
+                Err(crate::Error::Incomplete(x)) => format!("Incomplete({})", x),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "Ok(1234)",
+                "Incomplete(abcde)",
+                "Incomplete()",
+                "Ok()",
+                "Incomplete(45678)",
+                "Incomplete(123)",
+                "Ok(one)"
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn reads_in_blocks() {
+        let text = (0..20_000).map(|x| format!("{}\n", x)).collect::<String>();
+        let mut lines = Cursor::new(text).rev_lines_rc_with_capacity(10);
+        for expected in (0..20_000).rev() {
+            assert_eq!(expected.to_string(), *lines.next().unwrap().unwrap());
+        }
+        assert!(lines.next().is_none());
+        assert_eq!(20_000, lines.stats().lines);
+    }
+
+    #[test]
+    fn reads_each_byte_once_at_the_default_capacity() {
+        let text = numbers("\r\n");
+        let read = Cell::new(0);
+        let lines = Counting(Cursor::new(&text), &read).rev_lines_rc();
+        let lines = lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>();
+        let expected = text.lines().rev().collect::<Vec<_>>();
+        assert_eq!(expected, lines);
+        assert!(
+            read.get() <= text.len() + BLOCK_SIZE,
+            "{} bytes read",
+            read.get()
+        );
+    }
+
+    #[test]
+    fn tail_reads_a_single_block() {
+        let text = numbers("\n");
+        let read = Cell::new(0);
+        let last = super::last_n(Counting(Cursor::new(&text), &read), 10);
+        assert_eq!("19990", last[0].as_ref().unwrap().as_str());
+        assert_eq!("19999", last[9].as_ref().unwrap().as_str());
+        assert!(read.get() <= BLOCK_SIZE + 3, "{} bytes read", read.get());
+    }
+
+    #[test]
+    fn tail_returns_last_lines_in_order() {
+        let path = std::env::temp_dir().join(format!("simple_lines_tail_{}", std::process::id()));
+        std::fs::write(&path, "a\nb\nc\nd\n").unwrap();
+        let last = super::tail_last_n(&path, 2).unwrap();
+        let short = super::tail_last_n(&path, 10).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            vec!["c", "d"],
+            last.iter()
+                .map(|x| x.as_ref().unwrap().as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(4, short.len());
+    }
+}
@@ -0,0 +1,110 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::iter::StepBy,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields the first and then every `n`-th line. Lines in between are skipped without validating or copying them.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("0\n1\n2\n3\n4").lines_rc().sample_every(2);
+    /// let lines = lines.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!("024", lines.iter().map(|x| x.as_str()).collect::<String>());
+    /// ```
+    pub fn sample_every(self, n: usize) -> StepBy<Self> {
+        self.step_by(n)
+    }
+
+    /// Returns `k` items chosen uniformly at random in their original order, reading the input once with memory for `k` lines.
+    /// Lines between the chosen ones are skipped without validating or copying them (Algorithm L).
+    /// The same `seed` chooses the same lines of the same input. If `k` is 0, the result is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let input = (0..1000).map(|x| format!("{}\n", x)).collect::<String>();
+    /// let sample = std::io::Cursor::new(input).lines_rc().sample_reservoir(10, 42);
+    /// assert_eq!(10, sample.len());
+    /// ```
+    pub fn sample_reservoir(mut self, k: usize, seed: u64) -> Vec<RcLine> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut reservoir = self.by_ref().take(k).enumerate().collect::<Vec<_>>();
+        if reservoir.len() < k {
+            return reservoir.into_iter().map(|(_, x)| x).collect();
+        }
+        let mut random = SplitMix(seed);
+        let mut weight = (random.next_f64().ln() / k as f64).exp();
+        let mut position = k;
+        loop {
+            let skip = (random.next_f64().ln() / (1.0 - weight).ln()).floor();
+            if skip >= usize::MAX as f64 {
+                break;
+            }
+            let Some(line) = self.nth(skip as usize) else {
+                break;
+            };
+            position += skip as usize;
+            reservoir[(random.next_u64() % k as u64) as usize] = (position, line);
+            position += 1;
+            weight *= (random.next_f64().ln() / k as f64).exp();
+        }
+        reservoir.sort_unstable_by_key(|(position, _)| *position);
+        reservoir.into_iter().map(|(_, x)| x).collect()
+    }
+}
+
+/// Small and fast pseudo random generator, which is good enough for sampling
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the open interval (0, 1)
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn reservoir_keeps_order_and_spreads() {
+        let input = (0..10_000).map(|x| format!("{}\n", x)).collect::<String>();
+        let sample = std::io::Cursor::new(input.clone())
+            .lines_rc()
+            .sample_reservoir(100, 7)
+            .into_iter()
+            .map(|x| x.unwrap().parse::<u32>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(100, sample.len());
+        assert!(sample.windows(2).all(|x| x[0] < x[1]));
+        assert!(sample.iter().filter(|x| **x >= 5000).count() > 30);
+        let short = std::io::Cursor::new("a\nb")
+            .lines_rc()
+            .sample_reservoir(5, 7);
+        assert_eq!(2, short.len());
+    }
+
+    #[test]
+    fn empty_reservoir() {
+        let lines = std::io::Cursor::new("a\nb").lines_rc();
+        assert!(lines.sample_reservoir(0, 7).is_empty());
+    }
+}
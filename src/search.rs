@@ -0,0 +1,288 @@
+use {
+    crate::{chunks::OwnedLine, ReadExt},
+    std::{
+        collections::BTreeMap,
+        fs::File,
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::{mpsc, Arc, Mutex},
+    },
+};
+
+/// Options of `par_search()`
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    threads: usize,
+    ordered: bool,
+    max_line_length: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            threads: std::thread::available_parallelism().map_or(4, |x| x.get()),
+            ordered: true,
+            max_line_length: 64 * 1024,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Number of threads searching files. Defaults to the available parallelism.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "At least one thread is required");
+        self.threads = threads;
+        self
+    }
+
+    /// Yields matches as soon as they are found instead of in the order of the paths.
+    /// Matches of the same file are still yielded in order.
+    pub fn unordered(mut self) -> Self {
+        self.ordered = false;
+        self
+    }
+
+    /// Lines longer than `max_line_length` are searched in `Incomplete` parts like in `RcLineIterator::matching()`. Defaults to 64kb.
+    pub fn with_capacity(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+}
+
+/// Line found by `par_search()`
+#[derive(Debug)]
+pub struct SearchMatch {
+    /// Path of the file
+    pub path: Arc<Path>,
+    /// Zero-based number of the line
+    pub number: u64,
+    /// Matching line, or an error reading it
+    pub line: OwnedLine,
+}
+
+/// Number of matches a worker may send ahead per file
+const FILE_CAPACITY: usize = 64;
+
+/// Message from a worker
+enum Found {
+    /// Match if unordered
+    Match(SearchMatch),
+    /// Matches of the file at the index if ordered, which ends once the file is searched
+    File(usize, mpsc::Receiver<SearchMatch>),
+}
+
+/// Searches the files at `paths` for lines containing `pattern` on a pool of threads, like `grep -rF`.
+/// `paths` is consumed on its own thread, so it can be a lazy directory walk which runs concurrently with the search.
+///
+/// Binary files are skipped, as detected by `RcLineIterator::with_binary_detection()`. Files which can't be opened yield an `Io` error.
+/// The results are sent back over bounded channels, so workers wait if they aren't consumed.
+/// If ordered, at most twice as many files as threads are searched ahead of the file whose matches are yielded,
+/// each with at most 64 matches waiting, so the matches kept for reordering are bounded as well.
+/// Dropping the returned iterator stops all threads after their current line.
+///
+/// # Examples
+/// ```
+/// let dir = std::env::temp_dir().join(format!("simple_lines_search_doc_{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("a.txt"), "error: a\ninfo\n").unwrap();
+/// std::fs::write(dir.join("b.txt"), "info\nerror: b\n").unwrap();
+///
+/// let paths = vec![dir.join("a.txt"), dir.join("b.txt")];
+/// let matches = simple_lines::par_search(paths, "error", simple_lines::SearchOptions::default())
+///     .map(|x| (x.number, x.line.unwrap()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(vec![(0, "error: a".to_string()), (1, "error: b".into())], matches);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn par_search<P>(paths: P, pattern: &str, options: SearchOptions) -> SearchResults
+where
+    P: IntoIterator<Item = PathBuf> + Send + 'static,
+{
+    let (path_sender, path_receiver) = mpsc::sync_channel::<(usize, PathBuf)>(options.threads);
+    let (sender, receiver) = mpsc::sync_channel(options.threads * FILE_CAPACITY);
+    // Each file needs a permit if ordered, which is returned once its matches are yielded
+    let window = options.threads * 2;
+    let (permit_sender, permits) = mpsc::sync_channel(window);
+    let ordered = options.ordered;
+    std::thread::spawn(move || {
+        for path in paths.into_iter().enumerate() {
+            if (ordered && permits.recv().is_err()) || path_sender.send(path).is_err() {
+                break;
+            }
+        }
+    });
+    if ordered {
+        for _ in 0..window {
+            let _ = permit_sender.send(());
+        }
+    }
+    let path_receiver = Arc::new(Mutex::new(path_receiver));
+    let pattern: Arc<str> = pattern.into();
+    for _ in 0..options.threads {
+        let paths = path_receiver.clone();
+        let sender = sender.clone();
+        let pattern = pattern.clone();
+        let max_line_length = options.max_line_length;
+        std::thread::spawn(move || loop {
+            let next = paths.lock().map(|x| x.recv());
+            let Ok(Ok((index, path))) = next else {
+                break;
+            };
+            let searched = if ordered {
+                let (file_sender, file_receiver) = mpsc::sync_channel(FILE_CAPACITY);
+                sender.send(Found::File(index, file_receiver)).is_ok()
+                    && search_file(path, &pattern, max_line_length, |x| {
+                        file_sender.send(x).is_ok()
+                    })
+            } else {
+                search_file(path, &pattern, max_line_length, |x| {
+                    sender.send(Found::Match(x)).is_ok()
+                })
+            };
+            if !searched {
+                break;
+            }
+        });
+    }
+    SearchResults {
+        receiver,
+        permits: permit_sender,
+        next_index: 0,
+        files: BTreeMap::new(),
+    }
+}
+
+/// Sends the matches of one file, returning false if `send` failed because the receiver was dropped
+fn search_file(
+    path: PathBuf,
+    pattern: &str,
+    max_line_length: usize,
+    mut send: impl FnMut(SearchMatch) -> bool,
+) -> bool {
+    let path: Arc<Path> = path.into();
+    let mut send = |number, line| {
+        let path = path.clone();
+        send(SearchMatch { path, number, line })
+    };
+    match File::open(&path) {
+        Ok(file) => {
+            let lines = file
+                .lines_rc_with_capacity(max_line_length)
+                .with_binary_detection()
+                .matching(pattern);
+            for (number, line) in lines {
+                let line = match line {
+                    Ok(x) => Ok(Rc::unwrap_or_clone(x)),
+                    Err(crate::Error::BinaryContent) => break,
+                    Err(e) => Err(e.map_incomplete(Rc::unwrap_or_clone)),
+                };
+                if !send(number, line) {
+                    return false;
+                }
+            }
+            true
+        }
+        Err(e) => send(0, Err(e.into())),
+    }
+}
+
+/// Iterator over the results of `par_search()`
+pub struct SearchResults {
+    receiver: mpsc::Receiver<Found>,
+    /// Returns the permit of a file once its matches are yielded if ordered
+    permits: mpsc::SyncSender<()>,
+    /// Index of the file whose matches are yielded next if ordered
+    next_index: usize,
+    /// Matches of the files which are searched if ordered
+    files: BTreeMap<usize, mpsc::Receiver<SearchMatch>>,
+}
+
+impl Iterator for SearchResults {
+    type Item = SearchMatch;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(file) = self.files.get(&self.next_index) {
+                match file.recv() {
+                    Ok(x) => return Some(x),
+                    Err(_) => {
+                        self.files.remove(&self.next_index);
+                        self.next_index += 1;
+                        let _ = self.permits.send(());
+                        continue;
+                    }
+                }
+            }
+            match self.receiver.recv().ok()? {
+                Found::Match(x) => return Some(x),
+                Found::File(index, x) => {
+                    self.files.insert(index, x);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order_of_paths() {
+        let dir = std::env::temp_dir().join(format!("simple_lines_search_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = (0..20)
+            .map(|i| {
+                let path = dir.join(i.to_string());
+                let text = (0..i * 100)
+                    .map(|x| format!("{}:{}\n", i, x))
+                    .collect::<String>();
+                std::fs::write(&path, text).unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+        let options = SearchOptions::default().with_threads(4);
+        let found = par_search(paths.clone(), ":1", options.clone())
+            .map(|x| x.line.unwrap())
+            .collect::<Vec<_>>();
+        let mut expected = (0..20)
+            .flat_map(|i| (0..i * 100).map(move |x| format!("{}:{}", i, x)))
+            .filter(|x| x.contains(":1"))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, found);
+
+        let mut found = par_search(paths, ":1", options.unordered())
+            .map(|x| x.line.unwrap())
+            .collect::<Vec<_>>();
+        found.sort();
+        expected.sort();
+        assert_eq!(expected, found);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn searches_bounded_number_of_files_ahead() {
+        let dir =
+            std::env::temp_dir().join(format!("simple_lines_search_ahead_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = (0..10)
+            .map(|i| {
+                let path = dir.join(i.to_string());
+                std::fs::write(&path, "x\n").unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+        let mut found = par_search(paths, "x", SearchOptions::default().with_threads(2));
+        assert_eq!(0, found.next().unwrap().number);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        for registered in found.receiver.try_iter() {
+            if let Found::File(index, x) = registered {
+                found.files.insert(index, x);
+            }
+        }
+        assert_eq!(
+            (0..4).collect::<Vec<_>>(),
+            found.files.keys().copied().collect::<Vec<_>>()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+use {
+    crate::bound::{strip_delimiter, RcLine, SharedBuffers},
+    std::{
+        io::{self, Read, Seek, SeekFrom},
+        ops::Range,
+    },
+};
+
+/// Number of bytes read at once from either end
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Iterator over the lines of a seekable source from both ends, created by `SeekExt::seek_lines_rc()`.
+/// `next()` and `next_back()` can be mixed freely and stop where they meet, so each line is returned once.
+///
+/// Lines longer than the capacity are split into the same `Incomplete` parts in both directions, counted from the start of the line.
+/// To find that start, `next_back()` scans the long line backwards in blocks, so it reads the whole line but only keeps a block in memory.
+/// Errors of the underlying reader are returned without moving the end they occurred at, so the call can be retried.
+///
+/// Like `RcLineIterator`, the returned `Rc<String>` is reused for the next line if the consumer doesn't keep a reference to it.
+/// None of the options of `RcLineIterator` apply though: there are no `stats()`, quotas, trimming, forbidden bytes, binary detection,
+/// cancellation, timeouts, size hints, peeking or checkpoints. Use `lines_rc()` where they are needed and reading backwards isn't.
+pub struct SeekLines<T: Read + Seek> {
+    inner: T,
+    max_size: usize,
+    /// Positions of the first and the last unreturned byte, `None` until the first call
+    range: Option<Range<u64>>,
+    /// Where the iteration started, as lines are split relative to it
+    start: u64,
+    /// Whether `next()` continues a line split at the front
+    front_pending: bool,
+    /// Start of the line split at the back, if `next_back()` stopped in its middle
+    back_line_start: Option<u64>,
+    front_window: Window,
+    back_window: Window,
+    buffers: SharedBuffers,
+}
+
+impl<T: Read + Seek> SeekLines<T> {
+    pub(crate) fn new(inner: T, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be greater than 0");
+        Self {
+            inner,
+            max_size,
+            range: None,
+            start: 0,
+            front_pending: false,
+            back_line_start: None,
+            front_window: Window::default(),
+            back_window: Window::default(),
+            buffers: SharedBuffers::default(),
+        }
+    }
+
+    /// Returns the unreturned bytes, starting at the current position of the reader and ending at its end
+    fn range(&mut self) -> io::Result<Range<u64>> {
+        if let Some(range) = &self.range {
+            return Ok(range.clone());
+        }
+        let start = self.inner.stream_position()?;
+        let end = self.inner.seek(SeekFrom::End(0))?.max(start);
+        self.start = start;
+        self.range = Some(start..end);
+        Ok(start..end)
+    }
+
+    fn try_next(&mut self) -> io::Result<Option<RcLine>> {
+        let Range { start, end } = self.range()?;
+        if start >= end {
+            return Ok(None);
+        }
+        // Loading a block beyond the longest chunk lets the window serve many lines before it is extended
+        let load = start..end.min(start + (self.max_size + BLOCK_SIZE) as u64);
+        let window = self.front_window.get(
+            &mut self.inner,
+            start..end.min(start + self.max_size as u64),
+            load,
+        )?;
+        // The back end always stops at a line or part boundary, so it can't cut a chunk short
+        let chunk = &window[..memchr::memchr(b'\n', window).map_or(window.len(), |x| x + 1)];
+        let line = strip_delimiter(chunk);
+        let is_full = line.len() == self.max_size;
+        let incomplete = std::mem::replace(&mut self.front_pending, is_full) || is_full;
+        let item = to_item(&mut self.buffers, line, incomplete);
+        let next = start + chunk.len() as u64;
+        self.range = Some(next..end);
+        Ok(Some(item))
+    }
+
+    fn try_next_back(&mut self) -> io::Result<Option<RcLine>> {
+        let Range { start, end } = self.range()?;
+        if start >= end {
+            return Ok(None);
+        }
+        let line_start = match self.back_line_start {
+            Some(x) => x,
+            None => self.find_line_start(end)?,
+        };
+        // Parts are cut every `max_size` bytes from the start of the line, the last one may include the linebreak
+        let chunk_start =
+            line_start + (end - 1 - line_start) / self.max_size as u64 * self.max_size as u64;
+        let load = end
+            .saturating_sub(BLOCK_SIZE as u64)
+            .max(self.start)
+            .min(chunk_start)..end;
+        let chunk = self
+            .back_window
+            .get(&mut self.inner, chunk_start..end, load)?;
+        let line = strip_delimiter(chunk);
+        let item = to_item(
+            &mut self.buffers,
+            line,
+            chunk_start > line_start || line.len() == self.max_size,
+        );
+        self.back_line_start = if chunk_start > line_start {
+            Some(line_start)
+        } else {
+            None
+        };
+        self.range = Some(start..chunk_start);
+        Ok(Some(item))
+    }
+
+    /// Scans backwards from `end` to the byte following the previous linebreak.
+    /// The window keeps the bytes up to `end` as long as they fit beside a chunk, so `try_next_back()` needn't read them again.
+    fn find_line_start(&mut self, end: u64) -> io::Result<u64> {
+        let mut pos = end;
+        if self.back_window.get(
+            &mut self.inner,
+            end - 1..end,
+            end.saturating_sub(BLOCK_SIZE as u64).max(self.start)..end,
+        )? == b"\n"
+        {
+            pos -= 1;
+        }
+        while pos > self.start {
+            let from = pos.saturating_sub(BLOCK_SIZE as u64).max(self.start);
+            let keep = end.min(from + (self.max_size + 2 * BLOCK_SIZE) as u64);
+            let block = self
+                .back_window
+                .get(&mut self.inner, from..pos, from..keep)?;
+            if let Some(x) = memchr::memrchr(b'\n', block) {
+                return Ok(from + x as u64 + 1);
+            }
+            pos = from;
+        }
+        Ok(self.start)
+    }
+}
+
+fn to_item(buffers: &mut SharedBuffers, line: &[u8], incomplete: bool) -> RcLine {
+    match crate::utf8::from_utf8(line) {
+        Ok(x) if incomplete => Err(crate::Error::Incomplete(buffers.share(x))),
+        Ok(x) => Ok(buffers.share(x)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl<T: Read + Seek> Iterator for SeekLines<T> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().unwrap_or_else(|e| Some(Err(e.into())))
+    }
+}
+
+impl<T: Read + Seek> DoubleEndedIterator for SeekLines<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.try_next_back().unwrap_or_else(|e| Some(Err(e.into())))
+    }
+}
+
+impl<T: Read + Seek> std::iter::FusedIterator for SeekLines<T> {}
+
+/// Buffered bytes of the source, to avoid seeking for every line
+#[derive(Default)]
+struct Window {
+    start: u64,
+    data: Vec<u8>,
+}
+
+impl Window {
+    /// Returns the bytes in `range`. If they aren't buffered, the window is moved to `load`, which must contain `range`.
+    /// Buffered bytes within `load` are kept, so only the missing ones are read.
+    fn get<R: Read + Seek>(
+        &mut self,
+        inner: &mut R,
+        range: Range<u64>,
+        load: Range<u64>,
+    ) -> io::Result<&[u8]> {
+        let end = self.start + self.data.len() as u64;
+        if range.start < self.start || range.end > end {
+            if let Err(e) = self.load(inner, load, end) {
+                self.data.clear();
+                return Err(e);
+            }
+        }
+        let offset = (range.start - self.start) as usize;
+        Ok(&self.data[offset..offset + (range.end - range.start) as usize])
+    }
+
+    /// Moves the window to `load`, reading only the bytes which aren't buffered up to `end` already
+    fn load<R: Read + Seek>(
+        &mut self,
+        inner: &mut R,
+        load: Range<u64>,
+        end: u64,
+    ) -> io::Result<()> {
+        let kept = load.start.max(self.start)..load.end.min(end);
+        if kept.start >= kept.end {
+            self.data.clear();
+            read_at(inner, load.clone(), &mut self.data)?;
+        } else {
+            self.data.truncate((kept.end - self.start) as usize);
+            self.data.drain(..(kept.start - self.start) as usize);
+            let mut front = Vec::new();
+            read_at(inner, load.start..kept.start, &mut front)?;
+            self.data.splice(..0, front);
+            read_at(inner, kept.end..load.end, &mut self.data)?;
+        }
+        self.start = load.start;
+        Ok(())
+    }
+}
+
+/// Appends the bytes in `range` to `data`
+fn read_at<R: Read + Seek>(inner: &mut R, range: Range<u64>, data: &mut Vec<u8>) -> io::Result<()> {
+    if range.is_empty() {
+        return Ok(());
+    }
+    inner.seek(SeekFrom::Start(range.start))?;
+    let offset = data.len();
+    data.resize(offset + (range.end - range.start) as usize, 0);
+    inner.read_exact(&mut data[offset..])
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::BLOCK_SIZE,
+        crate::SeekExt,
+        std::{
+            cell::Cell,
+            io::{Cursor, Read, Seek, SeekFrom},
+        },
+    };
+
+    /// Counts the bytes read from the wrapped reader
+    struct Counting<'a, T>(T, &'a Cell<usize>);
+
+    impl<T: Read> Read for Counting<'_, T> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.0.read(buf)?;
+            self.1.set(self.1.get() + read);
+            Ok(read)
+        }
+    }
+
+    impl<T: Seek> Seek for Counting<'_, T> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    #[test]
+    fn reuses_released_buffers() {
+        let mut lines = Cursor::new("first\nsecond\nthird").seek_lines_rc();
+        let first = lines.next().unwrap().unwrap();
+        let address = first.as_ptr();
+        drop(first);
+        assert_eq!(address, lines.next_back().unwrap().unwrap().as_ptr());
+    }
+
+    #[test]
+    fn meets_forward_parts_from_both_ends() {
+        let data = b"abc\nabcdefg\r\n\xff\n\nabcd\nx";
+        for capacity in 1..10 {
+            let forward = Cursor::new(data)
+                .seek_lines_rc_with_capacity(capacity)
+                .map(|x| format!("{:?}", x.map(|x| x.to_string())))
+                .collect::<Vec<_>>();
+            let expected = crate::slice_lines(data)
+                .with_capacity(capacity)
+                .map(|x| format!("{:?}", x))
+                .collect::<Vec<_>>();
+            assert_eq!(expected, forward, "capacity {}", capacity);
+
+            for split in 0..=expected.len() {
+                let mut lines = Cursor::new(data).seek_lines_rc_with_capacity(capacity);
+                let mut back = lines
+                    .by_ref()
+                    .rev()
+                    .take(expected.len() - split)
+                    .map(|x| format!("{:?}", x.map(|x| x.to_string())))
+                    .collect::<Vec<_>>();
+                back.reverse();
+                let mut mixed = lines
+                    .map(|x| format!("{:?}", x.map(|x| x.to_string())))
+                    .collect::<Vec<_>>();
+                mixed.extend(back);
+                assert_eq!(expected, mixed, "capacity {}, split {}", capacity, split);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_each_byte_about_once_in_both_directions() {
+        let text = (0..20_000).map(|x| format!("{}\n", x)).collect::<String>();
+        for capacity in [16, 64 * 1024, 1 << 20] {
+            let read = Cell::new(0);
+            let lines = Counting(Cursor::new(&text), &read).seek_lines_rc_with_capacity(capacity);
+            assert_eq!(20_000, lines.count());
+            assert!(
+                read.get() <= text.len() + BLOCK_SIZE,
+                "{} bytes read forwards at capacity {}",
+                read.get(),
+                capacity
+            );
+
+            let read = Cell::new(0);
+            let lines = Counting(Cursor::new(&text), &read).seek_lines_rc_with_capacity(capacity);
+            assert_eq!(20_000, lines.rev().count());
+            assert!(
+                read.get() <= text.len() + BLOCK_SIZE,
+                "{} bytes read backwards at capacity {}",
+                read.get(),
+                capacity
+            );
+        }
+    }
+}
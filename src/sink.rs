@@ -0,0 +1,158 @@
+use {
+    crate::writer::{LineEncoder, WriteError},
+    futures::{io::AsyncWrite, ready, Sink},
+    std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+/// Sink writing lines to an async writer with the bounds of `LineWriter`, configured by a `LineEncoder`.
+/// Lines are buffered up to the capacity of 64kb, after which `poll_ready()` waits until the buffer is written,
+/// so a slow writer slows down the sender. Rejected lines fail `start_send()` without writing anything.
+///
+/// # Examples
+/// ```
+/// use {
+///     futures::SinkExt,
+///     simple_lines::{LineEncoder, LineSink, WriteError},
+/// };
+///
+/// futures::executor::block_on(async {
+///     let mut sink = LineSink::new(futures::io::Cursor::new(Vec::new()))
+///         .with_encoder(LineEncoder::new().with_max_line_length(8));
+///     sink.send("first".to_string()).await.unwrap();
+///     assert!(matches!(sink.send("too long line".into()).await, Err(WriteError::TooLong { length: 13 })));
+///     sink.close().await.unwrap();
+///     assert_eq!(b"first\n", sink.into_inner().into_inner().as_slice());
+/// });
+/// ```
+pub struct LineSink<W> {
+    inner: W,
+    encoder: LineEncoder,
+    buffer: Vec<u8>,
+    /// Number of bytes at the start of `buffer` which are written already
+    written: usize,
+    capacity: usize,
+}
+
+impl<W: AsyncWrite + Unpin> LineSink<W> {
+    /// Creates a sink with the defaults of `LineEncoder::new()`
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            encoder: LineEncoder::new(),
+            buffer: Vec::new(),
+            written: 0,
+            capacity: 64 * 1024,
+        }
+    }
+
+    /// Encodes the lines with `encoder`
+    pub fn with_encoder(mut self, encoder: LineEncoder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// Number of buffered bytes after which the sink waits for the writer. Defaults to 64kb.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the underlying writer. Lines which aren't flushed yet are lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes the whole buffer to the underlying writer
+    fn poll_write_buffer(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), WriteError>> {
+        while self.written < self.buffer.len() {
+            let pending = &self.buffer[self.written..];
+            match ready!(Pin::new(&mut self.inner).poll_write(cx, pending))? {
+                0 => return Poll::Ready(Err(io::Error::from(io::ErrorKind::WriteZero).into())),
+                n => self.written += n,
+            }
+        }
+        self.buffer.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<String> for LineSink<W> {
+    type Error = WriteError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.buffer.len() >= this.capacity {
+            ready!(this.poll_write_buffer(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, line: String) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        this.encoder.encode(&line, &mut this.buffer)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_buffer(cx))?;
+        Poll::Ready(ready!(Pin::new(&mut this.inner).poll_flush(cx)).map_err(Into::into))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Poll::Ready(ready!(Pin::new(&mut self.get_mut().inner).poll_close(cx)).map_err(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::writer::{Newlines, Overlong},
+        futures::SinkExt,
+    };
+
+    /// Accepts one byte per write
+    struct Slow(Vec<u8>);
+
+    impl AsyncWrite for Slow {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.0.extend_from_slice(&buf[..buf.len().min(1)]);
+            Poll::Ready(Ok(buf.len().min(1)))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn writes_like_line_writer() {
+        let encoder = LineEncoder::new()
+            .with_max_line_length(3)
+            .with_newlines(Newlines::Split)
+            .with_overlong(Overlong::Split);
+        let mut sink = LineSink::new(Slow(Vec::new()))
+            .with_encoder(encoder)
+            .with_capacity(2);
+        futures::executor::block_on(async {
+            sink.feed("abcd\ne\n".to_string()).await.unwrap();
+            sink.feed("f".to_string()).await.unwrap();
+            sink.flush().await.unwrap();
+        });
+        assert_eq!(b"abc\nd\ne\nf\n", sink.into_inner().0.as_slice());
+    }
+}
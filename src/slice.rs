@@ -0,0 +1,105 @@
+use crate::bound::strip_delimiter;
+
+/// Iterates the lines of `data` in memory without copying them, with the same bounds as `ReadExt::lines_rc()` and the default capacity of 64kb.
+/// The number of lines is counted upfront, so `SliceLines` implements `ExactSizeIterator`, e.g. to allocate a table of the right size.
+///
+/// # Examples
+/// ```
+/// let data = b"id,name\r\n1,a\n2,b";
+/// let mut lines = simple_lines::slice_lines(data);
+/// assert_eq!(3, lines.len());
+/// assert_eq!(Some(Ok("id,name")), lines.next().map(|x| x.map_err(drop)));
+/// assert_eq!(2, lines.len());
+/// ```
+pub fn slice_lines(data: &[u8]) -> SliceLines<'_> {
+    SliceLines::new(data, 64 * 1024)
+}
+
+/// Iterator over the lines of a slice, created by `slice_lines()`
+pub struct SliceLines<'a> {
+    rest: &'a [u8],
+    max_size: usize,
+    /// Whether the last item was an `Incomplete` part which is continued by the next one
+    pending_incomplete: bool,
+    remaining: usize,
+}
+
+impl<'a> SliceLines<'a> {
+    fn new(data: &'a [u8], max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be greater than 0");
+        let mut lines = Self {
+            rest: data,
+            max_size,
+            pending_incomplete: false,
+            remaining: 0,
+        };
+        let mut rest = data;
+        while !rest.is_empty() {
+            rest = &rest[lines.next_len(rest)..];
+            lines.remaining += 1;
+        }
+        lines
+    }
+
+    /// Lines longer than `max_line_length` are split into `Incomplete` parts. Defaults to 64kb.
+    pub fn with_capacity(self, max_line_length: usize) -> Self {
+        Self::new(self.rest, max_line_length)
+    }
+
+    /// Returns the length of the next chunk of `rest` including its linebreak, cut like `BufReadSource` does
+    fn next_len(&self, rest: &[u8]) -> usize {
+        let window = &rest[..rest.len().min(self.max_size)];
+        memchr::memchr(b'\n', window).map_or(window.len(), |x| x + 1)
+    }
+}
+
+impl<'a> Iterator for SliceLines<'a> {
+    type Item = Result<&'a str, crate::Error<&'a str>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let (chunk, rest) = self.rest.split_at(self.next_len(self.rest));
+        self.rest = rest;
+        self.remaining -= 1;
+        let line = strip_delimiter(chunk);
+        let is_full = line.len() == self.max_size;
+        let was_pending = core::mem::replace(&mut self.pending_incomplete, is_full);
+        Some(match crate::utf8::from_utf8(line) {
+            Ok(x) if is_full || was_pending => Err(crate::Error::Incomplete(x)),
+            Ok(x) => Ok(x),
+            Err(e) => Err(e.into()),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for SliceLines<'_> {}
+
+impl core::iter::FusedIterator for SliceLines<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn splits_like_reader_lines() {
+        let data = b"abc\nabcdefg\r\n\xff\n\nx";
+        for capacity in 1..10 {
+            let lines = super::slice_lines(data).with_capacity(capacity);
+            assert_eq!(lines.len(), lines.count());
+            let from_slice = super::slice_lines(data)
+                .with_capacity(capacity)
+                .map(|x| format!("{:?}", x))
+                .collect::<Vec<_>>();
+            let from_reader = std::io::Cursor::new(data)
+                .lines_rc_with_capacity(capacity)
+                .map(|x| format!("{:?}", x.map(|x| x.to_string())))
+                .collect::<Vec<_>>();
+            assert_eq!(from_reader, from_slice, "capacity {}", capacity);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use {
+    crate::{
+        bound::{BorrowedLines, SharedBuffers},
+        source::LineSource,
+    },
+    alloc::{rc::Rc, string::String},
+    core::{fmt, ops::Deref},
+};
+
+/// Iterator over the lines of a reader as `SmallLine`, created by `RcLineIterator::into_small()`
+pub struct SmallLineIterator<TSource: LineSource> {
+    lines: BorrowedLines<TSource>,
+    buffers: SharedBuffers,
+}
+
+impl<T: LineSource> SmallLineIterator<T> {
+    pub(crate) fn new(lines: BorrowedLines<T>, buffers: SharedBuffers) -> Self {
+        Self { lines, buffers }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<TSource: LineSource> core::iter::FusedIterator for SmallLineIterator<TSource> {}
+
+impl<TSource: LineSource> Iterator for SmallLineIterator<TSource> {
+    type Item = Result<SmallLine, crate::Error<SmallLine>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffers = &mut self.buffers;
+        let mut to_line = |x: &str| {
+            InlineStr::new(x)
+                .map(SmallLine::Inline)
+                .unwrap_or_else(|| SmallLine::Shared(buffers.share(x)))
+        };
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(to_line(x)),
+            Err(e) => Err(e.map_incomplete(to_line)),
+        })
+    }
+
+    /// Skips lines without validating or copying them
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating or copying them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+}
+
+/// Line which is stored inline if it is at most `InlineStr::CAPACITY` bytes long
+#[derive(Clone)]
+pub enum SmallLine {
+    /// Short line without heap allocation
+    Inline(InlineStr),
+    /// Longer line, which is shared like in `RcLineIterator`
+    Shared(Rc<String>),
+}
+
+impl Deref for SmallLine {
+    type Target = str;
+    fn deref(&self) -> &str {
+        match self {
+            SmallLine::Inline(x) => x,
+            SmallLine::Shared(x) => x,
+        }
+    }
+}
+
+impl fmt::Debug for SmallLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for SmallLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl PartialEq<str> for SmallLine {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+/// String which is stored on the stack
+#[derive(Clone)]
+pub struct InlineStr {
+    len: u8,
+    bytes: [u8; InlineStr::CAPACITY],
+}
+
+impl InlineStr {
+    /// Max length in bytes
+    pub const CAPACITY: usize = 22;
+
+    /// Returns None if `value` is longer than `CAPACITY`
+    pub fn new(value: &str) -> Option<Self> {
+        if value.len() > Self::CAPACITY {
+            return None;
+        }
+        let mut bytes = [0; Self::CAPACITY];
+        bytes[0..value.len()].copy_from_slice(value.as_bytes());
+        Some(Self {
+            len: value.len() as u8,
+            bytes,
+        })
+    }
+}
+
+impl Deref for InlineStr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        // SAFETY: bytes[0..len] is only ever written from a valid &str in `new()`
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[0..self.len as usize]) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn long_lines_are_shared() {
+        let long = "x".repeat(InlineStr::CAPACITY + 1);
+        let input = format!("{}\n{}", long, &long[1..]);
+        let lines = std::io::Cursor::new(input)
+            .lines_rc()
+            .into_small()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(matches!(&lines[0], SmallLine::Shared(x) if **x == long));
+        assert!(matches!(&lines[1], SmallLine::Inline(x) if **x == long[1..]));
+    }
+
+    #[test]
+    fn small_line_is_as_small_as_string() {
+        assert!(std::mem::size_of::<SmallLine>() <= std::mem::size_of::<String>());
+    }
+}
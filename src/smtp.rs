@@ -0,0 +1,206 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator, SharedBuffers},
+        source::BufReadSource,
+        BufReadExt,
+    },
+    std::io::{self, BufRead},
+};
+
+/// Maximum length of a line including CRLF according to RFC 5321
+const MAX_LINE_LENGTH: usize = 1000;
+
+/// Reads the body of an SMTP `DATA` command up to the terminating `.` line and removes the dot-stuffing of lines starting with `.`.
+/// `SmtpData::into_inner()` returns the reader positioned after the terminator, so the next command can be read from it.
+///
+/// Only a `.` line ending with CRLF after a line ending with CRLF terminates the body, as required by RFC 5321.
+/// Other `.` lines, e.g. `\n.\r\n` or `.\n`, are returned as empty lines, so bare linefeeds can't smuggle a terminator.
+/// A line following an error other than `Incomplete` can't terminate the body, as its linebreak is unknown.
+///
+/// Lines longer than the 1000 bytes including CRLF allowed by RFC 5321 are not split into parts,
+/// but flagged by a single `Incomplete` error with their first part, while the rest up to their linebreak is dropped.
+/// So a terminator following them is still found.
+/// If the reader ends before the terminator, an `io::ErrorKind::UnexpectedEof` error is yielded.
+///
+/// # Examples
+/// ```
+/// use std::io::BufRead;
+///
+/// let session = std::io::Cursor::new("Subject: a\r\n\r\n..hidden\r\n.\r\nQUIT\r\n");
+/// let mut data = simple_lines::smtp_data(session);
+/// let body = data.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(vec!["Subject: a", "", ".hidden"], body.iter().map(|x| x.as_str()).collect::<Vec<_>>());
+///
+/// let mut next = String::new();
+/// data.into_inner().read_line(&mut next).unwrap();
+/// assert_eq!("QUIT\r\n", next);
+/// ```
+pub fn smtp_data<B: BufRead>(reader: B) -> SmtpData<B> {
+    SmtpData {
+        lines: BufReadExt::lines_rc_with_capacity(reader, MAX_LINE_LENGTH),
+        buffers: SharedBuffers::default(),
+        after_crlf: true,
+        after_cr: false,
+        consumed: 0,
+        error: None,
+        finished: false,
+    }
+}
+
+/// Iterator over the lines of an SMTP `DATA` body, created by `smtp_data()`
+pub struct SmtpData<B: BufRead> {
+    lines: RcLineIterator<BufReadSource<B>>,
+    buffers: SharedBuffers,
+    /// Whether the last item ended with CRLF, so the next line may be the terminator
+    after_crlf: bool,
+    /// Whether the last item was an `Incomplete` part ending with CR, whose LF may follow in the next part
+    after_cr: bool,
+    /// Bytes consumed up to the end of the last item
+    consumed: u64,
+    /// Error which interrupted the dropped parts of a long line, returned after its flag
+    error: Option<RcLine>,
+    finished: bool,
+}
+
+impl<B: BufRead> SmtpData<B> {
+    /// Returns the reader positioned after the returned lines, which is after the terminator once it was reached
+    pub fn into_inner(self) -> B {
+        self.lines.into_source().into_inner()
+    }
+
+    /// Returns the totals of all lines consumed so far, including the terminator
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<B: BufRead> SmtpData<B> {
+    /// Reads the next item and its length including the linebreak. Fails with `UnexpectedEof` at the end of the reader.
+    fn next_part(&mut self) -> (RcLine, u64) {
+        let Some(line) = self.lines.next() else {
+            self.finished = true;
+            return (Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()), 0);
+        };
+        let bytes = self.lines.stats().bytes;
+        let length = bytes - std::mem::replace(&mut self.consumed, bytes);
+        let after_cr = std::mem::replace(
+            &mut self.after_cr,
+            matches!(&line, Err(crate::Error::Incomplete(x)) if x.ends_with('\r')),
+        );
+        self.after_crlf = match &line {
+            Ok(x) | Err(crate::Error::Incomplete(x)) => {
+                length == x.len() as u64 + 2 || (after_cr && x.is_empty() && length == 1)
+            }
+            _ => false,
+        };
+        (line, length)
+    }
+
+    /// Drops the remaining parts of a long line up to its linebreak
+    fn skip_parts(&mut self) {
+        while self.lines.continues_line() {
+            match self.next_part().0 {
+                Ok(_) | Err(crate::Error::Incomplete(_)) => {}
+                e => {
+                    self.error = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<B: BufRead> Iterator for SmtpData<B> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(e);
+        }
+        if self.finished {
+            return None;
+        }
+        let is_terminator_allowed = self.after_crlf;
+        let line = match self.next_part() {
+            (Ok(x), 3) if x.as_str() == "." && is_terminator_allowed => {
+                self.finished = true;
+                return None;
+            }
+            (Ok(x), _) if x.starts_with('.') => Ok(self.buffers.share(&x[1..])),
+            (Err(crate::Error::Incomplete(x)), _) if self.lines.continues_line() => {
+                let x = x.strip_suffix('\r').unwrap_or(&x);
+                let x = self.buffers.share(x.strip_prefix('.').unwrap_or(x));
+                self.skip_parts();
+                Err(crate::Error::Incomplete(x))
+            }
+            (x, _) => x,
+        };
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_long_lines_by_their_unstuffed_first_part() {
+        let line = format!(".{}\r\n", ".".repeat(1200));
+        let input = format!("{}.\r\n", line);
+        let parts = smtp_data(input.as_bytes())
+            .map(|x| match x {
+                Err(crate::Error::Incomplete(x)) => x.len(),
+                x => panic!("{:?}", x),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![999], parts);
+    }
+
+    #[test]
+    fn finds_terminator_after_lines_at_the_limit() {
+        for (length, expected) in [
+            (998, "Ok(998)"),
+            (999, "Incomplete(999)"),
+            (1000, "Incomplete(1000)"),
+        ] {
+            let input = format!("{}\r\n.\r\nQUIT\r\n", "a".repeat(length));
+            let mut data = smtp_data(input.as_bytes());
+            let body = data
+                .by_ref()
+                .map(|x| match x {
+                    Ok(x) => format!("Ok({})", x.len()),
+                    Err(crate::Error::Incomplete(x)) => format!("Incomplete({})", x.len()),
+                    Err(e) => panic!("{}", e),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(vec![expected], body, "{} bytes", length);
+            let mut next = String::new();
+            data.into_inner().read_line(&mut next).unwrap();
+            assert_eq!("QUIT\r\n", next);
+        }
+    }
+
+    #[test]
+    fn fails_without_terminator() {
+        let mut data = smtp_data("a\r\n".as_bytes());
+        assert_eq!("a", *data.next().unwrap().unwrap());
+        assert!(matches!(data.next(), Some(Err(crate::Error::Io(_)))));
+        assert!(data.next().is_none());
+    }
+
+    #[test]
+    fn ignores_terminators_with_bare_linefeeds() {
+        let body = |input: &str| {
+            smtp_data(input.as_bytes())
+                .map(|x| x.map(|x| x.to_string()).map_err(|e| e.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![Ok("".to_string()), Ok("a".to_string())],
+            body(".\na\r\n.\r\n")
+        );
+        assert_eq!(
+            vec![Ok("a".to_string()), Ok("".to_string()), Ok("b".to_string())],
+            body("a\n.\r\nb\r\n.\r\n")
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use {
+    crate::{merge::merge_sorted, paths::create_temp_file, ReadExt},
+    std::{
+        fs::File,
+        io::{self, BufWriter, Read, Write},
+        path::PathBuf,
+    },
+};
+
+/// Options of `sort_lines()`
+#[derive(Debug, Clone)]
+pub struct SortOptions {
+    memory_budget: usize,
+    temp_dir: PathBuf,
+    max_line_length: usize,
+    fan_in: usize,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            memory_budget: 64 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+            max_line_length: 64 * 1024,
+            fan_in: 64,
+        }
+    }
+}
+
+impl SortOptions {
+    /// Approximate number of bytes of lines kept in memory before they are sorted and spilled to a temporary file. Defaults to 64mb.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Directory of the temporary files. Defaults to `std::env::temp_dir()`.
+    pub fn with_temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// Lines longer than this abort the sort with `Error::Incomplete`. Defaults to 64kb.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Maximum number of runs opened at once. More runs are merged into new runs first, until a single pass covers all of them. Defaults to 64.
+    pub fn with_fan_in(mut self, fan_in: usize) -> Self {
+        assert!(fan_in > 1, "fan_in must be greater than 1");
+        self.fan_in = fan_in;
+        self
+    }
+}
+
+/// Sorts the lines of `reader` bytewise and writes them to `writer`, each followed by `\n`.
+/// If they don't fit into the memory budget, sorted runs are spilled to temporary files and merged afterwards,
+/// so inputs of any size can be sorted with bounded memory.
+///
+/// The first error of a line aborts the sort and is returned. Temporary files are removed in any case.
+///
+/// # Examples
+/// ```
+/// use simple_lines::SortOptions;
+///
+/// let mut sorted = Vec::new();
+/// let options = SortOptions::default().with_memory_budget(64);
+/// simple_lines::sort_lines("c\na\nb\n".as_bytes(), &mut sorted, &options).unwrap();
+/// assert_eq!(b"a\nb\nc\n", &sorted[..]);
+/// ```
+pub fn sort_lines<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    options: &SortOptions,
+) -> Result<(), crate::Error<String>> {
+    let mut writer = BufWriter::new(writer);
+    let mut runs = Vec::new();
+    let mut lines = Vec::new();
+    let mut used = 0;
+    let mut input = ReadExt::lines_rc_with_capacity(reader, options.max_line_length);
+    while let Some(line) = input.next_owned() {
+        let line = line?;
+        used += line.len() + std::mem::size_of::<String>();
+        lines.push(line);
+        if used >= options.memory_budget {
+            runs.push(spill(&mut lines, &options.temp_dir)?);
+            used = 0;
+        }
+    }
+    lines.sort_unstable();
+    if runs.is_empty() {
+        for line in &lines {
+            write_line(&mut writer, line)?;
+        }
+    } else {
+        if !lines.is_empty() {
+            runs.push(spill(&mut lines, &options.temp_dir)?);
+        }
+        while runs.len() > options.fan_in {
+            runs = runs
+                .chunks(options.fan_in)
+                .map(|x| {
+                    let (path, file) = create_run(&options.temp_dir)?;
+                    let run = TempRun(path);
+                    let mut file = BufWriter::new(file);
+                    merge_runs(x, &mut file, options)?;
+                    file.flush()?;
+                    Ok(run)
+                })
+                .collect::<Result<_, crate::Error<String>>>()?;
+        }
+        merge_runs(&runs, &mut writer, options)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// First pair of lines in the wrong order, returned by `is_sorted_lines()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsorted {
+    /// Zero-based number of `current`. `previous` is the line before it.
+    pub line: u64,
+    /// Line which should come after `current`
+    pub previous: String,
+    /// First line which is smaller than the line before it
+    pub current: String,
+}
+
+/// Checks whether the lines of `reader` are sorted by `compare`, e.g. before passing them to `merge_sorted()`.
+/// Only the previous line is kept in memory. Equal lines are considered sorted.
+///
+/// Returns the first pair of lines in the wrong order, or `None` if all lines are sorted.
+/// The first error of a line, including `Incomplete` for lines longer than 64kb, aborts the check and is returned.
+///
+/// # Examples
+/// ```
+/// let unsorted = simple_lines::is_sorted_lines("a\nc\nb\n".as_bytes(), Ord::cmp).unwrap().unwrap();
+/// assert_eq!((2, "c", "b"), (unsorted.line, unsorted.previous.as_str(), unsorted.current.as_str()));
+/// assert_eq!(None, simple_lines::is_sorted_lines("a\na\nb".as_bytes(), Ord::cmp).unwrap());
+/// ```
+pub fn is_sorted_lines<R: Read>(
+    reader: R,
+    mut compare: impl FnMut(&str, &str) -> std::cmp::Ordering,
+) -> Result<Option<Unsorted>, crate::Error<String>> {
+    let mut previous: Option<std::rc::Rc<String>> = None;
+    for (line, current) in (0..).zip(ReadExt::lines_rc_with_capacity(reader, 64 * 1024)) {
+        let current = current.map_err(|e| e.map_incomplete(|x| x.to_string()))?;
+        if let Some(previous) = previous.filter(|x| compare(x, &current).is_gt()) {
+            return Ok(Some(Unsorted {
+                line,
+                previous: previous.to_string(),
+                current: current.to_string(),
+            }));
+        }
+        previous = Some(current);
+    }
+    Ok(None)
+}
+
+/// Temporary file, which is removed on drop
+struct TempRun(PathBuf);
+
+impl Drop for TempRun {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Merges the sorted `runs` and writes their lines to `writer`
+fn merge_runs(
+    runs: &[TempRun],
+    writer: &mut impl Write,
+    options: &SortOptions,
+) -> Result<(), crate::Error<String>> {
+    let readers = runs
+        .iter()
+        .map(|x| File::open(&x.0).map(|x| x.lines_rc_with_capacity(options.max_line_length)))
+        .collect::<io::Result<Vec<_>>>()?;
+    for line in merge_sorted(readers) {
+        match line {
+            Ok(x) => write_line(writer, &x)?,
+            Err(e) => return Err(e.map_incomplete(|x| x.to_string())),
+        }
+    }
+    Ok(())
+}
+
+fn create_run(dir: &std::path::Path) -> io::Result<(PathBuf, File)> {
+    create_temp_file(|id| dir.join(format!("simple_lines_sort_{}_{}", std::process::id(), id)))
+}
+
+/// Sorts `lines` and writes them to a new temporary file
+fn spill(lines: &mut Vec<String>, dir: &std::path::Path) -> io::Result<TempRun> {
+    let (path, file) = create_run(dir)?;
+    let run = TempRun(path);
+    lines.sort_unstable();
+    let mut file = BufWriter::new(file);
+    for line in lines.drain(..) {
+        write_line(&mut file, &line)?;
+    }
+    file.flush()?;
+    Ok(run)
+}
+
+fn write_line(writer: &mut impl Write, line: &str) -> io::Result<()> {
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_spilled_runs() {
+        let mut numbers = (0..1000)
+            .map(|x| (x * 7919 % 1000).to_string())
+            .collect::<Vec<_>>();
+        let input = numbers
+            .iter()
+            .map(|x| format!("{}\n", x))
+            .collect::<String>();
+        let mut sorted = Vec::new();
+        let options = SortOptions::default().with_memory_budget(1000);
+        sort_lines(input.as_bytes(), &mut sorted, &options).unwrap();
+        numbers.sort();
+        assert_eq!(
+            numbers.join("\n") + "\n",
+            String::from_utf8(sorted).unwrap()
+        );
+    }
+
+    #[test]
+    fn merges_more_runs_than_the_fan_in() {
+        let dir =
+            std::env::temp_dir().join(format!("simple_lines_sort_fan_in_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut numbers = (0..500)
+            .map(|x| (x * 7919 % 500).to_string())
+            .collect::<Vec<_>>();
+        let input = numbers
+            .iter()
+            .map(|x| format!("{}\n", x))
+            .collect::<String>();
+        let mut sorted = Vec::new();
+        let options = SortOptions::default()
+            .with_memory_budget(1)
+            .with_fan_in(3)
+            .with_temp_dir(&dir);
+        sort_lines(input.as_bytes(), &mut sorted, &options).unwrap();
+        numbers.sort();
+        assert_eq!(
+            numbers.join("\n") + "\n",
+            String::from_utf8(sorted).unwrap()
+        );
+        assert_eq!(0, std::fs::read_dir(&dir).unwrap().count());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_existing_files_with_spill_names() {
+        let dir =
+            std::env::temp_dir().join(format!("simple_lines_sort_dir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = (0..1000)
+            .map(|id| dir.join(format!("simple_lines_sort_{}_{}", std::process::id(), id)))
+            .collect::<Vec<_>>();
+        for path in &existing {
+            std::fs::write(path, "keep").unwrap();
+        }
+        let mut sorted = Vec::new();
+        let options = SortOptions::default()
+            .with_memory_budget(1)
+            .with_temp_dir(&dir);
+        // Fails once no unused name is left, but must not write to any of the existing files
+        let _ = sort_lines("b\na\n".as_bytes(), &mut sorted, &options);
+        for path in &existing {
+            assert_eq!("keep", std::fs::read_to_string(path).unwrap());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn aborts_on_incomplete_line() {
+        let options = SortOptions::default().with_max_line_length(3);
+        let result = sort_lines("abcd\n".as_bytes(), Vec::new(), &options);
+        assert!(matches!(result, Err(crate::Error::Incomplete(x)) if x == "abc"));
+    }
+
+    #[test]
+    fn reports_first_unsorted_pair_by_comparator() {
+        let descending = |a: &str, b: &str| b.cmp(a);
+        let unsorted = is_sorted_lines("c\nb\nb\nc\na".as_bytes(), descending).unwrap();
+        assert_eq!(
+            Some(Unsorted {
+                line: 3,
+                previous: "b".into(),
+                current: "c".into()
+            }),
+            unsorted
+        );
+    }
+}
@@ -0,0 +1,297 @@
+#[cfg(feature = "std")]
+use std::{
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    time::Instant,
+};
+use {crate::io, alloc::vec::Vec, core::time::Duration};
+
+/// Without `std` there is no clock, so no deadline can be set
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy)]
+pub enum Instant {}
+
+/// Pause before retrying a reader which returned `WouldBlock`, so non-blocking sources don't spin until the deadline
+#[cfg(feature = "std")]
+const WOULD_BLOCK_DELAY: Duration = Duration::from_millis(1);
+
+/// Splits a stream into chunks which end on a linebreak or are `max_size` bytes long.
+/// The delimiter is part of the returned chunk.
+pub trait LineSource {
+    fn next_line(&mut self) -> Option<io::Result<&[u8]>>;
+    /// Reading fails with `io::ErrorKind::TimedOut` once `deadline` is reached.
+    /// Until then, `WouldBlock` and `TimedOut` errors of the reader are retried.
+    fn set_deadline(&mut self, deadline: Option<Instant>);
+    /// Returns the buffered bytes of the next lines without consuming them. Reads only if nothing is buffered.
+    fn peek(&mut self) -> io::Result<&[u8]>;
+}
+
+/// Buffered reader whose buffer `BufReadSource` splits.
+/// It is implemented for all `std::io::BufRead`, or for `&[u8]` without `std`, and for `EmbeddedReader`.
+pub trait FillBuf {
+    fn fill_buf(&mut self) -> io::Result<&[u8]>;
+    fn consume(&mut self, amount: usize);
+}
+
+#[cfg(feature = "std")]
+impl<T: BufRead> FillBuf for T {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        BufRead::fill_buf(self)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        BufRead::consume(self, amount)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl FillBuf for &[u8] {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        *self = &self[amount..];
+    }
+}
+
+/// Returns the deadline of a read which starts now and may take `timeout`
+#[cfg(feature = "std")]
+pub(crate) fn deadline_after(timeout: Duration) -> Option<Instant> {
+    Some(Instant::now() + timeout)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn deadline_after(_timeout: Duration) -> Option<Instant> {
+    None
+}
+
+/// Returns true if reading should be retried after `error`.
+/// After `WouldBlock`, it waits a millisecond first, but not beyond the deadline.
+#[cfg(feature = "std")]
+pub(crate) fn is_retryable(error: &io::Error, deadline: Option<Instant>) -> bool {
+    match (error.kind(), deadline) {
+        (io::ErrorKind::Interrupted, _) => true,
+        (io::ErrorKind::WouldBlock, Some(deadline)) => {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            std::thread::sleep(remaining.min(WOULD_BLOCK_DELAY));
+            true
+        }
+        (io::ErrorKind::TimedOut, Some(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn is_retryable(error: &io::Error, _deadline: Option<Instant>) -> bool {
+    error.kind() == io::ErrorKind::Interrupted
+}
+
+/// Fails if `deadline` is reached
+#[cfg(feature = "std")]
+pub(crate) fn check_deadline(deadline: Option<Instant>) -> io::Result<()> {
+    match deadline {
+        Some(x) if Instant::now() >= x => Err(io::ErrorKind::TimedOut.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn check_deadline(deadline: Option<Instant>) -> io::Result<()> {
+    match deadline {
+        Some(x) => match x {},
+        None => Ok(()),
+    }
+}
+
+/// Reads lines directly from the buffer of a `std::io::BufRead`.
+/// Only lines which span multiple `fill_buf()` calls are copied into `scratch`.
+pub struct BufReadSource<TBufRead: FillBuf> {
+    inner: TBufRead,
+    max_size: usize,
+    pending_consume: usize,
+    scratch: Scratch,
+    scratch_returned: bool,
+    deadline: Option<Instant>,
+}
+
+impl<T: FillBuf> BufReadSource<T> {
+    pub fn new(inner: T, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be greater than 0");
+        Self {
+            inner,
+            max_size,
+            pending_consume: 0,
+            scratch: Scratch::default(),
+            scratch_returned: false,
+            deadline: None,
+        }
+    }
+
+    /// Returns the reader positioned after the last returned line, e.g. to read a binary payload announced by that line
+    #[allow(dead_code)]
+    pub fn inner_mut(&mut self) -> &mut T {
+        self.inner
+            .consume(core::mem::take(&mut self.pending_consume));
+        &mut self.inner
+    }
+
+    /// Returns the reader positioned after the last returned line. Bytes it buffered beyond are kept in its buffer.
+    /// The start of a line which was interrupted by an error is lost.
+    pub fn into_inner(mut self) -> T {
+        self.inner
+            .consume(core::mem::take(&mut self.pending_consume));
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Read + Seek + Clone> BufReadSource<BufReader<T>> {
+    /// Returns a clone of the reader positioned after the last returned line, buffered like this one.
+    /// The start of a line which was interrupted by an error is read again.
+    pub(crate) fn fork_reader(&self) -> io::Result<BufReader<T>> {
+        let mut unread = self.inner.buffer().len() - self.pending_consume;
+        if !self.scratch_returned {
+            unread += self.scratch.len();
+        }
+        let mut reader = self.inner.get_ref().clone();
+        reader.seek(SeekFrom::Current(-(unread as i64)))?;
+        Ok(BufReader::with_capacity(self.inner.capacity(), reader))
+    }
+}
+
+/// Wiped on drop, as it can contain parts of sensitive lines
+#[cfg(feature = "zeroize")]
+type Scratch = zeroize::Zeroizing<Vec<u8>>;
+#[cfg(not(feature = "zeroize"))]
+type Scratch = Vec<u8>;
+
+impl<T: FillBuf> LineSource for BufReadSource<T> {
+    fn next_line(&mut self) -> Option<io::Result<&[u8]>> {
+        self.inner
+            .consume(core::mem::take(&mut self.pending_consume));
+        if core::mem::take(&mut self.scratch_returned) {
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(&mut self.scratch);
+            self.scratch.clear();
+        }
+
+        loop {
+            if let Err(e) = check_deadline(self.deadline) {
+                return Some(Err(e));
+            }
+            let available = match self.inner.fill_buf() {
+                Ok(x) => x,
+                Err(e) if is_retryable(&e, self.deadline) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if available.is_empty() {
+                if self.scratch.is_empty() {
+                    return None;
+                }
+                self.scratch_returned = true;
+                return Some(Ok(&self.scratch));
+            }
+
+            let room = self.max_size - self.scratch.len();
+            let window = &available[0..available.len().min(room)];
+            let len = match memchr::memchr(b'\n', window) {
+                Some(pos) => pos + 1,
+                None if window.len() == room => room,
+                None => {
+                    self.scratch.extend_from_slice(window);
+                    let len = window.len();
+                    self.inner.consume(len);
+                    continue;
+                }
+            };
+
+            if self.scratch.is_empty() {
+                // fill_buf() doesn't read again as long as the buffer isn't consumed,
+                // so the same bytes are returned without copying them.
+                self.pending_consume = len;
+                return Some(self.inner.fill_buf().map(|x| &x[0..len]));
+            }
+            self.scratch.extend_from_slice(&window[0..len]);
+            self.inner.consume(len);
+            self.scratch_returned = true;
+            return Some(Ok(&self.scratch));
+        }
+    }
+
+    fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    fn peek(&mut self) -> io::Result<&[u8]> {
+        self.inner
+            .consume(core::mem::take(&mut self.pending_consume));
+        loop {
+            check_deadline(self.deadline)?;
+            match self.inner.fill_buf() {
+                Ok(_) => break,
+                Err(e) if is_retryable(&e, self.deadline) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        // Returns the already filled buffer
+        self.inner.fill_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::ReadExt,
+        std::{io, time::Duration},
+    };
+
+    /// Returns `WouldBlock` after "ab" until `blocked` is decremented to 0
+    struct Blocking {
+        blocked: u32,
+        data: &'static [u8],
+    }
+
+    impl io::Read for Blocking {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.len() == 2 && self.blocked > 0 {
+                self.blocked -= 1;
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let len = buf.len().min(self.data.len()).min(2);
+            buf[0..len].copy_from_slice(&self.data[0..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn keeps_partial_line_after_timeout() {
+        let reader = Blocking {
+            blocked: 100,
+            data: b"abc\n",
+        };
+        let mut lines = reader
+            .lines_rc()
+            .with_read_timeout(Duration::from_millis(10));
+        assert!(matches!(lines.next(), Some(Err(crate::Error::TimedOut))));
+        lines = lines.with_read_timeout(Duration::from_secs(10));
+        assert_eq!("abc", *lines.next().unwrap().unwrap());
+    }
+
+    #[test]
+    fn waits_between_would_block_retries() {
+        struct NeverReady(std::rc::Rc<std::cell::Cell<u32>>);
+        impl io::Read for NeverReady {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                self.0.set(self.0.get() + 1);
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+        let reads = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut lines = NeverReady(reads.clone())
+            .lines_rc()
+            .with_read_timeout(Duration::from_millis(20));
+        assert!(matches!(lines.next(), Some(Err(crate::Error::TimedOut))));
+        assert!(reads.get() <= 21, "{} reads", reads.get());
+    }
+}
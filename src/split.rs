@@ -0,0 +1,97 @@
+use {
+    crate::{bound::RcLineIterator, lookup::first_line_from, source::BufReadSource, ReadExt},
+    std::{
+        io::{self, BufReader, Read, Seek, SeekFrom},
+        ops::Range,
+    },
+};
+
+/// Splits a seekable reader into at most `n` byte ranges of similar size, which start and end on line boundaries.
+/// Each range can be processed by an independent worker, e.g. with `SeekExt::lines_in_range()`.
+/// Fewer ranges are returned if there are not enough lines, but none of them is empty.
+///
+/// Line boundaries are found by reading at most 64kb chunks around each split point.
+///
+/// # Examples
+/// ```
+/// let mut reader = std::io::Cursor::new("first\nsecond\nthird\nfourth\n");
+/// let chunks = simple_lines::line_aligned_chunks(&mut reader, 2).unwrap();
+/// assert_eq!(vec![0..13, 13..26], chunks);
+/// ```
+pub fn line_aligned_chunks<R: Read + Seek>(
+    reader: &mut R,
+    n: usize,
+) -> io::Result<Vec<Range<u64>>> {
+    assert!(n > 0, "n must be greater than 0");
+    let len = reader.seek(SeekFrom::End(0))?;
+    let mut chunks = Vec::with_capacity(n);
+    let mut start = 0;
+    for i in 1..=n as u64 {
+        let end = if i == n as u64 {
+            len
+        } else {
+            let split = (len as u128 * i as u128 / n as u128) as u64;
+            first_line_from(reader, split.max(start), 64 * 1024)?.map_or(len, |(x, _)| x)
+        };
+        if end > start {
+            chunks.push(start..end);
+            start = end;
+        }
+    }
+    Ok(chunks)
+}
+
+/// Iterates the lines starting within `range`
+pub(crate) fn lines_in_range<R: Read + Seek>(
+    mut reader: R,
+    range: Range<u64>,
+    max_size: usize,
+) -> io::Result<RcLineIterator<BufReadSource<BufReader<io::Take<R>>>>> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let start = first_line_from(&mut reader, range.start, max_size)?.map_or(len, |(x, _)| x);
+    let end = first_line_from(&mut reader, range.end.max(start), max_size)?.map_or(len, |(x, _)| x);
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(ReadExt::lines_rc_with_capacity(
+        reader.take(end - start),
+        max_size,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_cover_all_lines_once() {
+        let text = (0..1000).map(|x| format!("{}\n", x)).collect::<String>();
+        let mut reader = std::io::Cursor::new(text.as_bytes());
+        let chunks = line_aligned_chunks(&mut reader, 7).unwrap();
+        assert_eq!(7, chunks.len());
+        let joined = chunks
+            .iter()
+            .map(|x| &text[x.start as usize..x.end as usize])
+            .inspect(|x| assert!(x.ends_with('\n')))
+            .collect::<String>();
+        assert_eq!(text, joined);
+        let few = line_aligned_chunks(&mut std::io::Cursor::new("a\nb"), 5).unwrap();
+        assert_eq!(vec![0..2, 2..3], few);
+    }
+
+    #[test]
+    fn ranges_own_lines_starting_in_them() {
+        use crate::SeekExt;
+
+        let text = "first\nsecond\nthird\n";
+        let lines = |range: Range<u64>| {
+            std::io::Cursor::new(text)
+                .lines_in_range(range)
+                .unwrap()
+                .map(|x| x.unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["first", "second"], lines(0..7));
+        assert_eq!(vec!["third"], lines(7..14));
+        assert_eq!(vec!["second"], lines(1..7));
+        assert!(lines(14..20).is_empty());
+    }
+}
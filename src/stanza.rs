@@ -0,0 +1,182 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Groups `Key: Value` fields into stanzas separated by blank lines, like in Debian control files, apt indexes or RFC 822 style headers.
+    /// Lines starting with a space or tab continue the value of the previous field. They are appended
+    /// with a `\n` and without their leading whitespace. Lines starting with `#` are skipped.
+    ///
+    /// Stanzas larger than `max_stanza_size` bytes or containing invalid lines are rejected,
+    /// and the iteration continues after the next blank line.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let control = "Package: a\nDescription: short\n long\n\nPackage: b\n";
+    /// let stanzas = std::io::Cursor::new(control).lines_rc().stanzas(64 * 1024);
+    /// let stanzas = stanzas.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(Some("short\nlong"), stanzas[0].get("description"));
+    /// assert_eq!(Some("b"), stanzas[1].get("Package"));
+    /// ```
+    pub fn stanzas(self, max_stanza_size: usize) -> Stanzas<T> {
+        Stanzas {
+            lines: self,
+            max_stanza_size,
+            number: 0,
+        }
+    }
+}
+
+/// Fields of a stanza, created by `Stanzas`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stanza {
+    /// Keys and values in the order of the file
+    pub fields: Vec<(String, String)>,
+    /// Zero-based number of the first line
+    pub line: u64,
+}
+
+impl Stanza {
+    /// Returns the value of the first field named `key`, which is compared case-insensitively
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(x, _)| x.eq_ignore_ascii_case(key))
+            .map(|(_, x)| x.as_str())
+    }
+}
+
+/// Error of `Stanzas`. Line numbers are zero-based.
+#[derive(thiserror::Error, Debug)]
+pub enum StanzaError {
+    /// The line couldn't be read
+    #[error("{0}")]
+    Read(crate::Error<Rc<String>>),
+    /// The stanza starting in `line` is larger than allowed
+    #[error("stanza in line {line} too large")]
+    TooLarge {
+        /// Number of the first line of the stanza
+        line: u64,
+    },
+    /// A line doesn't contain `:`, or a continuation line doesn't follow a field
+    #[error("invalid field in line {line}")]
+    Invalid {
+        /// Number of the offending line
+        line: u64,
+    },
+}
+
+/// Iterator over blank line separated stanzas, created by `RcLineIterator::stanzas()`
+pub struct Stanzas<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_stanza_size: usize,
+    /// Number of the next line
+    number: u64,
+}
+
+impl<T: LineSource> Stanzas<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    fn read_stanza(&mut self) -> Option<Result<Stanza, StanzaError>> {
+        let mut stanza = Stanza {
+            fields: Vec::new(),
+            line: self.number,
+        };
+        let mut size = 0;
+        // Error of the current stanza, whose remaining lines are skipped
+        let mut rejected = None;
+        // Line which is joined from `Incomplete` parts
+        let mut line = String::new();
+        while let Some(part) = self.lines.next() {
+            let number = self.number;
+            let part = match part {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x,
+                Err(e) => return Some(Err(StanzaError::Read(e))),
+            };
+            size += part.len();
+            if size > self.max_stanza_size && rejected.is_none() {
+                rejected = Some(StanzaError::TooLarge { line: stanza.line });
+            }
+            if rejected.is_none() || line.is_empty() {
+                line.push_str(&part);
+            }
+            if self.lines.continues_line() {
+                continue;
+            }
+            self.number += 1;
+            let line = std::mem::take(&mut line);
+            if line.trim().is_empty() {
+                if let Some(e) = rejected {
+                    return Some(Err(e));
+                } else if !stanza.fields.is_empty() {
+                    return Some(Ok(stanza));
+                }
+                stanza.line = self.number;
+                size = 0;
+                continue;
+            }
+            if rejected.is_some() || line.starts_with('#') {
+                continue;
+            }
+            let valid = if line.starts_with([' ', '\t']) {
+                stanza.fields.last_mut().map(|(_, value)| {
+                    value.push('\n');
+                    value.push_str(line.trim());
+                })
+            } else {
+                line.split_once(':').map(|(key, value)| {
+                    let field = (key.trim().to_string(), value.trim().to_string());
+                    stanza.fields.push(field);
+                })
+            };
+            if valid.is_none() {
+                rejected = Some(StanzaError::Invalid { line: number });
+            }
+        }
+        match rejected {
+            Some(e) => Some(Err(e)),
+            None => Some(stanza).filter(|x| !x.fields.is_empty()).map(Ok),
+        }
+    }
+}
+
+impl<T: LineSource> Iterator for Stanzas<T> {
+    type Item = Result<Stanza, StanzaError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_stanza()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn skips_rejected_stanzas() {
+        let input = "\n\n a\nb: 1\n\nc: 22222222\n d\n\n#c\ne: 3\n";
+        let stanzas = std::io::Cursor::new(input)
+            .lines_rc_with_capacity(4)
+            .stanzas(10)
+            .map(|x| match x {
+                Ok(x) => Ok((x.line, x.fields)),
+                Err(StanzaError::Invalid { line }) => Err(("invalid", line)),
+                Err(StanzaError::TooLarge { line }) => Err(("large", line)),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Err(("invalid", 2)),
+                Err(("large", 5)),
+                Ok((8, vec![("e".to_string(), "3".to_string())]))
+            ],
+            stanzas
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::boxed::Box;
+
+/// Totals collected while iterating, returned by `RcLineIterator::stats()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Number of yielded items, including errors
+    pub lines: u64,
+    /// Number of bytes consumed from the reader, including linebreaks
+    pub bytes: u64,
+    /// Number of `Error::Io` items
+    pub io_errors: u64,
+    /// Number of `Error::Encoding` items. Lines skipped by `Iterator::nth()` aren't validated and therefore not counted.
+    pub encoding_errors: u64,
+    /// Number of `Error::Incomplete` items
+    pub incomplete_lines: u64,
+    /// Number of `Error::ForbiddenByte` items
+    pub forbidden_lines: u64,
+    /// Length of the longest line in bytes without its linebreak, even if it was split into multiple `Incomplete` parts
+    pub longest_line: usize,
+}
+
+/// Interval in which the callback of `RcLineIterator::on_progress()` is called
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressInterval {
+    /// Every n bytes consumed from the reader
+    Bytes(u64),
+    /// Every n yielded items
+    Lines(u64),
+}
+
+pub(crate) struct ProgressHook {
+    interval: ProgressInterval,
+    next_at: u64,
+    callback: Box<dyn FnMut(&Stats) + Send>,
+}
+
+impl ProgressHook {
+    pub fn new(interval: ProgressInterval, callback: Box<dyn FnMut(&Stats) + Send>) -> Self {
+        let (ProgressInterval::Bytes(step) | ProgressInterval::Lines(step)) = interval;
+        assert!(step > 0, "ProgressInterval must be greater than 0");
+        Self {
+            interval,
+            next_at: step,
+            callback,
+        }
+    }
+
+    pub fn report(&mut self, stats: &Stats) {
+        let (current, step) = match self.interval {
+            ProgressInterval::Bytes(step) => (stats.bytes, step),
+            ProgressInterval::Lines(step) => (stats.lines, step),
+        };
+        if current >= self.next_at {
+            (self.callback)(stats);
+            self.next_at = (current / step + 1) * step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt, std::sync::mpsc};
+
+    #[test]
+    fn reports_once_per_passed_interval() {
+        let (sender, receiver) = mpsc::channel();
+        let input = format!("{}\n{}\nab\n", "x".repeat(25), "y".repeat(3));
+        std::io::Cursor::new(input)
+            .lines_rc()
+            .on_progress(ProgressInterval::Bytes(10), move |stats| {
+                sender.send(stats.bytes).unwrap()
+            })
+            .for_each(drop);
+        assert_eq!(vec![26, 30], receiver.iter().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{bound::RcLineIterator, chunks::OwnedLine, source::LineSource},
+    futures::{channel::mpsc, SinkExt, Stream, StreamExt},
+    std::{
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+/// Number of lines read ahead of the consumer of a `LineStream`
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Locks stdin once and streams its lines with the default capacity of 64kb, like `stdin_lines()` for async code.
+///
+/// Stdin is read on a dedicated thread, as it can't be polled portably, so the stream works with any executor.
+/// The lines are sent over a bounded channel, so the thread waits while they aren't consumed.
+/// It ends after the last line, or after the next line once the stream is dropped.
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+///
+/// futures::executor::block_on(async {
+///     let mut lines = simple_lines::stdin_lines_async();
+///     while let Some(line) = lines.next().await {
+///         println!("{}", line.unwrap().to_uppercase());
+///     }
+/// });
+/// ```
+pub fn stdin_lines_async() -> LineStream {
+    stdin_lines_async_with_capacity(64 * 1024)
+}
+
+/// Locks stdin once and streams its lines, which are at most `max_line_length` bytes long, like `stdin_lines_async()`
+pub fn stdin_lines_async_with_capacity(max_line_length: usize) -> LineStream {
+    spawn_lines(move || crate::stdin_lines_with_capacity(max_line_length))
+}
+
+/// Creates the lines returned by `open` on a new thread and sends them to the returned stream
+fn spawn_lines<T: LineSource>(
+    open: impl FnOnce() -> RcLineIterator<T> + Send + 'static,
+) -> LineStream {
+    let (mut sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+    std::thread::spawn(move || {
+        let mut lines = open();
+        while let Some(line) = lines.next_owned() {
+            if futures::executor::block_on(sender.send(line)).is_err() {
+                break;
+            }
+        }
+    });
+    LineStream(receiver)
+}
+
+/// Stream of lines read on another thread, created by `stdin_lines_async()`
+pub struct LineStream(mpsc::Receiver<OwnedLine>);
+
+impl Stream for LineStream {
+    type Item = OwnedLine;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn streams_bounded_lines() {
+        let lines = spawn_lines(|| std::io::Cursor::new("a\nbcd\n").lines_rc_with_capacity(2));
+        let lines = futures::executor::block_on(lines.collect::<Vec<_>>());
+        assert!(matches!(
+            lines.as_slice(),
+            [Ok(a), Err(crate::Error::Incomplete(b)), Err(crate::Error::Incomplete(c))]
+                if a == "a" && b == "bc" && c == "d"
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::rc::Rc,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields the lines up to the first error, including `Incomplete`, which ends the iteration.
+    /// The error is kept for `StrictLines::take_error()`, so it can be checked after consuming the lines with a plain `for` loop or `collect()`.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut lines = std::io::Cursor::new(b"a\nb\n\xff\nc").lines_rc().strict();
+    /// let valid = lines.by_ref().map(|x| x.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["a", "b"], valid);
+    /// assert!(matches!(lines.take_error(), Some(simple_lines::Error::Encoding(_))));
+    /// assert!(lines.next().is_none());
+    /// ```
+    pub fn strict(self) -> StrictLines<T> {
+        StrictLines {
+            lines: self,
+            error: None,
+            finished: false,
+        }
+    }
+}
+
+/// Iterator over the lines up to the first error, created by `RcLineIterator::strict()`
+pub struct StrictLines<T: LineSource> {
+    lines: RcLineIterator<T>,
+    error: Option<crate::Error<Rc<String>>>,
+    finished: bool,
+}
+
+impl<T: LineSource> StrictLines<T> {
+    /// Returns the error which ended the iteration, if any. Subsequent calls return `None`.
+    pub fn take_error(&mut self) -> Option<crate::Error<Rc<String>>> {
+        self.error.take()
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for StrictLines<T> {
+    type Item = Rc<String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.lines.next() {
+            Some(Ok(x)) => Some(x),
+            Some(Err(e)) => {
+                self.error = Some(e);
+                self.finished = true;
+                None
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+impl<T: LineSource> std::iter::FusedIterator for StrictLines<T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn stops_at_incomplete() {
+        let mut lines = std::io::Cursor::new("ab\nabcdef\ng")
+            .lines_rc_with_capacity(4)
+            .strict();
+        assert_eq!(1, lines.by_ref().count());
+        assert!(matches!(lines.take_error(), Some(crate::Error::Incomplete(x)) if *x == "abcd"));
+        assert!(lines.take_error().is_none());
+        assert_eq!(2, lines.stats().lines);
+    }
+}
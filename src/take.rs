@@ -0,0 +1,146 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::BufReadSource,
+    },
+    std::io::BufRead,
+};
+
+/// Error of `into_inner()` while peeked or unread lines are pending, which would be lost as the reader is positioned after them.
+/// Consume them, e.g. with `next()`, and try again with the iterator from `IntoInnerError::into_lines()`.
+pub struct IntoInnerError<T>(Box<T>);
+
+impl<T> IntoInnerError<T> {
+    /// Returns the iterator, whose pending lines are still returned by `next()`
+    pub fn into_lines(self) -> T {
+        *self.0
+    }
+}
+
+impl<T> std::fmt::Debug for IntoInnerError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IntoInnerError")
+    }
+}
+
+impl<T> std::fmt::Display for IntoInnerError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("lines which were peeked or unread are pending")
+    }
+}
+
+impl<T> std::error::Error for IntoInnerError<T> {}
+
+impl<B: BufRead> RcLineIterator<BufReadSource<B>> {
+    /// Returns the reader positioned after the last returned line, e.g. to read a binary body after a line based header.
+    /// Bytes which were read ahead are kept in the buffer of the returned `BufRead`.
+    ///
+    /// The start of a line which was interrupted by an error is lost.
+    /// Fails while lines returned by `peek_line()` or passed to `unread()` are pending.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::BufReadExt, std::io::BufRead};
+    ///
+    /// let mut lines = "a\nb\n".as_bytes().lines_rc();
+    /// lines.peek_line();
+    /// let mut lines = lines.into_inner().unwrap_err().into_lines();
+    /// assert_eq!("a", *lines.next().unwrap().unwrap());
+    /// assert_eq!(b"b\n", lines.into_inner().unwrap().fill_buf().unwrap());
+    /// ```
+    pub fn into_inner(self) -> Result<B, IntoInnerError<Self>> {
+        if self.has_pushed_back() {
+            return Err(IntoInnerError(Box::new(self)));
+        }
+        Ok(self.into_source().into_inner())
+    }
+
+    /// Yields at most `n` items and returns the reader positioned after them with `TakeLines::into_inner()`.
+    /// Each error and `Incomplete` part counts as an item.
+    ///
+    /// # Examples
+    /// ```
+    /// use {simple_lines::ReadExt, std::io::Read};
+    ///
+    /// let mut header = std::io::Cursor::new(b"GET / HTTP/1.1\r\nHost: a\r\n\x00\x01\xff").lines_rc().take_lines(2);
+    /// assert_eq!("GET / HTTP/1.1", *header.next().unwrap().unwrap());
+    /// assert_eq!("Host: a", *header.next().unwrap().unwrap());
+    /// assert!(header.next().is_none());
+    ///
+    /// let mut body = Vec::new();
+    /// header.into_inner().unwrap().read_to_end(&mut body).unwrap();
+    /// assert_eq!(vec![0, 1, 255], body);
+    /// ```
+    pub fn take_lines(self, n: usize) -> TakeLines<B> {
+        TakeLines {
+            lines: self,
+            remaining: n,
+        }
+    }
+}
+
+/// Iterator over the first lines of a reader, created by `RcLineIterator::take_lines()`
+pub struct TakeLines<B: BufRead> {
+    lines: RcLineIterator<BufReadSource<B>>,
+    remaining: usize,
+}
+
+impl<B: BufRead> TakeLines<B> {
+    /// Returns the reader positioned after the returned lines, including the bytes it buffered beyond them.
+    /// Fails like `RcLineIterator::into_inner()` while peeked or unread lines are pending.
+    pub fn into_inner(self) -> Result<B, IntoInnerError<Self>> {
+        let remaining = self.remaining;
+        self.lines.into_inner().map_err(|e| {
+            IntoInnerError(Box::new(TakeLines {
+                lines: e.into_lines(),
+                remaining,
+            }))
+        })
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<B: BufRead> Iterator for TakeLines<B> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.lines.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::BufReadExt,
+        std::io::{BufRead, BufReader, Read},
+    };
+
+    #[test]
+    fn keeps_bytes_buffered_beyond_lines() {
+        let reader = BufReader::with_capacity(4, "ab\ncdefgh\nrest".as_bytes());
+        let mut lines = reader.lines_rc().take_lines(2);
+        assert_eq!(
+            vec!["ab", "cdefgh"],
+            lines
+                .by_ref()
+                .map(|x| x.unwrap().to_string())
+                .collect::<Vec<_>>()
+        );
+        let mut reader = lines.into_inner().unwrap();
+        assert_eq!(b"re", reader.fill_buf().unwrap());
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!("rest", rest);
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+        terminator::Terminator,
+    },
+    std::io::Write,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Writes each line to `writer` as it is yielded, e.g. to keep an audit log of the input of a streaming pipeline.
+    /// Lines are followed by their original linebreak, `\n`, `\r\n` or none for the last line, unless `Tee::with_terminator()` configures one.
+    /// `Incomplete` parts are written as they arrive, with the linebreak after the last part. Other errors aren't written.
+    ///
+    /// If writing fails, the line is replaced by `Error::Io`, which ends the iteration.
+    /// Writes aren't buffered, so wrap `writer` in a `BufWriter` if it is slow.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut audit = Vec::new();
+    /// let first = std::io::Cursor::new("a\r\nb\nc").lines_rc().tee(&mut audit).next();
+    /// assert_eq!("a", *first.unwrap().unwrap());
+    /// assert_eq!(b"a\r\n", &audit[..]);
+    ///
+    /// let mut audit = Vec::new();
+    /// let lines = std::io::Cursor::new("a\r\nb\nc").lines_rc().tee(&mut audit).with_terminator("\n");
+    /// assert_eq!(3, lines.count());
+    /// assert_eq!(b"a\nb\nc\n", &audit[..]);
+    /// ```
+    pub fn tee<W: Write>(self, writer: W) -> Tee<T, W> {
+        let consumed = self.stats().bytes;
+        Tee {
+            lines: self,
+            writer,
+            terminator: None,
+            consumed,
+            finished: false,
+        }
+    }
+}
+
+/// Iterator writing its lines to a writer, created by `RcLineIterator::tee()`
+pub struct Tee<T: LineSource, W: Write> {
+    lines: RcLineIterator<T>,
+    writer: W,
+    terminator: Option<String>,
+    /// Bytes of the lines which were written
+    consumed: u64,
+    finished: bool,
+}
+
+impl<T: LineSource, W: Write> Tee<T, W> {
+    /// Writes `terminator` after each line instead of its original linebreak.
+    /// Required to get linebreaks when the lines are trimmed, as the trimmed bytes can't be told apart from the linebreak.
+    pub fn with_terminator(mut self, terminator: &str) -> Self {
+        self.terminator = Some(terminator.into());
+        self
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Returns the writer, e.g. to flush it
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    fn write(&mut self, line: &str) -> std::io::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        let bytes = self.lines.stats().bytes;
+        let length = bytes - std::mem::replace(&mut self.consumed, bytes);
+        if self.lines.continues_line() {
+            return Ok(());
+        }
+        let terminator = match &self.terminator {
+            Some(x) => x.as_str(),
+            None => Terminator::from_len(length - line.len() as u64).as_str(),
+        };
+        self.writer.write_all(terminator.as_bytes())
+    }
+}
+
+impl<T: LineSource, W: Write> Iterator for Tee<T, W> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let line = self.lines.next()?;
+        let written = match &line {
+            Ok(x) | Err(crate::Error::Incomplete(x)) => self.write(&x.clone()),
+            Err(_) => {
+                self.consumed = self.lines.stats().bytes;
+                Ok(())
+            }
+        };
+        if let Err(e) = written {
+            self.finished = true;
+            return Some(Err(e.into()));
+        }
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn writes_incomplete_parts_with_one_linebreak() {
+        let mut audit = Vec::new();
+        let lines = std::io::Cursor::new(b"abcde\r\n\xff\nf")
+            .lines_rc_with_capacity(3)
+            .tee(&mut audit);
+        assert_eq!(5, lines.count());
+        assert_eq!(b"abcde\r\nf", &audit[..]);
+    }
+}
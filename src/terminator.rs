@@ -0,0 +1,124 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::io::{self, Write},
+};
+
+/// Linebreak which ended a line in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terminator {
+    /// Last line without linebreak, or an `Incomplete` part which is continued by the next one
+    None,
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl Terminator {
+    /// Returns the terminator which is `length` bytes long
+    pub(crate) fn from_len(length: u64) -> Self {
+        match length {
+            0 => Terminator::None,
+            1 => Terminator::Lf,
+            _ => Terminator::CrLf,
+        }
+    }
+
+    /// Returns the linebreak as text
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Terminator::None => "",
+            Terminator::Lf => "\n",
+            Terminator::CrLf => "\r\n",
+        }
+    }
+
+    /// Writes `line` followed by this terminator, which reproduces the original bytes of an unchanged line
+    pub fn write_line(&self, writer: &mut impl Write, line: &str) -> io::Result<()> {
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(self.as_str().as_bytes())
+    }
+}
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields each line together with the linebreak which ended it, so lines can be written back byte for byte with `Terminator::write_line()`.
+    /// This includes `\r\n` linebreaks, a missing linebreak after the last line and a byte order mark, which is kept as part of the first line.
+    ///
+    /// `Incomplete` parts have `Terminator::None`, except for the last one. Errors other than `Incomplete` have no content and are yielded with `Terminator::None`.
+    /// Lines trimmed with `with_trim()` can't be reproduced, as the trimmed bytes are lost.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let input = "\u{feff}header\r\nkeep\ndrop\r\nlast";
+    /// let mut output = Vec::new();
+    /// for (line, terminator) in std::io::Cursor::new(input).lines_rc().with_terminators() {
+    ///     let line = line.unwrap();
+    ///     if *line != "drop" {
+    ///         terminator.write_line(&mut output, &line).unwrap();
+    ///     }
+    /// }
+    /// assert_eq!("\u{feff}header\r\nkeep\nlast", std::str::from_utf8(&output).unwrap());
+    /// ```
+    pub fn with_terminators(self) -> TerminatedLines<T> {
+        let consumed = self.stats().bytes;
+        TerminatedLines {
+            lines: self,
+            consumed,
+        }
+    }
+}
+
+/// Iterator over lines and their linebreaks, created by `RcLineIterator::with_terminators()`
+pub struct TerminatedLines<T: LineSource> {
+    lines: RcLineIterator<T>,
+    /// Bytes of the lines which were yielded
+    consumed: u64,
+}
+
+impl<T: LineSource> TerminatedLines<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for TerminatedLines<T> {
+    type Item = (RcLine, Terminator);
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        let bytes = self.lines.stats().bytes;
+        let length = bytes - std::mem::replace(&mut self.consumed, bytes);
+        let terminator = match &line {
+            Ok(x) | Err(crate::Error::Incomplete(x)) if !self.lines.continues_line() => {
+                Terminator::from_len(length - x.len() as u64)
+            }
+            _ => Terminator::None,
+        };
+        Some((line, terminator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn reproduces_split_crlf() {
+        let input = "abcde\r\nf\n\n";
+        let mut output = Vec::new();
+        let lines = std::io::Cursor::new(input).lines_rc_with_capacity(3);
+        for (line, terminator) in lines.with_terminators() {
+            let line = match line {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x,
+                Err(e) => panic!("{}", e),
+            };
+            terminator.write_line(&mut output, &line).unwrap();
+        }
+        assert_eq!(input.as_bytes(), &output[..]);
+    }
+}
@@ -0,0 +1,54 @@
+/// Sides of a line which are trimmed by `RcLineIterator::with_trim()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trim {
+    /// Leading whitespace only
+    Start,
+    /// Trailing whitespace only
+    End,
+    /// Leading and trailing whitespace
+    Both,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Trimming {
+    pub trim: Trim,
+    /// Whether only ASCII whitespace is trimmed instead of all Unicode whitespace
+    pub ascii: bool,
+}
+
+impl Trimming {
+    /// Trims `line`, whose start is only trimmed if it is the first part of a line and whose end only if it is the last part
+    pub fn apply<'a>(&self, mut line: &'a str, is_first: bool, is_last: bool) -> &'a str {
+        if is_first && self.trim != Trim::End {
+            line = match self.ascii {
+                true => line.trim_ascii_start(),
+                false => line.trim_start(),
+            };
+        }
+        if is_last && self.trim != Trim::Start {
+            line = match self.ascii {
+                true => line.trim_ascii_end(),
+                false => line.trim_end(),
+            };
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ReadExt};
+
+    #[test]
+    fn trims_only_outer_sides_of_incomplete_parts() {
+        let lines = std::io::Cursor::new(" ab cd \n\u{a0}\n")
+            .lines_rc_with_capacity(3)
+            .with_trim_ascii(Trim::Both)
+            .map(|x| match x {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => x.to_string(),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["ab", " cd", "", "\u{a0}"], lines);
+    }
+}
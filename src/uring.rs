@@ -0,0 +1,204 @@
+use {
+    io_uring::{opcode, types, IoUring},
+    std::{
+        fs::File,
+        io::{self, BufRead, Read},
+        os::unix::io::AsRawFd,
+    },
+};
+
+/// Default size of each of the two buffers of `UringReader`
+const DEFAULT_BLOCK_SIZE: usize = 256 * 1024;
+
+/// Buffered reader of a file, which refills its buffer with io_uring instead of a `read()` syscall per refill.
+/// Two buffers are registered with the ring, so the next block is read by the kernel while the current one is consumed.
+/// Meant for large files read sequentially from the start, e.g. on NVMe drives where the syscalls dominate.
+///
+/// It implements `BufRead`, so `BufReadExt::lines_rc()` reads lines directly from the registered buffers.
+/// The file is read from offset 0 regardless of its current position.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{BufReadExt, UringReader};
+///
+/// let path = std::env::temp_dir().join(format!("simple_lines_uring_doc_{}", std::process::id()));
+/// std::fs::write(&path, "first\nsecond\n").unwrap();
+///
+/// // Kernels without io_uring, or with it disabled, fail here and the file can be read as usual instead
+/// if let Ok(reader) = UringReader::new(std::fs::File::open(&path).unwrap()) {
+///     assert_eq!(2, reader.lines_rc().count());
+/// }
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct UringReader {
+    ring: IoUring,
+    file: File,
+    buffers: Vec<Box<[u8]>>,
+    /// Buffer whose bytes are returned by `fill_buf()`
+    current: usize,
+    /// Range of unconsumed bytes in the current buffer
+    position: usize,
+    filled: usize,
+    /// Offset in the file of the next read which isn't submitted yet
+    offset: u64,
+    /// Whether a read into the other buffer is submitted and not completed yet
+    in_flight: bool,
+    eof: bool,
+}
+
+impl UringReader {
+    /// Reads `file` in blocks of 256kb. Fails if io_uring is not supported or disabled.
+    pub fn new(file: File) -> io::Result<Self> {
+        Self::with_block_size(file, DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Reads `file` in blocks of `block_size` bytes, which are kept in two buffers
+    pub fn with_block_size(file: File, block_size: usize) -> io::Result<Self> {
+        assert!(
+            block_size > 0 && block_size <= u32::MAX as usize,
+            "block_size must be between 1 and u32::MAX"
+        );
+        let ring = IoUring::new(2)?;
+        let mut buffers = vec![vec![0; block_size].into_boxed_slice(); 2];
+        let iovecs = buffers
+            .iter_mut()
+            .map(|x| libc::iovec {
+                iov_base: x.as_mut_ptr().cast(),
+                iov_len: x.len(),
+            })
+            .collect::<Vec<_>>();
+        // SAFETY: The buffers are neither moved nor freed before the ring, see `Drop`
+        unsafe { ring.submitter().register_buffers(&iovecs)? };
+        Ok(Self {
+            ring,
+            file,
+            buffers,
+            current: 0,
+            position: 0,
+            filled: 0,
+            offset: 0,
+            in_flight: false,
+            eof: false,
+        })
+    }
+
+    /// Submits a read of the next block into the buffer which isn't current
+    fn submit(&mut self) -> io::Result<()> {
+        let index = 1 - self.current;
+        let buffer = &mut self.buffers[index];
+        let entry = opcode::ReadFixed::new(
+            types::Fd(self.file.as_raw_fd()),
+            buffer.as_mut_ptr(),
+            buffer.len() as u32,
+            index as u16,
+        )
+        .offset(self.offset)
+        .build();
+        // SAFETY: The buffer is registered and not accessed until the read is completed
+        unsafe {
+            self.ring
+                .submission()
+                .push(&entry)
+                .map_err(|_| io::Error::other("io_uring submission queue is full"))?;
+        }
+        self.ring.submit()?;
+        self.in_flight = true;
+        Ok(())
+    }
+
+    /// Waits for the submitted read and returns the number of bytes read. `in_flight` is only reset once the read completed.
+    fn complete(&mut self) -> io::Result<usize> {
+        let result = loop {
+            if let Some(entry) = self.ring.completion().next() {
+                break entry.result();
+            }
+            match self.ring.submit_and_wait(1) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                x => {
+                    x?;
+                }
+            }
+        };
+        self.in_flight = false;
+        match result {
+            x if x >= 0 => Ok(x as usize),
+            x => Err(io::Error::from_raw_os_error(-x)),
+        }
+    }
+
+    /// Waits for a read in flight, ignoring its result
+    fn wait(&mut self) {
+        while self.in_flight {
+            if self.complete().is_err() && self.in_flight {
+                // Waiting on the ring failed, so leak the buffers instead of freeing them while the kernel may write into them
+                std::mem::forget(std::mem::take(&mut self.buffers));
+                break;
+            }
+        }
+    }
+}
+
+impl BufRead for UringReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.filled && !self.eof {
+            if !self.in_flight {
+                self.submit()?;
+            }
+            let n = self.complete()?;
+            self.current = 1 - self.current;
+            self.position = 0;
+            self.filled = n;
+            self.offset += n as u64;
+            if n == 0 {
+                self.eof = true;
+            } else {
+                // Read ahead while the current buffer is consumed. If it fails, the next refill submits it again and reports the error.
+                let _ = self.submit();
+            }
+        }
+        Ok(&self.buffers[self.current][self.position..self.filled])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.filled);
+    }
+}
+
+impl Read for UringReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl Drop for UringReader {
+    fn drop(&mut self) {
+        self.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::BufReadExt};
+
+    #[test]
+    fn reads_lines_across_blocks() {
+        let path = std::env::temp_dir().join(format!("simple_lines_uring_{}", std::process::id()));
+        let content = (0..1000).map(|x| format!("{}\n", x)).collect::<String>();
+        std::fs::write(&path, &content).unwrap();
+        let reader = UringReader::with_block_size(File::open(&path).unwrap(), 7).unwrap();
+        let lines = reader
+            .lines_rc()
+            .map(|x| x.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(content.lines().collect::<Vec<_>>(), lines);
+
+        let mut reader = UringReader::with_block_size(File::open(&path).unwrap(), 7).unwrap();
+        reader.fill_buf().unwrap();
+        drop(reader);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,57 @@
+use core::str::Utf8Error;
+
+/// Validates UTF8 with `simdutf8`, which is much faster on long lines.
+/// Only invalid input is validated a second time by the std implementation to get a detailed `Utf8Error`.
+#[cfg(feature = "simd")]
+pub fn from_utf8(input: &[u8]) -> Result<&str, Utf8Error> {
+    simdutf8::basic::from_utf8(input).or_else(|_| core::str::from_utf8(input))
+}
+
+#[cfg(not(feature = "simd"))]
+pub fn from_utf8(input: &[u8]) -> Result<&str, Utf8Error> {
+    core::str::from_utf8(input)
+}
+
+/// Guesses whether `sample` is the start of binary content like grep does:
+/// It contains NUL or more than 10% of its bytes are invalid UTF8.
+/// A sequence which is cut at the end of `sample` isn't counted.
+pub fn looks_binary(sample: &[u8]) -> bool {
+    if memchr::memchr(0, sample).is_some() {
+        return true;
+    }
+    let mut invalid = 0;
+    let mut rest = sample;
+    while let Err(e) = core::str::from_utf8(rest) {
+        match e.error_len() {
+            Some(len) => {
+                invalid += len;
+                rest = &rest[e.valid_up_to() + len..];
+            }
+            None => break,
+        }
+    }
+    invalid * 10 > sample.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_detailed_error() {
+        let error = from_utf8(&[b'a', b'b', 0xe2, 0x82, b'c']).unwrap_err();
+        assert_eq!(2, error.valid_up_to());
+        assert_eq!(Some(2), error.error_len());
+        assert_eq!(Ok("abc"), from_utf8(b"abc"));
+    }
+
+    #[test]
+    fn detects_binary() {
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(looks_binary(&[
+            0xff, 0xd8, 0xff, 0xe0, b'J', b'F', b'I', b'F'
+        ]));
+        assert!(!looks_binary("Grüezi\nmitenand\n".as_bytes()));
+        assert!(!looks_binary(&"Grüezi".as_bytes()[0..3]));
+    }
+}
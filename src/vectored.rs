@@ -0,0 +1,136 @@
+use std::io::{self, BufRead, IoSliceMut, Read};
+
+/// `BufRead` which refills multiple segments with a single `Read::read_vectored()` call, e.g. to read a fast TCP stream with fewer syscalls
+/// without allocating one large contiguous buffer.
+///
+/// Use it with `BufReadExt::lines_rc_with_capacity()`. Lines spanning segments are joined by the line iterator like lines spanning reads,
+/// so they are bounded by its capacity, not by the segment size.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{BufReadExt, VectoredBufReader};
+///
+/// let reader = VectoredBufReader::new(std::io::Cursor::new("first\nsecond\n"), 4, 3);
+/// let lines = reader.lines_rc_with_capacity(16).map(|x| x.unwrap().to_string());
+/// assert_eq!(vec!["first", "second"], lines.collect::<Vec<_>>());
+/// ```
+pub struct VectoredBufReader<R> {
+    inner: R,
+    segments: Vec<Box<[u8]>>,
+    /// Number of bytes read into each segment by the last refill
+    filled: Vec<usize>,
+    /// Segment which is currently consumed
+    current: usize,
+    /// Consumed bytes of the current segment
+    position: usize,
+}
+
+impl<R: Read> VectoredBufReader<R> {
+    /// Creates a reader with `segments` buffers of `segment_size` bytes each
+    pub fn new(inner: R, segments: usize, segment_size: usize) -> Self {
+        assert!(
+            segments > 0 && segment_size > 0,
+            "segments and segment_size must be greater than 0"
+        );
+        Self {
+            inner,
+            segments: (0..segments)
+                .map(|_| vec![0; segment_size].into_boxed_slice())
+                .collect(),
+            filled: vec![0; segments],
+            current: 0,
+            position: 0,
+        }
+    }
+
+    /// Returns the underlying reader. Buffered bytes are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads into all segments at once, after they were consumed
+    fn refill(&mut self) -> io::Result<()> {
+        let mut slices = self
+            .segments
+            .iter_mut()
+            .map(|x| IoSliceMut::new(x))
+            .collect::<Vec<_>>();
+        let mut remaining = self.inner.read_vectored(&mut slices)?;
+        for (filled, segment) in self.filled.iter_mut().zip(&self.segments) {
+            *filled = remaining.min(segment.len());
+            remaining -= *filled;
+        }
+        self.current = 0;
+        self.position = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for VectoredBufReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: Read> BufRead for VectoredBufReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position == self.filled[self.current] {
+            let next = self.current + 1;
+            if next < self.segments.len() && self.filled[next] > 0 {
+                self.current = next;
+                self.position = 0;
+            } else {
+                self.refill()?;
+                if self.filled[0] == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(&self.segments[self.current][self.position..self.filled[self.current]])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.filled[self.current]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::BufReadExt};
+
+    /// Counts the calls to `read_vectored()`
+    struct Counting(io::Cursor<Vec<u8>>, usize);
+
+    impl Read for Counting {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            unreachable!("only read_vectored() is used")
+        }
+
+        fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+            self.1 += 1;
+            self.0.read_vectored(bufs)
+        }
+    }
+
+    #[test]
+    fn reads_all_segments_at_once() {
+        let input = "a\n1234567890\nbc\n".as_bytes().to_vec();
+        let mut lines = VectoredBufReader::new(Counting(io::Cursor::new(input), 0), 4, 4)
+            .lines_rc_with_capacity(8);
+        let read = lines
+            .by_ref()
+            .map(|x| match x {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::Incomplete(x)) => format!("{}..", x),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["a", "12345678..", "90..", "bc"], read);
+        let reader = lines.into_source().into_inner().into_inner();
+        assert_eq!(2, reader.1);
+    }
+}
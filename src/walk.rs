@@ -0,0 +1,262 @@
+use {
+    crate::{
+        paths::{OpenFile, PathLine},
+        ReadExt,
+    },
+    std::{
+        fs::File,
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Walks the directory tree below `root` in the order of the file names and iterates the lines of all text files,
+/// tagged with their path and zero-based line number like in `lines_from_paths()`. Symlinks aren't followed.
+///
+/// Binary files are skipped silently, as detected by `RcLineIterator::with_binary_detection()`.
+/// Directories and files which can't be read yield an `Io` error, after which the walk continues.
+///
+/// # Examples
+/// ```
+/// let dir = std::env::temp_dir().join(format!("simple_lines_walk_doc_{}", std::process::id()));
+/// std::fs::create_dir_all(dir.join("sub")).unwrap();
+/// std::fs::write(dir.join("a.txt"), "a\n").unwrap();
+/// std::fs::write(dir.join("sub").join("b.txt"), "b1\nb2\n").unwrap();
+/// std::fs::write(dir.join("c.bin"), b"\0\x01\x02").unwrap();
+///
+/// let lines = simple_lines::walk_lines(&dir)
+///     .map(|x| format!("{}:{}", x.path.file_name().unwrap().to_str().unwrap(), x.line.unwrap()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(vec!["a.txt:a", "b.txt:b1", "b.txt:b2"], lines);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn walk_lines(root: impl AsRef<Path>) -> WalkLines {
+    let root = root.as_ref().to_path_buf();
+    WalkLines {
+        entries: None,
+        root,
+        current: None,
+        ignore_files: false,
+        global_limit: false,
+        max_line_length: 64 * 1024,
+        max_file_bytes: u64::MAX,
+        max_total_bytes: u64::MAX,
+        consumed: 0,
+        finished: false,
+    }
+}
+
+/// Iterator over the lines of all text files below a directory, created by `walk_lines()`
+pub struct WalkLines {
+    /// Created on the first call of `next()`, so the options apply
+    entries: Option<ignore::Walk>,
+    root: PathBuf,
+    current: Option<OpenFile>,
+    ignore_files: bool,
+    /// Whether the quota of the current file is the rest of `max_total_bytes`
+    global_limit: bool,
+    max_line_length: usize,
+    max_file_bytes: u64,
+    max_total_bytes: u64,
+    /// Bytes read from all closed files
+    consumed: u64,
+    finished: bool,
+}
+
+impl WalkLines {
+    /// Lines longer than `max_line_length` are split into `Incomplete` parts. Defaults to 64kb.
+    pub fn with_capacity(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Stops reading a file after `max_file_bytes` with `Error::QuotaExceeded`, after which the walk continues with the next file.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Ends the walk with `Error::QuotaExceeded` once `max_total_bytes` were read from all files together.
+    pub fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Skips hidden files and directories, as well as those matched by `.gitignore`, `.ignore` and git exclude files,
+    /// so the walk finds the same files as ripgrep would. Ignore files of the directories above `root` apply too,
+    /// even outside of a git repository.
+    ///
+    /// # Examples
+    /// ```
+    /// let dir = std::env::temp_dir().join(format!("simple_lines_ignore_doc_{}", std::process::id()));
+    /// std::fs::create_dir_all(dir.join("target")).unwrap();
+    /// std::fs::write(dir.join(".gitignore"), "/target/\n*.log\n").unwrap();
+    /// std::fs::write(dir.join("target").join("a.txt"), "a\n").unwrap();
+    /// std::fs::write(dir.join("b.log"), "b\n").unwrap();
+    /// std::fs::write(dir.join("c.txt"), "c\n").unwrap();
+    ///
+    /// let lines = simple_lines::walk_lines(&dir).with_ignore_files().map(|x| x.line.unwrap().to_string());
+    /// assert_eq!(vec!["c"], lines.collect::<Vec<_>>());
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn with_ignore_files(mut self) -> Self {
+        self.ignore_files = true;
+        self
+    }
+
+    /// Opens the next file, or returns the error of an entry which can't be read
+    fn open_next(&mut self) -> Option<Result<(), PathLine>> {
+        let error = |path: PathBuf, e: io::Error| PathLine {
+            path: path.into(),
+            number: 0,
+            line: Err(e.into()),
+        };
+        let (root, ignore_files) = (&self.root, self.ignore_files);
+        let entries = self.entries.get_or_insert_with(|| {
+            ignore::WalkBuilder::new(root)
+                .standard_filters(ignore_files)
+                .require_git(false)
+                .sort_by_file_name(|a, b| a.cmp(b))
+                .build()
+        });
+        let entry = match entries.next()? {
+            Ok(x) => x,
+            Err(e) => {
+                let path = error_path(&e).unwrap_or(&self.root).to_path_buf();
+                let kind = e.io_error().map_or(io::ErrorKind::Other, io::Error::kind);
+                return Some(Err(error(path, io::Error::new(kind, e))));
+            }
+        };
+        if !entry.file_type().is_some_and(|x| x.is_file()) {
+            return Some(Ok(()));
+        }
+        let path = entry.into_path();
+        let file = match File::open(&path) {
+            Ok(x) => x,
+            Err(e) => return Some(Err(error(path, e))),
+        };
+        let remaining = self.max_total_bytes - self.consumed;
+        self.global_limit = remaining <= self.max_file_bytes;
+        let lines = file
+            .lines_rc_with_capacity(self.max_line_length)
+            .with_binary_detection()
+            .with_max_total_bytes(remaining.min(self.max_file_bytes));
+        self.current = Some(OpenFile::new(path, lines));
+        Some(Ok(()))
+    }
+
+    fn close(&mut self) {
+        if let Some(file) = self.current.take() {
+            self.consumed += file
+                .lines
+                .stats()
+                .bytes
+                .min(self.max_total_bytes - self.consumed);
+        }
+    }
+}
+
+/// Returns the path of the entry which caused `error`, if it is known
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
+}
+
+impl Iterator for WalkLines {
+    type Item = PathLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let Some(file) = self.current.as_mut() else {
+                match self.open_next()? {
+                    Ok(()) => continue,
+                    Err(e) => return Some(e),
+                }
+            };
+            let Some(line) = file.next_line() else {
+                self.close();
+                continue;
+            };
+            match line.line {
+                Err(crate::Error::BinaryContent) => {
+                    self.close();
+                    continue;
+                }
+                Err(crate::Error::QuotaExceeded) if self.global_limit => self.finished = true,
+                Err(crate::Error::QuotaExceeded | crate::Error::Io(_)) => self.close(),
+                _ => {}
+            }
+            return Some(line);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enforces_file_and_total_quotas() {
+        let dir = std::env::temp_dir().join(format!("simple_lines_walk_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a", "b", "c"] {
+            std::fs::write(dir.join(name), "12\n34\n56\n").unwrap();
+        }
+        let lines = |walk: WalkLines| {
+            walk.map(|x| match x.line {
+                Ok(x) => x.to_string(),
+                Err(crate::Error::QuotaExceeded) => "quota".into(),
+                Err(e) => panic!("{}", e),
+            })
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["12", "quota", "12", "quota", "12", "quota"],
+            lines(walk_lines(&dir).with_max_file_bytes(4))
+        );
+        assert_eq!(
+            vec!["12", "34", "56", "12", "34", "quota"],
+            lines(
+                walk_lines(&dir)
+                    .with_max_file_bytes(9)
+                    .with_max_total_bytes(15)
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn honors_nested_ignore_files_and_hidden_entries() {
+        let dir =
+            std::env::temp_dir().join(format!("simple_lines_walk_ignore_{}", std::process::id()));
+        for sub in [".git", ".hidden", "sub"] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        std::fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.join("sub").join(".ignore"), "!keep.log\n").unwrap();
+        for file in [
+            ".git/a",
+            ".hidden/b",
+            "c.log",
+            "d",
+            "sub/e.log",
+            "sub/keep.log",
+        ] {
+            std::fs::write(dir.join(file), "x\n").unwrap();
+        }
+        let names = |walk: WalkLines| {
+            walk.map(|x| x.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["d", "keep.log"],
+            names(walk_lines(&dir).with_ignore_files())
+        );
+        assert_eq!(8, names(walk_lines(&dir)).len());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
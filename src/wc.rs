@@ -0,0 +1,76 @@
+use {
+    crate::{bound::BorrowedLines, source::BufReadSource},
+    std::io::{self, BufReader, Read},
+};
+
+/// Totals of a reader like `wc` computes them, returned by `LineStats::compute()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineStats {
+    /// Number of lines, where a last line without linebreak is counted too
+    pub lines: u64,
+    /// Number of words separated by whitespace. Lines with invalid UTF8 aren't counted.
+    pub words: u64,
+    /// Number of bytes, including linebreaks
+    pub bytes: u64,
+    /// Length of the longest line in bytes without its linebreak
+    pub max_line_length: usize,
+    /// Number of lines with invalid UTF8
+    pub encoding_errors: u64,
+}
+
+impl LineStats {
+    /// Computes the totals in one pass, without allocating anything but the read buffer.
+    /// Lines longer than the 64kb buffer are counted once.
+    ///
+    /// # Examples
+    /// ```
+    /// let stats = simple_lines::LineStats::compute("hello world\r\n\nbye".as_bytes()).unwrap();
+    /// assert_eq!((3, 3, 17, 11), (stats.lines, stats.words, stats.bytes, stats.max_line_length));
+    /// ```
+    pub fn compute<R: Read>(reader: R) -> io::Result<Self> {
+        const MAX_SIZE: usize = 64 * 1024;
+        let source = BufReadSource::new(BufReader::with_capacity(MAX_SIZE, reader), MAX_SIZE);
+        let mut lines = BorrowedLines::new(source, MAX_SIZE);
+        let mut result = LineStats::default();
+        let mut continued = false;
+        let mut in_word = false;
+        while let Some(line) = lines.next_str() {
+            if !continued {
+                result.lines += 1;
+                in_word = false;
+            }
+            match line {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => {
+                    for c in x.chars() {
+                        let is_word = !c.is_whitespace();
+                        result.words += (is_word && !in_word) as u64;
+                        in_word = is_word;
+                    }
+                }
+                Err(crate::Error::Encoding(_)) if !continued => result.encoding_errors += 1,
+                Err(crate::Error::Io(e)) => return Err(e),
+                Err(_) => {}
+            }
+            continued = lines.continues_line();
+        }
+        let stats = lines.stats();
+        result.bytes = stats.bytes;
+        result.max_line_length = stats.longest_line;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_words_across_incomplete_parts() {
+        let input = "a ".repeat(40_000) + "\n" + &"b".repeat(70_000);
+        let stats = LineStats::compute(input.as_bytes()).unwrap();
+        assert_eq!(
+            (2, 40_001, 80_000),
+            (stats.lines, stats.words, stats.max_line_length)
+        );
+    }
+}
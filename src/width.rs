@@ -0,0 +1,129 @@
+use {
+    crate::{
+        bound::{RcLine, RcLineIterator},
+        source::LineSource,
+    },
+    std::rc::Rc,
+    unicode_segmentation::UnicodeSegmentation,
+    unicode_width::UnicodeWidthStr,
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Truncates lines wider than `max_width` terminal columns and marks them with `…`, e.g. for TUI log viewers which show one row per line.
+    /// Widths are measured with [unicode-width](https://crates.io/crates/unicode-width), so wide CJK characters and emoji count as two columns.
+    /// Lines are only cut between grapheme clusters as found by [unicode-segmentation](https://crates.io/crates/unicode-segmentation)
+    /// and the marker is included in `max_width`, so truncated lines don't end in broken accents, emoji, flags or Hangul syllables.
+    ///
+    /// `Incomplete` parts of lines longer than the capacity are joined until `max_width` is reached and the rest of the line is skipped,
+    /// so they are yielded as one truncated line. Control characters like tabs have no width and aren't expanded.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let lines = std::io::Cursor::new("short\n日本語のテキスト\nabcdefghij").lines_rc().truncate_width(6);
+    /// let lines = lines.map(|x| x.unwrap().to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["short", "日本…", "abcde…"], lines);
+    /// ```
+    pub fn truncate_width(self, max_width: usize) -> WidthTruncated<T> {
+        WidthTruncated {
+            lines: self,
+            max_width,
+            marker: "…".into(),
+        }
+    }
+}
+
+/// Iterator over lines which are truncated to a display width, created by `RcLineIterator::truncate_width()`
+pub struct WidthTruncated<T: LineSource> {
+    lines: RcLineIterator<T>,
+    max_width: usize,
+    marker: String,
+}
+
+impl<T: LineSource> WidthTruncated<T> {
+    /// Appended to truncated lines instead of `…`. Can be empty.
+    pub fn with_marker(mut self, marker: &str) -> Self {
+        self.marker = marker.into();
+        self
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+
+    /// Returns `line` cut to `max_width` including the marker, or `None` if it fits
+    fn truncate(&self, line: &str) -> Option<String> {
+        if line.width() <= self.max_width {
+            return None;
+        }
+        let budget = self.max_width.saturating_sub(self.marker.width());
+        let mut width = 0;
+        let end = line
+            .grapheme_indices(true)
+            .find(|(_, x)| {
+                width += x.width();
+                width > budget
+            })
+            .map_or(line.len(), |(i, _)| i);
+        Some(format!("{}{}", &line[..end], self.marker))
+    }
+}
+
+impl<T: LineSource> Iterator for WidthTruncated<T> {
+    type Item = RcLine;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut joined = match self.lines.next()? {
+            Ok(x) => return Some(Ok(self.truncate(&x).map_or(x, Rc::new))),
+            Err(crate::Error::Incomplete(x)) => x.to_string(),
+            Err(e) => return Some(Err(e)),
+        };
+        while self.lines.continues_line() {
+            match self.lines.next()? {
+                Ok(x) | Err(crate::Error::Incomplete(x)) => {
+                    // Once the line is too wide, the rest is skipped
+                    if joined.width() <= self.max_width {
+                        joined.push_str(&x);
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        Some(Ok(Rc::new(self.truncate(&joined).unwrap_or(joined))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn joins_incomplete_parts_up_to_the_width() {
+        let input = "😀abcdefghijklmnop\nq";
+        let lines = std::io::Cursor::new(input)
+            .lines_rc_with_capacity(4)
+            .truncate_width(5)
+            .with_marker(">")
+            .map(|x| x.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["😀ab>", "q"], lines);
+    }
+
+    #[test]
+    fn keeps_clusters_together() {
+        let input = "abe\u{301}\na👩\u{200d}💻bc";
+        let lines = std::io::Cursor::new(input)
+            .lines_rc()
+            .truncate_width(4)
+            .map(|x| x.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["abe\u{301}", "a👩\u{200d}💻…"], lines);
+        let lines = std::io::Cursor::new("ae\u{301}e\u{301}x\n🇨🇭🇨🇭")
+            .lines_rc()
+            .truncate_width(3)
+            .map(|x| x.unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["ae\u{301}…", "🇨🇭…"], lines);
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    crate::{bound::RcLineIterator, source::LineSource},
+    std::{collections::VecDeque, rc::Rc},
+};
+
+impl<T: LineSource> RcLineIterator<T> {
+    /// Yields overlapping windows of the last `size` lines, like `slice::windows()`, e.g. to look at neighbouring lines of a stack trace.
+    /// The lines are shared, so each window only clones `size` `Rc`s.
+    ///
+    /// Errors are yielded on their own and are not part of any window.
+    ///
+    /// # Examples
+    /// ```
+    /// use simple_lines::ReadExt;
+    ///
+    /// let mut windows = std::io::Cursor::new("a\nb\nc").lines_rc().windows(2);
+    /// assert_eq!(vec!["a", "b"], windows.next().unwrap().unwrap().iter().map(|x| x.as_str()).collect::<Vec<_>>());
+    /// assert_eq!(vec!["b", "c"], windows.next().unwrap().unwrap().iter().map(|x| x.as_str()).collect::<Vec<_>>());
+    /// assert!(windows.next().is_none());
+    /// ```
+    pub fn windows(self, size: usize) -> LineWindows<T> {
+        assert!(size > 0, "Window size must be greater than 0");
+        LineWindows {
+            lines: self,
+            ring: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+}
+
+/// Iterator over overlapping windows of lines, created by `RcLineIterator::windows()`
+pub struct LineWindows<TSource: LineSource> {
+    lines: RcLineIterator<TSource>,
+    ring: VecDeque<Rc<String>>,
+    size: usize,
+}
+
+impl<T: LineSource> LineWindows<T> {
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<T: LineSource> Iterator for LineWindows<T> {
+    type Item = Result<Vec<Rc<String>>, crate::Error<Rc<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(x) => x,
+                Err(e) => return Some(Err(e)),
+            };
+            if self.ring.len() == self.size {
+                self.ring.pop_front();
+            }
+            self.ring.push_back(line);
+            if self.ring.len() == self.size {
+                return Some(Ok(self.ring.iter().cloned().collect()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn errors_are_not_part_of_windows() {
+        let input = [b'a', b'\n', 254, b'\n', b'b', b'\n', b'c'];
+        let mut windows = std::io::Cursor::new(input).lines_rc().windows(2);
+        assert!(matches!(
+            windows.next(),
+            Some(Err(crate::Error::Encoding(_)))
+        ));
+        let mut next = || {
+            let window = windows.next()?.unwrap();
+            Some(window.iter().map(|x| x.to_string()).collect::<Vec<_>>())
+        };
+        assert_eq!(Some(vec!["a".into(), "b".into()]), next());
+        assert_eq!(Some(vec!["b".into(), "c".into()]), next());
+        assert_eq!(None, next());
+    }
+}
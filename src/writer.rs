@@ -0,0 +1,297 @@
+use std::io::{self, BufWriter, Write};
+
+/// Handling of line breaks within a line passed to `LineWriter::write_line()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newlines {
+    /// Fails with `WriteError::Newline`
+    Reject,
+    /// Writes each part as a separate line, where `\r\n`, `\n` and `\r` are breaks.
+    /// A single break at the end only ends the line, so it doesn't add an empty line.
+    Split,
+    /// Replaces line feeds, carriage returns and backslashes by the escape sequences `\n`, `\r` and `\\`, so the line can be restored after reading it
+    Escape,
+}
+
+/// Handling of lines longer than the maximum length of a `LineWriter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlong {
+    /// Fails with `WriteError::TooLong`
+    Reject,
+    /// Writes the line in parts of at most the maximum length, split at char boundaries and never within an escape sequence of `Newlines::Escape`
+    Split,
+}
+
+/// Error of `LineWriter`
+#[derive(thiserror::Error, Debug)]
+pub enum WriteError {
+    /// The underlying writer failed
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// The line is longer than allowed
+    #[error("line with {length} bytes is too long")]
+    TooLong {
+        /// Length of the line in bytes, after escaping
+        length: usize,
+    },
+    /// The line contains a line break
+    #[error("line contains a line break at byte {position}")]
+    Newline {
+        /// Byte offset of the line break
+        position: usize,
+    },
+}
+
+/// Writes lines followed by a terminator, enforcing the same bounds on output which `RcLineIterator` enforces on input.
+/// Lines are buffered and nothing is written for rejected lines.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{LineWriter, Newlines, WriteError};
+///
+/// let mut writer = LineWriter::new(Vec::new()).with_max_line_length(8);
+/// writer.write_line("first").unwrap();
+/// assert!(matches!(writer.write_line("too long line"), Err(WriteError::TooLong { length: 13 })));
+/// assert!(matches!(writer.write_line("a\nb"), Err(WriteError::Newline { position: 1 })));
+///
+/// let mut writer = writer.with_newlines(Newlines::Escape);
+/// writer.write_line("a\nb").unwrap();
+/// assert_eq!(b"first\na\\nb\n", writer.into_inner().unwrap().as_slice());
+/// ```
+pub struct LineWriter<W: Write> {
+    inner: BufWriter<W>,
+    encoder: LineEncoder,
+}
+
+impl<W: Write> LineWriter<W> {
+    /// Creates a writer terminating lines with `\n`, which rejects lines longer than 64kb and lines containing line breaks
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: BufWriter::with_capacity(64 * 1024, inner),
+            encoder: LineEncoder::new(),
+        }
+    }
+
+    /// Written after each line. Defaults to `\n`.
+    pub fn with_terminator(mut self, terminator: &str) -> Self {
+        self.encoder = self.encoder.with_terminator(terminator);
+        self
+    }
+
+    /// Maximum length of a line in bytes without the terminator. Defaults to 64kb, the default capacity of `ReadExt::lines_rc()`.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.encoder = self.encoder.with_max_line_length(max_line_length);
+        self
+    }
+
+    /// Handling of line breaks within lines. Defaults to `Newlines::Reject`.
+    pub fn with_newlines(mut self, newlines: Newlines) -> Self {
+        self.encoder = self.encoder.with_newlines(newlines);
+        self
+    }
+
+    /// Handling of lines longer than the maximum length. Defaults to `Overlong::Reject`.
+    pub fn with_overlong(mut self, overlong: Overlong) -> Self {
+        self.encoder = self.encoder.with_overlong(overlong);
+        self
+    }
+
+    /// Writes `line` followed by the terminator. Fails without writing anything if the line is rejected.
+    pub fn write_line(&mut self, line: &str) -> Result<(), WriteError> {
+        let inner = &mut self.inner;
+        self.encoder
+            .encode_parts(line, |x| inner.write_all(x.as_bytes()))
+    }
+
+    /// Writes the buffered lines to the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Flushes the buffered lines and returns the underlying writer
+    pub fn into_inner(self) -> io::Result<W> {
+        self.inner.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+/// Encodes lines with the bounds of `LineWriter` into a buffer, e.g. to write them to an async writer or a framed transport.
+/// Nothing is appended for rejected lines.
+///
+/// # Examples
+/// ```
+/// use simple_lines::{LineEncoder, Newlines};
+///
+/// let encoder = LineEncoder::new().with_terminator("\r\n").with_newlines(Newlines::Escape);
+/// let mut buffer = Vec::new();
+/// encoder.encode("a\nb", &mut buffer).unwrap();
+/// assert_eq!(b"a\\nb\r\n", buffer.as_slice());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineEncoder {
+    terminator: String,
+    max_line_length: usize,
+    newlines: Newlines,
+    overlong: Overlong,
+}
+
+impl Default for LineEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineEncoder {
+    /// Creates an encoder terminating lines with `\n`, which rejects lines longer than 64kb and lines containing line breaks
+    pub fn new() -> Self {
+        Self {
+            terminator: "\n".into(),
+            max_line_length: 64 * 1024,
+            newlines: Newlines::Reject,
+            overlong: Overlong::Reject,
+        }
+    }
+
+    /// Appended after each line. Defaults to `\n`.
+    pub fn with_terminator(mut self, terminator: &str) -> Self {
+        self.terminator = terminator.into();
+        self
+    }
+
+    /// Maximum length of a line in bytes without the terminator. Defaults to 64kb, the default capacity of `ReadExt::lines_rc()`.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        assert!(
+            max_line_length > 0,
+            "max_line_length must be greater than 0"
+        );
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Handling of line breaks within lines. Defaults to `Newlines::Reject`.
+    pub fn with_newlines(mut self, newlines: Newlines) -> Self {
+        self.newlines = newlines;
+        self
+    }
+
+    /// Handling of lines longer than the maximum length. Defaults to `Overlong::Reject`.
+    pub fn with_overlong(mut self, overlong: Overlong) -> Self {
+        self.overlong = overlong;
+        self
+    }
+
+    /// Appends `line` followed by the terminator to `buffer`. Fails without appending anything if the line is rejected.
+    pub fn encode(&self, line: &str, buffer: &mut Vec<u8>) -> Result<(), WriteError> {
+        self.encode_parts(line, |x| {
+            buffer.extend_from_slice(x.as_bytes());
+            Ok(())
+        })
+    }
+
+    /// Validates `line` and passes its parts and their terminators to `write`
+    fn encode_parts(
+        &self,
+        line: &str,
+        mut write: impl FnMut(&str) -> io::Result<()>,
+    ) -> Result<(), WriteError> {
+        let escaped;
+        let parts = match self.newlines {
+            Newlines::Reject => {
+                if let Some(position) = line.find(['\r', '\n']) {
+                    return Err(WriteError::Newline { position });
+                }
+                vec![line]
+            }
+            Newlines::Split => {
+                let line = line
+                    .strip_suffix("\r\n")
+                    .or_else(|| line.strip_suffix(['\r', '\n']))
+                    .unwrap_or(line);
+                line.split("\r\n")
+                    .flat_map(|x| x.split(['\r', '\n']))
+                    .collect()
+            }
+            Newlines::Escape => {
+                escaped = escape(line);
+                vec![escaped.as_str()]
+            }
+        };
+        if self.overlong == Overlong::Reject {
+            if let Some(part) = parts.iter().find(|x| x.len() > self.max_line_length) {
+                return Err(WriteError::TooLong { length: part.len() });
+            }
+        }
+        let escaped = self.newlines == Newlines::Escape;
+        for mut part in parts {
+            while part.len() > self.max_line_length {
+                let (head, tail) = part.split_at(split_point(part, self.max_line_length, escaped));
+                write(head)?;
+                write(&self.terminator)?;
+                part = tail;
+            }
+            write(part)?;
+            write(&self.terminator)?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\\' => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the end of the last char at or before `max`, but after the first char.
+/// If `escaped`, escape sequences starting with `\` count as one char.
+fn split_point(line: &str, max: usize, escaped: bool) -> usize {
+    let mut point = 0;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        let end = match c {
+            '\\' if escaped => chars.next().map_or(i + 1, |(j, x)| j + x.len_utf8()),
+            c => i + c.len_utf8(),
+        };
+        if end > max && point > 0 {
+            break;
+        }
+        point = end;
+        if end >= max {
+            break;
+        }
+    }
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_breaks_and_char_boundaries() {
+        let mut writer = LineWriter::new(Vec::new())
+            .with_terminator("\r\n")
+            .with_max_line_length(3)
+            .with_newlines(Newlines::Split)
+            .with_overlong(Overlong::Split);
+        writer.write_line("ab\r\nc\rdäe\n").unwrap();
+        let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!("ab\r\nc\r\ndä\r\ne\r\n", written);
+    }
+
+    #[test]
+    fn splits_escaped_lines_between_escape_sequences() {
+        let mut writer = LineWriter::new(Vec::new())
+            .with_max_line_length(3)
+            .with_newlines(Newlines::Escape)
+            .with_overlong(Overlong::Split);
+        writer.write_line("ab\ncd\\").unwrap();
+        let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!("ab\n\\nc\nd\\\\\n", written);
+    }
+}
@@ -0,0 +1,64 @@
+use {
+    crate::{bound::BorrowedLines, source::LineSource},
+    zeroize::Zeroizing,
+};
+
+/// Iterator over the lines of a reader as `zeroize::Zeroizing<String>`, created by `RcLineIterator::into_zeroizing()`.
+///
+/// Each line is copied into its own buffer, which is wiped as soon as it is dropped by the consumer.
+pub struct ZeroizingLineIterator<TSource: LineSource> {
+    lines: BorrowedLines<TSource>,
+}
+
+impl<T: LineSource> ZeroizingLineIterator<T> {
+    pub(crate) fn new(lines: BorrowedLines<T>) -> Self {
+        Self { lines }
+    }
+
+    /// Returns the totals of all lines consumed so far
+    pub fn stats(&self) -> crate::Stats {
+        self.lines.stats()
+    }
+}
+
+impl<TSource: LineSource> std::iter::FusedIterator for ZeroizingLineIterator<TSource> {}
+
+impl<TSource: LineSource> Iterator for ZeroizingLineIterator<TSource> {
+    type Item = Result<Zeroizing<String>, crate::Error<Zeroizing<String>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let to_owned = |x: &str| Zeroizing::new(x.to_string());
+        self.lines.next_str().map(|line| match line {
+            Ok(x) => Ok(to_owned(x)),
+            Err(e) => Err(e.map_incomplete(to_owned)),
+        })
+    }
+
+    /// Skips lines without validating or copying them
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if self.lines.skip(n) {
+            self.next()
+        } else {
+            None
+        }
+    }
+
+    /// Counts the remaining lines without validating or copying them
+    fn count(self) -> usize {
+        self.lines.count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReadExt;
+
+    #[test]
+    fn debug_hides_content() {
+        let mut lines = std::io::Cursor::new("secret\n123456789")
+            .lines_rc_with_capacity(8)
+            .into_zeroizing();
+        assert_eq!("secret", lines.next().unwrap().unwrap().as_str());
+        let incomplete = format!("{:?}", lines.next().unwrap());
+        assert!(!incomplete.contains("1234"), "{}", incomplete);
+    }
+}